@@ -0,0 +1,96 @@
+//! Diffie-Hellman key agreement in the prime order subgroups of `subgroup` and `ffdhe`. Each
+//! side picks a secret exponent `x` and publishes `g^x`, and both get `g^(xy)` from their own
+//! secret and the other's public key.
+//!
+//! The shared secret is a group element. Its `to_bytes` is big-endian and padded to the length
+//! of p, which is the encoding RFC 7919 asks for before it goes into a key derivation function.
+//!
+//! The secret key only goes through `SubgroupParams::exp_secret`, whose operations don't depend
+//! on the exponent, with the caveat on `PrimeField`.
+use field::Field;
+use num_traits::{One, Zero};
+use rand::Rng;
+use subgroup::SubgroupParams;
+
+///A random nonzero secret key and its public key.
+pub fn keypair<P: SubgroupParams, R: Rng>(rng: &mut R) -> (P::Scalar, P::Element) {
+    let mut secret = P::Scalar::random(rng);
+    while secret.is_zero() {
+        secret = P::Scalar::random(rng);
+    }
+    (secret, public_key::<P>(&secret))
+}
+
+///The public key `g^secret` of a secret key.
+pub fn public_key<P: SubgroupParams>(secret: &P::Scalar) -> P::Element {
+    P::exp_secret(&P::generator(), secret)
+}
+
+///The shared secret `their_public^secret`. None if `their_public` is 1 or isn't a member of the
+///subgroup, since raising anything outside it to a secret leaks the secret mod the cofactor.
+pub fn shared_secret<P: SubgroupParams>(
+    secret: &P::Scalar,
+    their_public: &P::Element,
+) -> Option<P::Element> {
+    if their_public.is_one() || !P::is_member(their_public) {
+        None
+    } else {
+        Some(P::exp_secret(their_public, secret))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffdhe::Ffdhe2048;
+    use field::PrimeField;
+    use fp_safe_256::FpSafe256;
+    use proptest::prelude::*;
+    use subgroup::SafePrime256;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn ffdhe2048_agreement() {
+        let mut rng = rng_from_seed(48);
+        let (a, a_public) = keypair::<Ffdhe2048, _>(&mut rng);
+        let (b, b_public) = keypair::<Ffdhe2048, _>(&mut rng);
+        let shared = shared_secret::<Ffdhe2048>(&a, &b_public).unwrap();
+        assert_eq!(shared_secret::<Ffdhe2048>(&b, &a_public), Some(shared));
+        assert_eq!(shared.to_bytes().len(), 256);
+        // p - 1 has order 2.
+        let minus_one = -<Ffdhe2048 as SubgroupParams>::Element::one();
+        assert_eq!(shared_secret::<Ffdhe2048>(&a, &minus_one), None);
+    }
+
+    #[test]
+    fn rejects_small_and_non_members() {
+        let secret = <SafePrime256 as SubgroupParams>::Scalar::from(12345u64);
+        for x in [
+            FpSafe256::zero(),
+            FpSafe256::one(),
+            -FpSafe256::one(),
+            // The negation of a square is a non-square, since -1 isn't a square mod
+            // a safe prime.
+            -FpSafe256::from(4u64),
+        ]
+        .iter()
+        {
+            assert_eq!(shared_secret::<SafePrime256>(&secret, x), None);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn both_sides_agree(seed in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            let (a, a_public) = keypair::<SafePrime256, _>(&mut rng);
+            let (b, b_public) = keypair::<SafePrime256, _>(&mut rng);
+            prop_assert!(SafePrime256::is_member(&a_public));
+            let shared = shared_secret::<SafePrime256>(&a, &b_public);
+            prop_assert!(shared.is_some());
+            prop_assert_eq!(shared, shared_secret::<SafePrime256>(&b, &a_public));
+            prop_assert_eq!(shared, Some(public_key::<SafePrime256>(&(a * b))));
+        }
+    }
+}
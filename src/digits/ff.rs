@@ -34,6 +34,7 @@ macro_rules! from_signed { ($classname: ident; $($T:ty),*) => { $(
 macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $prime: expr, $barrettmu: expr) => { pub mod $modname {
     use digits::util::*;
     use digits::signed::*;
+    use digits::naf::{Jsf, Wnaf};
     use std::cmp::Ordering;
     use std::fmt;
     use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign, BitAnd, BitAndAssign};
@@ -41,12 +42,14 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
     use std::convert::From;
     use std::option::Option;
     use std::ops::Deref;
+    use field::{Field, PrimeField};
+    use rand::Rng;
 
     pub const LIMBSIZEBYTES: usize = 8;
     pub const LIMBSIZEBITS: usize = 64;
     pub const PRIME: [u64; NUMLIMBS] = $prime;
     pub const PRIMEBITS: usize = $bits;
-    pub const NUMBYTES: usize = PRIMEBITS / LIMBSIZEBYTES;
+    pub const NUMBYTES: usize = PRIMEBITS.div_ceil(BITSPERBYTE);
     pub const NUMLIMBS: usize = $limbs;
     pub const NUMDOUBLELIMBS: usize = $limbs * 2;
     pub const BARRETTMU: [u64; NUMLIMBS + 1] = $barrettmu;
@@ -327,6 +330,107 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
         }
     }
 
+    impl Field for $classname {
+        #[inline]
+        fn square(&self) -> $classname {
+            $classname::square(self)
+        }
+
+        /// Rejection sample limbs masked down to the size of the prime.
+        fn random<R: Rng>(rng: &mut R) -> $classname {
+            let top_bits = PRIMEBITS - LIMBSIZEBITS * (NUMLIMBS - 1);
+            let mask = if top_bits == LIMBSIZEBITS { !0u64 } else { (1u64 << top_bits) - 1 };
+            loop {
+                let mut limbs = [0u64; NUMLIMBS];
+                for limb in limbs.iter_mut() {
+                    *limb = rng.gen();
+                }
+                limbs[NUMLIMBS - 1] &= mask;
+                if limbs.less(&PRIME) {
+                    return $classname { limbs };
+                }
+            }
+        }
+    }
+
+    impl PrimeField for $classname {
+        const PRIME: &'static [u64] = &PRIME;
+        const PRIMEBITS: usize = PRIMEBITS;
+
+        fn to_limbs(&self) -> Vec<u64> {
+            self.limbs.to_vec()
+        }
+
+        #[inline]
+        fn sqrt(&self) -> Option<$classname> {
+            $classname::sqrt(self)
+        }
+
+        #[inline]
+        fn create_naf(&self) -> Vec<i8> {
+            $classname::create_naf(self)
+        }
+
+        #[inline]
+        fn create_wnaf(&self, w: usize) -> Vec<i8> {
+            $classname::create_wnaf(self, w)
+        }
+
+        #[inline]
+        fn create_jsf(&self, other: &$classname) -> Vec<(i8, i8)> {
+            $classname::create_jsf(self, other)
+        }
+
+        fn conditional_select(a: &$classname, b: &$classname, choice: bool) -> $classname {
+            let mask = 0u64.wrapping_sub(choice as u64);
+            let mut limbs = [0u64; NUMLIMBS];
+            for (limb, (x, y)) in limbs.iter_mut().zip(a.limbs.iter().zip(b.limbs.iter())) {
+                *limb = x ^ (mask & (x ^ y));
+            }
+            $classname { limbs }
+        }
+
+        fn to_bytes(&self) -> Vec<u8> {
+            self.to_bytes_array().to_vec()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Option<$classname> {
+            if bytes.len() != NUMBYTES {
+                return None;
+            }
+            let mut array = [0u8; NUMBYTES];
+            array.copy_from_slice(bytes);
+            let value = $classname::from(array);
+            // From reduces mod p, so a value that doesn't round trip wasn't canonical.
+            if value.to_bytes_array()[..] == bytes[..] {
+                Some(value)
+            } else {
+                None
+            }
+        }
+
+        ///Barrett reduction rather than the default byte at a time, so anything up to twice the
+        ///limbs of the prime, like the output of `hash_to_field`, takes one reduction.
+        fn from_bytes_reduced(bytes: &[u8]) -> $classname {
+            let limbs: Vec<u64> = bytes
+                .rchunks(LIMBSIZEBYTES)
+                .map(|chunk| chunk.iter().fold(0u64, |acc, b| (acc << BITSPERBYTE) | u64::from(*b)))
+                .collect();
+            // The top 2 NUMLIMBS limbs at most go in the first reduction, and each reduction after
+            // that shifts the result up NUMLIMBS limbs and adds the next NUMLIMBS, so it's always
+            // reducing less than 2^(128 NUMLIMBS).
+            let rest = limbs.len().saturating_sub(NUMDOUBLELIMBS).div_ceil(NUMLIMBS) * NUMLIMBS;
+            let mut wide = [0u64; NUMDOUBLELIMBS];
+            wide[..limbs.len() - rest].copy_from_slice(&limbs[rest..]);
+            let mut ret = reduce_barrett(&wide);
+            for chunk in limbs[..rest].chunks(NUMLIMBS).rev() {
+                wide[..NUMLIMBS].copy_from_slice(chunk);
+                wide[NUMLIMBS..].copy_from_slice(&ret);
+                ret = reduce_barrett(&wide);
+            }
+            $classname { limbs: ret }
+        }
+    }
 
     from_unsigned! { $classname; u64, u32, u8 }
     from_signed! { $classname; i64, i32, i8 }
@@ -393,15 +497,21 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
 
         #[inline]
         pub fn exp_by_squaring(y: $classname, x: &$classname, n: &$classname) -> $classname {
-            if n.is_zero() {
-                y
-            } else if n.is_one() {
-                x.mul(y)
-            } else if n.is_even() {
-                $classname::exp_by_squaring(y, &x.square(), &n.div2())
-            } else {
-                $classname::exp_by_squaring(x.mul(y), &x.square(), &(*n - $classname::one()).div2())
+            // A loop rather than recursion, which needs a stack frame per bit of n and overflows
+            // the stack for primes of thousands of bits.
+            let mut ret = y;
+            let mut x = *x;
+            let mut n = *n;
+            while !n.is_zero() {
+                if !n.is_even() {
+                    ret = x.mul(ret);
+                }
+                n = n.div2();
+                if !n.is_zero() {
+                    x = x.square();
+                }
             }
+            ret
         }
 
         ///Divide the value by 2.
@@ -472,28 +582,42 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
         }
 
         /// Create a Non-Adjacent form of the value.
-        /// return - Vector which represents the NAF of value.
+        /// return - Vector which represents the NAF of value, least significant digit first.
         pub fn create_naf(&self) -> Vec<i8> {
-            // TODO: build unit tests for this
-            // Mutable collection which has all 0s in it.
-            let mut naf = vec![0; PRIMEBITS + 1];
-            let mut i = 0;
-            let mut n = SignedDigitsArray::new_pos(self.limbs);
-            let zero = SignedDigitsArray::new_pos([0u64; NUMLIMBS]);
+            self.create_wnaf(2)
+        }
 
-            while n > zero {
-                if !n.is_even() {
-                    let diff = (n % 4) as i8;
-                    naf[i] = 2i8 - diff;
-                    n -= naf[i] as i64;
-                } else {
-                    n >>= 1;
-                    i += 1;
-                }
+        /// Create the width-w Non-Adjacent form of the value, least significant digit first and
+        /// padded with zeros to PRIMEBITS + 1 digits. Panics unless w is between 2 and 8.
+        pub fn create_wnaf(&self, w: usize) -> Vec<i8> {
+            let mut naf = vec![0; PRIMEBITS + 1];
+            for (digit, d) in naf.iter_mut().zip(self.wnaf_iter(w)) {
+                *digit = d;
             }
             naf
         }
 
+        /// The width-w NAF digits without allocating, least significant first, up to the last
+        /// nonzero one.
+        pub fn wnaf_iter(&self, w: usize) -> Wnaf<[u64; NUMLIMBS]> {
+            Wnaf::new(self.limbs, w)
+        }
+
+        /// Create the joint sparse form of the value and other, as pairs of digits least
+        /// significant first, padded with zeros to PRIMEBITS + 1 pairs.
+        pub fn create_jsf(&self, other: &$classname) -> Vec<(i8, i8)> {
+            let mut jsf = vec![(0, 0); PRIMEBITS + 1];
+            for (column, c) in jsf.iter_mut().zip(self.jsf_iter(other)) {
+                *column = c;
+            }
+            jsf
+        }
+
+        /// The joint sparse form without allocating, up to the last nonzero column.
+        pub fn jsf_iter(&self, other: &$classname) -> Jsf<[u64; NUMLIMBS]> {
+            Jsf::new(self.limbs, other.limbs)
+        }
+
         // TODO: build unit tests for this
         pub fn quadratic_residue(&self) -> bool {
             self.legendre_symbol() == 1
@@ -512,6 +636,53 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
             }
         }
 
+        /// One of the square roots of the value, or None if it isn't a square.
+        /// This is Tonelli-Shanks, so it works whatever p is mod 4.
+        pub fn sqrt(&self) -> Option<$classname> {
+            if self.is_zero() {
+                return Some(*self);
+            }
+            if !self.quadratic_residue() {
+                return None;
+            }
+            // p - 1 = q * 2^s with q odd
+            let mut q = PRIME.sub_ignore_carry(&[1]);
+            let mut s = 0;
+            while q.is_even() {
+                q = q.div2();
+                s += 1;
+            }
+            let mut z = $classname::new_from_u64(2);
+            while z.quadratic_residue() {
+                z += $classname::one();
+            }
+
+            let mut m = s;
+            let mut c = z.pow($classname::new(q));
+            // x = a^((q - 1) / 2), r = a^((q + 1) / 2), t = a^q
+            let x = self.pow($classname::new(q.div2()));
+            let mut r = x * *self;
+            let mut t = x * r;
+            while !t.is_one() {
+                // least i with t^(2^i) = 1; it is less than m since t is a square in the subgroup
+                let mut i = 0;
+                let mut t2i = t;
+                while !t2i.is_one() {
+                    t2i = t2i.square();
+                    i += 1;
+                }
+                let mut b = c;
+                for _ in 0..m - i - 1 {
+                    b = b.square();
+                }
+                m = i;
+                c = b.square();
+                t *= c;
+                r *= b;
+            }
+            Some(r)
+        }
+
     }
 
     // From Handbook of Applied Crypto algo 14.12
@@ -551,8 +722,9 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
         // let q2 = BARRETTMU.mul_classic(&q1);
         let q2 = q1.mul_classic(&BARRETTMU[..]);
 
-        let mut q3 = [0u64; NUMLIMBS];
-        q3.copy_from_slice(&q2[NUMLIMBS + 1..NUMDOUBLELIMBS + 1]);
+        // q3 can need all k + 1 limbs when p is small for its limb count
+        let mut q3 = [0u64; NUMLIMBS + 1];
+        q3.copy_from_slice(&q2[NUMLIMBS + 1..NUMDOUBLELIMBS + 2]);
 
         let mut r1 = [0u64; NUMLIMBS + 2];
         r1.copy_from_slice(&a[..NUMLIMBS+2]);
@@ -561,17 +733,18 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
 
         // r = r1 - r2
         let (r3, _) = r1.expand_one().sub(&r2);
-        let mut r = [0u64; NUMLIMBS]; // need to chop off extra limb
-        r.copy_from_slice(&r3[..NUMLIMBS]);
+        // r < 3p here, which needs the extra limb when p is close to a whole number of limbs
+        let mut r = [0u64; NUMLIMBS + 1];
+        r.copy_from_slice(&r3[..NUMLIMBS + 1]);
 
         // at most two subtractions with p
         for _i in 0..2 {
-            if cmp(&r, &PRIME) != Some(Ordering::Less) {
-                r.sub_assign(&PRIME);
+            if (&mut r[..]).greater_or_equal(&PRIME[..]) {
+                r.sub_assign(&PRIME[..]);
             }
         }
-        debug_assert!(cmp(&r, &PRIME) == Some(Ordering::Less));
-        r
+        debug_assert!(r[NUMLIMBS] == 0 && cmp(&r[..NUMLIMBS], &PRIME) == Some(Ordering::Less));
+        r.contract_one()
     }
 
 
@@ -581,6 +754,8 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
         use super::*;
         // use limb_math;
         use proptest::prelude::*;
+        use proptest::collection::vec;
+        use poly::Poly;
         use rand::OsRng;
 
         prop_compose! {
@@ -603,7 +778,12 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
             }
         }
 
+        // Exponentiation takes seconds mod the thousands of bits of the RFC 7919 primes, so past 8
+        // limbs the number of cases shrinks with the square of the limb count.
+        const CASES: u32 = if NUMLIMBS <= 8 { 256 } else { (256 * 64 / (NUMLIMBS * NUMLIMBS)) as u32 + 1 };
+
         proptest! {
+            #![proptest_config(ProptestConfig::with_cases(CASES))]
             #[test]
             fn identity(a in arb_fp()) {
                 prop_assert_eq!(a * 1, a);
@@ -630,6 +810,44 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
                 assert_eq!(a - a, $classname::zero());
             }
 
+            #[test]
+            fn wnaf_recombines(a in arb_fp(), w in 2usize..9) {
+                let two = $classname::from(2u64);
+                for x in [a, -a].iter() {
+                    let naf = x.create_wnaf(w);
+                    prop_assert_eq!(naf.len(), PRIMEBITS + 1);
+                    prop_assert!(x.wnaf_iter(w).count() <= PRIMEBITS + 1);
+                    let recombined = naf
+                        .iter()
+                        .rev()
+                        .fold($classname::zero(), |acc, d| acc * two + $classname::from(*d));
+                    prop_assert_eq!(recombined, *x);
+                    for (i, d) in naf.iter().enumerate() {
+                        if *d != 0 {
+                            prop_assert!(d % 2 != 0 && i32::from(d.abs()) < 1 << (w - 1));
+                            prop_assert!(naf[i + 1..].iter().take(w - 1).all(|x| *x == 0));
+                        }
+                    }
+                }
+                prop_assert_eq!(a.create_naf(), a.create_wnaf(2));
+            }
+
+            #[test]
+            fn jsf_recombines(a in arb_fp(), b in arb_fp()) {
+                let two = $classname::from(2u64);
+                let (b, c) = (-b, b);
+                let jsf = a.create_jsf(&b);
+                prop_assert_eq!(jsf.len(), PRIMEBITS + 1);
+                let (x, y) = jsf.iter().rev().fold(($classname::zero(), $classname::zero()), |acc, d| {
+                    (acc.0 * two + $classname::from(d.0), acc.1 * two + $classname::from(d.1))
+                });
+                prop_assert_eq!((x, y), (a, b));
+                for window in jsf.windows(3) {
+                    prop_assert!(window.contains(&(0, 0)));
+                }
+                prop_assert!(c.jsf_iter(&c).all(|d| d.0 == d.1));
+            }
+
             #[test]
             fn commutative(a in arb_fp(), b in arb_fp()) {
                 prop_assert_eq!(a + b , b + a);
@@ -715,11 +933,72 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
                 prop_assert_eq!($classname::from(bytes), a);
             }
 
+            #[test]
+            fn prime_field_from_bytes_roundtrip(a in arb_fp()) {
+                prop_assert_eq!(<$classname as PrimeField>::from_bytes(&a.to_bytes()), Some(a));
+            }
+
+            #[test]
+            fn from_bytes_reduced_matches_horner(ref bytes in vec(any::<u8>(), 0..5 * NUMBYTES)) {
+                let radix = $classname::from(256u64);
+                let expected = bytes.iter().fold($classname::zero(), |acc, b| acc * radix + $classname::from(*b));
+                prop_assert_eq!(<$classname as PrimeField>::from_bytes_reduced(bytes), expected);
+            }
+
+            #[test]
+            fn conditional_select_and_swap(a in arb_fp(), b in arb_fp(), choice in any::<bool>()) {
+                let selected = <$classname as PrimeField>::conditional_select(&a, &b, choice);
+                prop_assert_eq!(selected, if choice { b } else { a });
+                let (mut x, mut y) = (a, b);
+                <$classname as PrimeField>::conditional_swap(&mut x, &mut y, choice);
+                prop_assert_eq!((x, y), if choice { (b, a) } else { (a, b) });
+            }
+
             #[test]
             fn square_same_as_pow_2(a in arb_fp()) {
                 prop_assert_eq!(a.square(), a.pow(2));
             }
 
+            #[test]
+            fn sqrt_of_square(a in arb_fp()) {
+                let square = a.square();
+                let root = square.sqrt().unwrap();
+                prop_assert_eq!(root.square(), square);
+                prop_assert!(root == a || root == -a);
+                if !a.is_zero() && !a.quadratic_residue() {
+                    prop_assert_eq!(a.sqrt(), None);
+                }
+            }
+
+            #[test]
+            fn poly_div_rem(a in vec(arb_fp(), 0..10), b in vec(arb_fp(), 1..6)) {
+                let a = Poly::new(a);
+                let b = Poly::new(b);
+                prop_assume!(!b.is_zero());
+                let (q, r) = a.div_rem(&b);
+                prop_assert!(r.degree() < b.degree());
+                prop_assert_eq!(&(&q * &b) + &r, a);
+            }
+
+            #[test]
+            fn poly_interpolate_roundtrip(coeffs in vec(arb_fp(), 0..10)) {
+                let f = Poly::new(coeffs);
+                let points: Vec<($classname, $classname)> = (1..11u64)
+                    .map(|x| ($classname::from(x), f.evaluate($classname::from(x))))
+                    .collect();
+                prop_assert_eq!(Poly::interpolate(&points), f.clone());
+                let xs: Vec<$classname> = points.iter().map(|&(x, _)| x).collect();
+                prop_assert_eq!(f.evaluate_many(&xs), points.iter().map(|&(_, y)| y).collect::<Vec<_>>());
+            }
+
+            #[test]
+            fn poly_mul_evaluates_pointwise(a in vec(arb_fp(), 0..8), b in vec(arb_fp(), 0..8), x in arb_fp()) {
+                let a = Poly::new(a);
+                let b = Poly::new(b);
+                prop_assert_eq!((&a * &b).evaluate(x), a.evaluate(x) * b.evaluate(x));
+                prop_assert_eq!(a.compose(&b).evaluate(x), a.evaluate(b.evaluate(x)));
+            }
+
             #[test]
             fn from_signed_ints(a in any::<i64>()) {
                 if a < 0 {
@@ -729,5 +1008,21 @@ macro_rules! fp { ($modname: ident, $classname: ident, $bits: tt, $limbs: tt, $p
                 }
             }
         }
+
+        #[test]
+        fn from_bytes_rejects_non_canonical() {
+            let mut p = [0u8; NUMBYTES];
+            for (i, limb) in PRIME.iter().enumerate() {
+                for j in 0..LIMBSIZEBYTES {
+                    let idx = i * LIMBSIZEBYTES + j;
+                    if idx < NUMBYTES {
+                        p[NUMBYTES - idx - 1] = (limb >> (j * BITSPERBYTE)) as u8;
+                    }
+                }
+            }
+            assert_eq!(<$classname as PrimeField>::from_bytes(&p), None);
+            assert_eq!(<$classname as PrimeField>::from_bytes(&p[1..]), None);
+            assert_eq!(<$classname as PrimeField>::from_bytes(&[0u8; NUMBYTES]), Some($classname::zero()));
+        }
     }
 }};}
@@ -0,0 +1,238 @@
+//! Signed digit recodings of integers given as little-endian limbs, which cut down the additions
+//! in scalar multiplication and exponentiation.
+//!
+//! * The width-w non-adjacent form writes `k = sum(d_i 2^i)` with every nonzero digit odd and
+//!   less than `2^(w-1)` in absolute value, and at most one nonzero digit in any `w` in a row,
+//!   so about one in `w + 1` digits is nonzero. Width 2 is the ordinary NAF.
+//! * The joint sparse form of Solinas writes a pair `(k0, k1)` with digits in `{-1, 0, 1}` so
+//!   that at least one column in any three in a row is all zero. On average half the columns
+//!   are nonzero, which is what `k0 P + k1 Q` costs in additions with Shamir's trick.
+//!
+//! Both are iterators over the digits, least significant first, which own a copy of the limbs
+//! and never allocate. They stop after the last nonzero digit.
+
+/// The width-w NAF digits of a number. See the module documentation.
+#[derive(Clone, Debug)]
+pub struct Wnaf<A> {
+    limbs: A,
+    // A carry out of the top limb, which adding to a number that fills its limbs can make.
+    overflow: bool,
+    width: usize,
+}
+
+impl<A: AsRef<[u64]> + AsMut<[u64]>> Wnaf<A> {
+    ///The width-w NAF of `limbs`. Panics unless `width` is between 2 and 8, so the digits fit
+    ///in an `i8`.
+    pub fn new(limbs: A, width: usize) -> Wnaf<A> {
+        assert!(
+            (2..=8).contains(&width),
+            "The NAF width must be between 2 and 8."
+        );
+        Wnaf {
+            limbs,
+            overflow: false,
+            width,
+        }
+    }
+}
+
+impl<A: AsRef<[u64]> + AsMut<[u64]>> Iterator for Wnaf<A> {
+    type Item = i8;
+
+    fn next(&mut self) -> Option<i8> {
+        if !self.overflow && is_zero(self.limbs.as_ref()) {
+            return None;
+        }
+        let limbs = self.limbs.as_mut();
+        let mut digit = 0i8;
+        if limbs[0] & 1 == 1 {
+            // The residue mod 2^w closest to zero. Taking it off clears the low w bits.
+            let modulus = 1u64 << self.width;
+            let m = limbs[0] & (modulus - 1);
+            if m < modulus / 2 {
+                digit = m as i8;
+                limbs[0] -= m;
+            } else {
+                digit = -((modulus - m) as i8);
+                self.overflow |= add_small(limbs, modulus - m);
+            }
+        }
+        shift_right_one(limbs, &mut self.overflow);
+        Some(digit)
+    }
+}
+
+/// The joint sparse form digits of a pair of numbers. See the module documentation.
+#[derive(Clone, Debug)]
+pub struct Jsf<A> {
+    k: [A; 2],
+    // The carries d_i of Solinas' algorithm, which are 0 or 1.
+    d: [u64; 2],
+}
+
+impl<A: AsRef<[u64]>> Jsf<A> {
+    pub fn new(k0: A, k1: A) -> Jsf<A> {
+        Jsf {
+            k: [k0, k1],
+            d: [0, 0],
+        }
+    }
+}
+
+impl<A: AsRef<[u64]> + AsMut<[u64]>> Iterator for Jsf<A> {
+    type Item = (i8, i8);
+
+    fn next(&mut self) -> Option<(i8, i8)> {
+        if self.d == [0, 0] && is_zero(self.k[0].as_ref()) && is_zero(self.k[1].as_ref()) {
+            return None;
+        }
+        // l_i = k_i + d_i mod 8 is all the next digits depend on.
+        let l = [
+            (self.k[0].as_ref()[0] & 7) + self.d[0],
+            (self.k[1].as_ref()[0] & 7) + self.d[1],
+        ];
+        let mut u = [0i8; 2];
+        for i in 0..2 {
+            if l[i] & 1 == 1 {
+                u[i] = if l[i] & 3 == 1 { 1 } else { -1 };
+                if (l[i] & 7 == 3 || l[i] & 7 == 5) && l[1 - i] & 3 == 2 {
+                    u[i] = -u[i];
+                }
+            }
+        }
+        for ((d, k), u) in self.d.iter_mut().zip(self.k.iter_mut()).zip(u.iter()) {
+            if 2 * *d as i8 == 1 + u {
+                *d = 1 - *d;
+            }
+            let mut overflow = false;
+            shift_right_one(k.as_mut(), &mut overflow);
+        }
+        Some((u[0], u[1]))
+    }
+}
+
+fn is_zero(limbs: &[u64]) -> bool {
+    limbs.iter().all(|limb| *limb == 0)
+}
+
+///Add `x` in place, returning the carry out of the top limb.
+fn add_small(limbs: &mut [u64], x: u64) -> bool {
+    let mut carry = x;
+    for limb in limbs.iter_mut() {
+        let (sum, over) = limb.overflowing_add(carry);
+        *limb = sum;
+        if !over {
+            return false;
+        }
+        carry = 1;
+    }
+    true
+}
+
+///Shift right one bit, shifting `top` in at the top and clearing it.
+fn shift_right_one(limbs: &mut [u64], top: &mut bool) {
+    let mut high = *top as u64;
+    for limb in limbs.iter_mut().rev() {
+        let low = *limb & 1;
+        *limb = (*limb >> 1) | (high << 63);
+        high = low;
+    }
+    *top = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn recombine(digits: &[i8]) -> i128 {
+        digits
+            .iter()
+            .rev()
+            .fold(0i128, |acc, d| 2 * acc + i128::from(*d))
+    }
+
+    #[test]
+    fn known_recodings() {
+        // 7 = 8 - 1 and 0b1011 = 16 - 4 - 1
+        assert_eq!(Wnaf::new([7u64], 2).collect::<Vec<_>>(), vec![-1, 0, 0, 1]);
+        assert_eq!(
+            Wnaf::new([11u64], 2).collect::<Vec<_>>(),
+            vec![-1, 0, -1, 0, 1]
+        );
+        assert_eq!(
+            Wnaf::new([11u64], 4).collect::<Vec<_>>(),
+            vec![-5, 0, 0, 0, 1]
+        );
+        assert_eq!(Wnaf::new([0u64, 0], 3).count(), 0);
+        // The JSF of (53, 102) from Hankerson, Menezes and Vanstone, example 3.51.
+        let jsf: Vec<(i8, i8)> = Jsf::new([53u64], [102u64]).collect();
+        assert_eq!(
+            jsf.iter().rev().map(|c| c.0).collect::<Vec<_>>(),
+            vec![1, 0, 0, -1, 0, -1, -1]
+        );
+        assert_eq!(
+            jsf.iter().rev().map(|c| c.1).collect::<Vec<_>>(),
+            vec![1, 1, 0, 1, 0, -1, 0]
+        );
+    }
+
+    #[test]
+    fn carries_past_the_top_limb() {
+        let digits: Vec<i8> = Wnaf::new([!0u64, !0u64], 5).collect();
+        assert_eq!(digits.len(), 129);
+        assert_eq!(digits[0], -1);
+        assert_eq!(digits[128], 1);
+        let jsf: Vec<(i8, i8)> = Jsf::new([!0u64], [!0u64 - 2]).collect();
+        assert_eq!(jsf.len(), 65);
+        assert_eq!(
+            recombine(&jsf.iter().map(|c| c.0).collect::<Vec<_>>()),
+            (1i128 << 64) - 1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "between 2 and 8")]
+    fn width_too_large() {
+        Wnaf::new([1u64], 9);
+    }
+
+    proptest! {
+        #[test]
+        fn wnaf_recombines(k in any::<u64>(), w in 2usize..9) {
+            let digits: Vec<i8> = Wnaf::new([k], w).collect();
+            prop_assert_eq!(recombine(&digits), i128::from(k));
+            prop_assert!(digits.len() <= 65);
+            for (i, d) in digits.iter().enumerate() {
+                if *d != 0 {
+                    prop_assert!(d % 2 != 0 && i32::from(d.abs()) < 1 << (w - 1));
+                    prop_assert!(digits[i + 1..].iter().take(w - 1).all(|x| *x == 0));
+                }
+            }
+        }
+
+        #[test]
+        fn jsf_recombines(k0 in any::<u64>(), k1 in any::<u64>()) {
+            let jsf: Vec<(i8, i8)> = Jsf::new([k0], [k1]).collect();
+            let (d0, d1): (Vec<i8>, Vec<i8>) = jsf.iter().cloned().unzip();
+            prop_assert_eq!(recombine(&d0), i128::from(k0));
+            prop_assert_eq!(recombine(&d1), i128::from(k1));
+            prop_assert!(jsf.len() <= 65);
+            // Of any three consecutive columns at least one is zero.
+            for window in jsf.windows(3) {
+                prop_assert!(window.contains(&(0, 0)));
+            }
+            // If a row has two nonzero digits in a row, the other row's digits there are
+            // nonzero then zero.
+            for j in 0..jsf.len().saturating_sub(1) {
+                let (lo, hi) = (jsf[j], jsf[j + 1]);
+                if lo.0 != 0 && hi.0 != 0 {
+                    prop_assert!(hi.1 != 0 && lo.1 == 0);
+                }
+                if lo.1 != 0 && hi.1 != 0 {
+                    prop_assert!(hi.0 != 0 && lo.0 == 0);
+                }
+            }
+        }
+    }
+}
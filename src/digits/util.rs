@@ -599,8 +599,11 @@ macro_rules! digits_u64_impls { ($($N:expr)+) => {
         )+
 }}
 
+// An fp! type with n limbs needs n through n + 3 and 2n. Past 19 these cover the 2048 to 8192
+// bit primes of the RFC 7919 groups, with 32, 48, 64, 96 and 128 limbs.
 digits_u64_impls! {
     2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19
+    32 33 34 35 48 49 50 51 64 65 66 67 96 97 98 99 128 129 130 131 192 256
 }
 
 #[inline]
@@ -622,6 +625,30 @@ pub fn cmp(x: &[u64], y: &[u64]) -> Option<Ordering> {
     }
 }
 
+///Bits `start..start + width` of the little-endian `limbs` as a number, for `width` less than
+///64. Bits past the end are zero.
+#[inline]
+pub fn limb_window(limbs: &[u64], start: usize, width: usize) -> usize {
+    let (limb, shift) = (start / 64, start % 64);
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut bits = limbs[limb] >> shift;
+    if shift + width > 64 && limb + 1 < limbs.len() {
+        bits |= limbs[limb + 1] << (64 - shift);
+    }
+    (bits & ((1 << width) - 1)) as usize
+}
+
+///Position of the highest set bit plus one, so zero for zero.
+#[inline]
+pub fn bit_length(limbs: &[u64]) -> usize {
+    match limbs.iter().rposition(|limb| *limb != 0) {
+        Some(i) => i * 64 + 64 - limbs[i].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
 #[inline]
 pub fn mul_slice_by_1_assign_carry(x: &mut [u64], y: u64) -> u64 {
     let mut carry = 0u64;
@@ -844,6 +871,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn limb_window_crosses_limbs() {
+        let limbs = [0xf000_0000_0000_0000, 0b1011];
+        assert_eq!(limb_window(&limbs, 60, 8), 0xbf);
+        assert_eq!(limb_window(&limbs, 64, 3), 0b011);
+        assert_eq!(limb_window(&limbs, 126, 5), 0);
+        assert_eq!(limb_window(&limbs, 130, 5), 0);
+        assert_eq!(bit_length(&limbs), 68);
+        assert_eq!(bit_length(&[1, 0]), 1);
+        assert_eq!(bit_length(&[0, 0]), 0);
+    }
+
     proptest! {
         #[test]
         fn divrem_random(ref a in arb_limbs8(), b in any::<u64>()) {
@@ -935,5 +974,4 @@ mod tests {
         let b = [2u64, 2];
         assert_eq!(div_3_limbs_by_2_limbs(a, b), [9223372036854775808, 0]);
     }
-
 }
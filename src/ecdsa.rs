@@ -0,0 +1,413 @@
+//! ECDSA (FIPS 186-4, SEC 1) on prime order Weierstrass curves such as `Secp256k1` and `P256`.
+//! The signature of a message hash `e` under the secret key `d` is `r = x(kG) mod n` and
+//! `s = (e + rd) / k` for a nonce `k`, and it verifies against the public key `Q = dG` if
+//! `x((e / s) G + (r / s) Q) mod n = r`.
+//!
+//! Anyone who learns a nonce, or sees two signatures with the same one, learns the key, so the
+//! nonces come from the HMAC-DRBG of RFC 6979 seeded with the key and the hash. Signing is
+//! deterministic and needs no randomness. Since `(r, -s)` verifies whenever `(r, s)` does, `sign`
+//! returns the one with `s <= (n - 1) / 2` as Bitcoin and Ethereum require, though `verify`
+//! accepts both.
+//!
+//! The hash is a type parameter, such as `sha2::Sha256`. A hash longer than the group order is
+//! cut down to its leftmost bits, which is `bits2int` in RFC 6979, and then reduced mod n.
+//!
+//! A few bits of each nonce leaking through timing are enough to recover the key with a lattice
+//! attack, so `kG` and `dG` use `Projective::scalar_mul_constant_time`, and the nonce is inverted
+//! with `PrimeField::inv_constant_time`, with the caveat on `PrimeField`. Verification only
+//! handles public values and uses the faster variable-time code.
+use field::PrimeField;
+use hmac::digest::{BlockInput, Digest, FixedOutput, Input, Reset};
+use hmac::{Hmac, Mac};
+use msm::msm;
+use num_traits::{Inv, Zero};
+use std::marker::PhantomData;
+use weierstrass::{Affine, Projective, WeierstrassParams};
+
+/// A hash that HMAC, and so the nonces of RFC 6979, can be built on. The `sha2` hashes are.
+pub trait HmacHash: Input + BlockInput + FixedOutput + Reset + Default + Clone {}
+
+impl<D: Input + BlockInput + FixedOutput + Reset + Default + Clone> HmacHash for D {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature<C: WeierstrassParams> {
+    pub r: C::Scalar,
+    pub s: C::Scalar,
+}
+
+impl<C: WeierstrassParams> Signature<C> {
+    ///True if `s <= (n - 1) / 2`, the form `sign` produces.
+    pub fn is_low_s(&self) -> bool {
+        self.s <= -self.s
+    }
+
+    ///The signature with `s` replaced by `-s` if that's lower. The two verify alike.
+    pub fn normalize_s(&self) -> Signature<C> {
+        Signature {
+            r: self.r,
+            s: if self.is_low_s() { self.s } else { -self.s },
+        }
+    }
+
+    ///`r` followed by `s`, each in big-endian bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.r.to_bytes();
+        bytes.extend(self.s.to_bytes());
+        bytes
+    }
+
+    ///The inverse of `to_bytes`. None if `bytes` is the wrong length or `r` or `s` isn't in
+    ///`1..n`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Signature<C>> {
+        let len = scalar_bytes::<C::Scalar>();
+        if bytes.len() != 2 * len {
+            return None;
+        }
+        let r = C::Scalar::from_bytes(&bytes[..len])?;
+        let s = C::Scalar::from_bytes(&bytes[len..])?;
+        if r.is_zero() || s.is_zero() {
+            None
+        } else {
+            Some(Signature { r, s })
+        }
+    }
+}
+
+///The public key `dG` of the secret key `d`.
+pub fn public_key<C>(secret: &C::Scalar) -> Affine<C>
+where
+    C: WeierstrassParams,
+    C::Base: PrimeField,
+{
+    Projective::generator()
+        .scalar_mul_constant_time(secret)
+        .to_affine_constant_time()
+}
+
+///Sign the `D` hash of `message`. Panics if the secret key is zero.
+pub fn sign<C, D>(secret: &C::Scalar, message: &[u8]) -> Signature<C>
+where
+    C: WeierstrassParams,
+    C::Base: PrimeField,
+    D: HmacHash,
+{
+    sign_prehashed::<C, D>(secret, &D::digest(message))
+}
+
+///Sign a message hash computed with `D`, which also drives the nonce generation. Panics if the
+///secret key is zero.
+pub fn sign_prehashed<C, D>(secret: &C::Scalar, digest: &[u8]) -> Signature<C>
+where
+    C: WeierstrassParams,
+    C::Base: PrimeField,
+    D: HmacHash,
+{
+    assert!(!secret.is_zero(), "The secret key can't be zero.");
+    let e = C::Scalar::from_bytes_reduced(&bits2int::<C::Scalar>(digest));
+    let mut nonces = Nonces::<D>::new(secret, digest);
+    loop {
+        // r or s is zero with negligible probability, but RFC 6979 says to take the next nonce.
+        let k: C::Scalar = nonces.next();
+        let r: C::Scalar = match public_key::<C>(&k).coordinates() {
+            Some((x, _)) => C::Scalar::from_bytes_reduced(&x.to_bytes()),
+            None => continue,
+        };
+        let s = (e + r * *secret) * k.inv_constant_time();
+        if !r.is_zero() && !s.is_zero() {
+            return Signature { r, s }.normalize_s();
+        }
+    }
+}
+
+///True if `signature` is a signature of the `D` hash of `message` under `public`.
+pub fn verify<C, D>(public: &Affine<C>, message: &[u8], signature: &Signature<C>) -> bool
+where
+    C: WeierstrassParams,
+    C::Base: PrimeField,
+    D: Digest,
+{
+    verify_prehashed(public, &D::digest(message), signature)
+}
+
+///True if `signature` is a signature of the message hash `digest` under `public`. Either `s` or
+///`-s` is accepted, and the key has to be a point of the group other than the identity.
+pub fn verify_prehashed<C>(public: &Affine<C>, digest: &[u8], signature: &Signature<C>) -> bool
+where
+    C: WeierstrassParams,
+    C::Base: PrimeField,
+{
+    if signature.r.is_zero() || signature.s.is_zero() || public.is_identity() {
+        return false;
+    }
+    let valid_key = if C::COFACTOR == [1] {
+        public.is_on_curve()
+    } else {
+        public.is_in_subgroup()
+    };
+    if !valid_key {
+        return false;
+    }
+    let e = C::Scalar::from_bytes_reduced(&bits2int::<C::Scalar>(digest));
+    let w = signature.s.inv();
+    let point = msm(&[Affine::generator(), *public], &[e * w, signature.r * w]).to_affine();
+    match point.coordinates() {
+        Some((x, _)) => C::Scalar::from_bytes_reduced(&x.to_bytes()) == signature.r,
+        None => false,
+    }
+}
+
+///The first nonce RFC 6979 derives from `secret` and the message hash `digest`, which is the
+///one `sign_prehashed` uses unless it makes `r` or `s` zero.
+pub fn rfc6979_nonce<F: PrimeField, D: HmacHash>(secret: &F, digest: &[u8]) -> F {
+    Nonces::<D>::new(secret, digest).next()
+}
+
+/// The HMAC-DRBG of RFC 6979 section 3.2, which yields the candidate nonces in `1..n`.
+struct Nonces<D: HmacHash> {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    hash: PhantomData<D>,
+}
+
+impl<D: HmacHash> Nonces<D> {
+    fn new<F: PrimeField>(secret: &F, digest: &[u8]) -> Nonces<D> {
+        let size = D::output_size();
+        let mut nonces = Nonces {
+            k: vec![0; size],
+            v: vec![1; size],
+            hash: PhantomData,
+        };
+        let x = secret.to_bytes();
+        let h = F::from_bytes_reduced(&bits2int::<F>(digest)).to_bytes();
+        for separator in 0u8..2 {
+            nonces.k = nonces.hmac(&[&nonces.v, &[separator], &x, &h]);
+            nonces.v = nonces.hmac(&[&nonces.v]);
+        }
+        nonces
+    }
+
+    fn hmac(&self, data: &[&[u8]]) -> Vec<u8> {
+        let mut mac = Hmac::<D>::new_varkey(&self.k).expect("HMAC takes keys of any length.");
+        for d in data {
+            mac.input(d);
+        }
+        mac.result().code().to_vec()
+    }
+
+    fn next<F: PrimeField>(&mut self) -> F {
+        loop {
+            let mut t = Vec::new();
+            while t.len() < scalar_bytes::<F>() {
+                self.v = self.hmac(&[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let candidate = F::from_bytes(&bits2int::<F>(&t));
+            // Step the generator whether or not the candidate is taken, so a caller rejecting it
+            // gets the next one.
+            self.k = self.hmac(&[&self.v, &[0]]);
+            self.v = self.hmac(&[&self.v]);
+            match candidate {
+                Some(k) if !k.is_zero() => return k,
+                _ => continue,
+            }
+        }
+    }
+}
+
+fn scalar_bytes<F: PrimeField>() -> usize {
+    F::PRIMEBITS.div_ceil(8)
+}
+
+///The leftmost `F::PRIMEBITS` bits of `bytes` as an integer, in big-endian bytes the length of
+///an `F`. It can be larger than the prime.
+fn bits2int<F: PrimeField>(bytes: &[u8]) -> Vec<u8> {
+    let len = scalar_bytes::<F>();
+    let mut ret = vec![0u8; len];
+    if bytes.len() < len {
+        ret[len - bytes.len()..].copy_from_slice(bytes);
+        return ret;
+    }
+    ret.copy_from_slice(&bytes[..len]);
+    let shift = 8 * len - F::PRIMEBITS;
+    if shift > 0 {
+        for i in (0..len).rev() {
+            let high = if i > 0 { ret[i - 1] << (8 - shift) } else { 0 };
+            ret[i] = (ret[i] >> shift) | high;
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fr_p256::FrP256;
+    use fr_secp256k1::FrSecp256k1;
+    use proptest::prelude::*;
+    use sha2::{Sha256, Sha512};
+    use test_util::{field_from_hex, from_hex};
+    use weierstrass::{Secp256k1, P256};
+
+    #[test]
+    fn rfc6979_p256_vectors() {
+        // RFC 6979 appendix A.2.5.
+        let d: FrP256 =
+            field_from_hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let (ux, uy) = public_key::<P256>(&d).coordinates().unwrap();
+        assert_eq!(
+            ux.to_bytes(),
+            from_hex("60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6")
+        );
+        assert_eq!(
+            uy.to_bytes(),
+            from_hex("7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299")
+        );
+        let vectors: [(&[u8], bool, &str, &str, &str); 4] = [
+            (
+                b"sample",
+                false,
+                "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+                "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+            ),
+            (
+                b"sample",
+                true,
+                "5fa81c63109badb88c1f367b47da606da28cad69aa22c4fe6ad7df73a7173aa5",
+                "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00",
+                "2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe",
+            ),
+            (
+                b"test",
+                false,
+                "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0",
+                "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+                "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+            ),
+            (
+                b"test",
+                true,
+                "6915d11632aca3c40d5d51c08daf9c555933819548784480e93499000d9f0b7f",
+                "461d93f31b6540894788fd206c07cfa0cc35f46fa3c91816fff1040ad1581a04",
+                "39af9f15de0db8d97e72719c74820d304ce5226e32dedae67519e840d1194e55",
+            ),
+        ];
+        let public = public_key::<P256>(&d);
+        for &(message, sha512, k, r, s) in vectors.iter() {
+            let expected = Signature::<P256> {
+                r: field_from_hex(r),
+                s: field_from_hex(s),
+            };
+            let (nonce, signature) = if sha512 {
+                (
+                    rfc6979_nonce::<_, Sha512>(&d, &Sha512::digest(message)),
+                    sign::<P256, Sha512>(&d, message),
+                )
+            } else {
+                (
+                    rfc6979_nonce::<_, Sha256>(&d, &Sha256::digest(message)),
+                    sign::<P256, Sha256>(&d, message),
+                )
+            };
+            assert_eq!(nonce, field_from_hex(k));
+            // The RFC doesn't normalize s.
+            assert_eq!(signature, expected.normalize_s());
+            assert!(signature.is_low_s());
+            if !sha512 {
+                assert!(verify::<P256, Sha256>(&public, message, &expected));
+                assert!(verify::<P256, Sha256>(&public, message, &signature));
+            }
+        }
+    }
+
+    #[test]
+    fn secp256k1_vectors() {
+        // Widely used vectors for secret key 1, as in python-ecdsa and Trezor.
+        let d = FrSecp256k1::from(1u64);
+        let vectors: [(&[u8], &str); 2] = [
+            (
+                b"Satoshi Nakamoto",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8\
+                 2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                b"All those moments will be lost in time, like tears in rain. Time to die...",
+                "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b\
+                 547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21",
+            ),
+        ];
+        let public = public_key::<Secp256k1>(&d);
+        assert_eq!(public, Affine::generator());
+        for &(message, expected) in vectors.iter() {
+            let signature = sign::<Secp256k1, Sha256>(&d, message);
+            assert_eq!(signature.to_bytes(), from_hex(expected));
+            assert!(verify::<Secp256k1, Sha256>(&public, message, &signature));
+        }
+        assert_eq!(
+            rfc6979_nonce::<_, Sha256>(&d, &Sha256::digest(b"Satoshi Nakamoto")),
+            field_from_hex::<FrSecp256k1>(
+                "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15"
+            )
+        );
+    }
+
+    #[test]
+    fn signature_bytes() {
+        let signature = sign::<Secp256k1, Sha256>(&FrSecp256k1::from(7u64), b"bytes");
+        let bytes = signature.to_bytes();
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(Signature::from_bytes(&bytes), Some(signature));
+        assert_eq!(Signature::<Secp256k1>::from_bytes(&bytes[1..]), None);
+        assert_eq!(Signature::<Secp256k1>::from_bytes(&[0; 64]), None);
+        assert_eq!(Signature::<Secp256k1>::from_bytes(&[0xff; 64]), None);
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        let d = FrP256::from(5u64);
+        let signature = sign::<P256, Sha256>(&d, b"key");
+        assert!(!verify::<P256, Sha256>(
+            &Affine::identity(),
+            b"key",
+            &signature
+        ));
+        let (x, y) = public_key::<P256>(&d).coordinates().unwrap();
+        let off_curve = Affine::new_unchecked(x, -x - y);
+        assert!(!verify::<P256, Sha256>(&off_curve, b"key", &signature));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be zero")]
+    fn zero_secret_key() {
+        sign::<P256, Sha256>(&FrP256::zero(), b"zero");
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+        #[test]
+        fn sign_then_verify(key in any::<[u8; 32]>(), message in any::<Vec<u8>>(), flip in any::<usize>()) {
+            let d = FrSecp256k1::from_bytes_reduced(&key);
+            prop_assume!(!d.is_zero());
+            let public = public_key::<Secp256k1>(&d);
+            let signature = sign::<Secp256k1, Sha256>(&d, &message);
+            prop_assert!(signature.is_low_s());
+            prop_assert!(verify::<Secp256k1, Sha256>(&public, &message, &signature));
+            let high = Signature::<Secp256k1> { r: signature.r, s: -signature.s };
+            prop_assert!(verify::<Secp256k1, Sha256>(&public, &message, &high));
+            let mut tampered = message.clone();
+            if tampered.is_empty() {
+                tampered.push(0);
+            } else {
+                let i = flip % tampered.len();
+                tampered[i] ^= 1;
+            }
+            prop_assert!(!verify::<Secp256k1, Sha256>(&public, &tampered, &signature));
+            let other = public_key::<Secp256k1>(&(d + FrSecp256k1::from(1u64)));
+            prop_assert!(!verify::<Secp256k1, Sha256>(&other, &message, &signature));
+
+            let d = FrP256::from_bytes_reduced(&key);
+            let signature = sign::<P256, Sha512>(&d, &message);
+            prop_assert!(verify_prehashed(&public_key::<P256>(&d), &Sha512::digest(&message), &signature));
+        }
+    }
+}
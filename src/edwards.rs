@@ -0,0 +1,440 @@
+//! Twisted Edwards curves `a x^2 + y^2 = 1 + d x^2 y^2` over prime fields, in extended
+//! coordinates `(X, Y, Z, T)` for `(X / Z, Y / Z)` with `T = XY / Z` (Hisil, Wong, Carter and
+//! Dawson, https://eprint.iacr.org/2008/522). The unified addition formula handles doubling and
+//! the identity `(0, 1)` too, and when `a` is a square and `d` isn't it has no exceptions at all.
+//!
+//! Points compress to the little-endian bytes of y with the low bit of x in the top bit, as
+//! Ed25519 does (RFC 8032).
+use field::{Field, PrimeField};
+use fp_25519::Fp25519;
+use fr_25519::Fr25519;
+use num_traits::{Inv, One, Zero};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Neg, Sub};
+
+/// The parameters of a curve, and of the prime order subgroup used as the group.
+pub trait EdwardsParams: Copy + Debug + Eq {
+    ///Field the coordinates live in.
+    type Base: PrimeField;
+    ///Field of scalars, whose prime is the order of the subgroup.
+    type Scalar: PrimeField;
+    ///The cofactor, where the curve has `cofactor * order` points. In limbs, least significant
+    ///first.
+    const COFACTOR: &'static [u64];
+
+    fn a() -> Self::Base;
+    fn d() -> Self::Base;
+    ///Coordinates of a generator of the prime order subgroup.
+    fn generator() -> (Self::Base, Self::Base);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EdwardsPoint<C: EdwardsParams> {
+    x: C::Base,
+    y: C::Base,
+    z: C::Base,
+    t: C::Base,
+    curve: PhantomData<C>,
+}
+
+impl<C: EdwardsParams> EdwardsPoint<C> {
+    ///The point `(x, y)`, or None if it isn't on the curve.
+    pub fn new(x: C::Base, y: C::Base) -> Option<EdwardsPoint<C>> {
+        let p = EdwardsPoint::new_unchecked(x, y);
+        if p.is_on_curve() {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    ///The point `(x, y)` without checking it's on the curve.
+    pub fn new_unchecked(x: C::Base, y: C::Base) -> EdwardsPoint<C> {
+        EdwardsPoint::extended(x, y, C::Base::one(), x * y)
+    }
+
+    fn extended(x: C::Base, y: C::Base, z: C::Base, t: C::Base) -> EdwardsPoint<C> {
+        EdwardsPoint {
+            x,
+            y,
+            z,
+            t,
+            curve: PhantomData,
+        }
+    }
+
+    ///The identity `(0, 1)`.
+    pub fn identity() -> EdwardsPoint<C> {
+        EdwardsPoint::new_unchecked(C::Base::zero(), C::Base::one())
+    }
+
+    pub fn generator() -> EdwardsPoint<C> {
+        let (x, y) = C::generator();
+        EdwardsPoint::new_unchecked(x, y)
+    }
+
+    ///The affine coordinates `(x, y)`.
+    pub fn coordinates(&self) -> (C::Base, C::Base) {
+        let zinv = self.z.inv();
+        (self.x * zinv, self.y * zinv)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y == self.z
+    }
+
+    ///True if `(a X^2 + Y^2) Z^2 = Z^4 + d X^2 Y^2` and `T Z = X Y`.
+    pub fn is_on_curve(&self) -> bool {
+        let (x2, y2, z2) = (self.x.square(), self.y.square(), self.z.square());
+        !self.z.is_zero()
+            && (C::a() * x2 + y2) * z2 == z2.square() + C::d() * x2 * y2
+            && self.t * self.z == self.x * self.y
+    }
+
+    ///Double the point with dbl-2008-hwcd, which skips the multiplications by T and d.
+    pub fn double(&self) -> EdwardsPoint<C> {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square() + self.z.square();
+        let d = C::a() * a;
+        let e = (self.x + self.y).square() - a - b;
+        let g = d + b;
+        let f = g - c;
+        let h = d - b;
+        EdwardsPoint::extended(e * f, g * h, f * g, e * h)
+    }
+
+    ///Multiply by a scalar, adding or subtracting the point for each nonzero digit of the
+    ///scalar's non-adjacent form.
+    pub fn scalar_mul(&self, k: &C::Scalar) -> EdwardsPoint<C> {
+        let neg = -self;
+        k.create_naf()
+            .iter()
+            .rev()
+            .fold(EdwardsPoint::identity(), |acc, digit| {
+                let acc = acc.double();
+                match *digit {
+                    1 => &acc + self,
+                    -1 => acc + neg,
+                    _ => acc,
+                }
+            })
+    }
+
+    ///Multiply by an integer given in limbs, least significant first, which can be larger than
+    ///the group order. This is double and add, so it's meant for public values like the order
+    ///and the cofactor.
+    pub fn mul_limbs(&self, k: &[u64]) -> EdwardsPoint<C> {
+        let mut ret = EdwardsPoint::identity();
+        for limb in k.iter().rev() {
+            for i in (0..64).rev() {
+                ret = ret.double();
+                if (limb >> i) & 1 == 1 {
+                    ret = &ret + self;
+                }
+            }
+        }
+        ret
+    }
+
+    ///Multiply by the cofactor, which maps any point on the curve into the prime order subgroup
+    ///and kills the small order part.
+    pub fn clear_cofactor(&self) -> EdwardsPoint<C> {
+        self.mul_limbs(C::COFACTOR)
+    }
+
+    ///True if the cofactor times the point is the identity.
+    pub fn is_small_order(&self) -> bool {
+        self.clear_cofactor().is_identity()
+    }
+
+    ///True if the point is on the curve and in the prime order subgroup.
+    pub fn is_in_subgroup(&self) -> bool {
+        self.is_on_curve() && self.mul_limbs(C::Scalar::PRIME).is_identity()
+    }
+
+    ///The little-endian bytes of y, with the low bit of x in the most significant bit. Panics if
+    ///the prime's bit length is a multiple of 8, leaving no spare bit for x.
+    pub fn compress(&self) -> Vec<u8> {
+        assert!(
+            !C::Base::PRIMEBITS.is_multiple_of(8),
+            "The encoding needs a spare bit for the sign of x."
+        );
+        let (x, y) = self.coordinates();
+        let mut bytes = y.to_bytes();
+        bytes.reverse();
+        let last = bytes.len() - 1;
+        bytes[last] |= (x.sgn0() as u8) << 7;
+        bytes
+    }
+
+    ///The inverse of `compress`: recover x from y as a square root of
+    ///`(y^2 - 1) / (d y^2 - a)`, choosing the root whose low bit matches the sign bit. None if y
+    ///isn't canonical, there is no such x, or the sign bit is set with x = 0.
+    pub fn decompress(bytes: &[u8]) -> Option<EdwardsPoint<C>> {
+        let mut bytes = bytes.to_vec();
+        let last = bytes.len().checked_sub(1)?;
+        let sign = bytes[last] >> 7 == 1;
+        bytes[last] &= 0x7f;
+        bytes.reverse();
+        let y = C::Base::from_bytes(&bytes)?;
+        let y2 = y.square();
+        let denominator = C::d() * y2 - C::a();
+        if denominator.is_zero() {
+            return None;
+        }
+        let x = ((y2 - C::Base::one()) / denominator).sqrt()?;
+        if x.is_zero() && sign {
+            return None;
+        }
+        let x = if x.sgn0() == sign { x } else { -x };
+        Some(EdwardsPoint::new_unchecked(x, y))
+    }
+}
+
+impl<C: EdwardsParams> PartialEq for EdwardsPoint<C> {
+    fn eq(&self, other: &EdwardsPoint<C>) -> bool {
+        self.x * other.z == other.x * self.z && self.y * other.z == other.y * self.z
+    }
+}
+
+impl<C: EdwardsParams> Eq for EdwardsPoint<C> {}
+
+impl<C: EdwardsParams> Add<&EdwardsPoint<C>> for &EdwardsPoint<C> {
+    type Output = EdwardsPoint<C>;
+    ///The unified add-2008-hwcd.
+    fn add(self, other: &EdwardsPoint<C>) -> EdwardsPoint<C> {
+        let a = self.x * other.x;
+        let b = self.y * other.y;
+        let c = self.t * C::d() * other.t;
+        let d = self.z * other.z;
+        let e = (self.x + self.y) * (other.x + other.y) - a - b;
+        let f = d - c;
+        let g = d + c;
+        let h = b - C::a() * a;
+        EdwardsPoint::extended(e * f, g * h, f * g, e * h)
+    }
+}
+
+impl<C: EdwardsParams> Sub<&EdwardsPoint<C>> for &EdwardsPoint<C> {
+    type Output = EdwardsPoint<C>;
+    fn sub(self, other: &EdwardsPoint<C>) -> EdwardsPoint<C> {
+        self + &(-other)
+    }
+}
+
+impl<C: EdwardsParams> Neg for &EdwardsPoint<C> {
+    type Output = EdwardsPoint<C>;
+    fn neg(self) -> EdwardsPoint<C> {
+        EdwardsPoint::extended(-self.x, self.y, self.z, -self.t)
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! edwards_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<C: EdwardsParams> $imp for EdwardsPoint<C> {
+        type Output = EdwardsPoint<C>;
+        #[inline]
+        fn $method(self, other: EdwardsPoint<C>) -> EdwardsPoint<C> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+edwards_owned_ops! { Add, add; Sub, sub }
+
+impl<C: EdwardsParams> Neg for EdwardsPoint<C> {
+    type Output = EdwardsPoint<C>;
+    #[inline]
+    fn neg(self) -> EdwardsPoint<C> {
+        -&self
+    }
+}
+
+/// The Edwards form of Curve25519 used by Ed25519: `-x^2 + y^2 = 1 - (121665 / 121666) x^2 y^2`
+/// over `2^255 - 19`, with cofactor 8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ed25519;
+
+impl EdwardsParams for Ed25519 {
+    type Base = Fp25519;
+    type Scalar = Fr25519;
+    const COFACTOR: &'static [u64] = &[8];
+
+    fn a() -> Fp25519 {
+        -Fp25519::one()
+    }
+
+    fn d() -> Fp25519 {
+        -Fp25519::from(121665u64) / Fp25519::from(121666u64)
+    }
+
+    ///The point with y = 4/5 and x even.
+    fn generator() -> (Fp25519, Fp25519) {
+        (
+            Fp25519::new([
+                0xc9562d608f25d51a,
+                0x692cc7609525a7b2,
+                0xc0a4e231fdd6dc5c,
+                0x216936d3cd6e53fe,
+            ]),
+            Fp25519::new([
+                0x6666666666666658,
+                0x6666666666666666,
+                0x6666666666666666,
+                0x6666666666666666,
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_util::{from_hex, rng_from_seed};
+
+    type Point = EdwardsPoint<Ed25519>;
+
+    fn decompress_hex(hex: &str) -> Option<Point> {
+        Point::decompress(&from_hex(hex))
+    }
+
+    prop_compose! {
+        fn arb_scalar()(seed in any::<u64>()) -> Fr25519 {
+            Fr25519::random(&mut rng_from_seed(seed))
+        }
+    }
+
+    #[test]
+    fn base_point_multiples() {
+        let b = Point::generator();
+        assert!(b.is_in_subgroup());
+        assert_eq!(
+            b.compress(),
+            from_hex("5866666666666666666666666666666666666666666666666666666666666666")
+        );
+        let multiples = [
+            (
+                2u64,
+                "c9a3f86aae465f0e56513864510f3997561fa2c9e85ea21dc2292309f3cd6022",
+            ),
+            (
+                3,
+                "d4b4f5784868c3020403246717ec169ff79e26608ea126a1ab69ee77d1b16712",
+            ),
+            (
+                7,
+                "b862409fb5c4c4123df2abf7462b88f041ad36dd6864ce872fd5472be363c5b1",
+            ),
+            (
+                1000,
+                "e7caaa83373a94afae43fec59b447c99ba282b19a7616c24c785ad8966a1e10e",
+            ),
+        ];
+        for &(k, hex) in multiples.iter() {
+            let p = b.scalar_mul(&Fr25519::from(k));
+            assert_eq!(p.compress(), from_hex(hex));
+            assert_eq!(decompress_hex(hex), Some(p));
+        }
+        assert_eq!(
+            b.scalar_mul(&-Fr25519::one()).compress(),
+            from_hex("58666666666666666666666666666666666666666666666666666666666666e6")
+        );
+    }
+
+    #[test]
+    fn rfc8032_public_key() {
+        // Test 1 of RFC 8032 section 7.1: the secret key
+        // 9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60 hashes to this clamped
+        // scalar, whose multiple of the base point is the public key.
+        let mut s = from_hex("4fe94d9006f020a5a3c080d96827fffd3c010ac0f12e7a42cb33284f86837c30");
+        s.reverse();
+        let limbs: Vec<u64> = s
+            .chunks(8)
+            .map(|c| c.iter().rev().fold(0, |acc, b| (acc << 8) | u64::from(*b)))
+            .collect();
+        assert_eq!(
+            Point::generator().mul_limbs(&limbs).compress(),
+            from_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+        );
+    }
+
+    #[test]
+    fn small_order_points() {
+        let identity =
+            decompress_hex("0100000000000000000000000000000000000000000000000000000000000000");
+        assert_eq!(identity, Some(Point::identity()));
+        // (0, -1) has order 2, and y = 0 gives the points of order 4.
+        let two =
+            decompress_hex("ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+                .unwrap();
+        let four =
+            decompress_hex("0000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap();
+        // One of the points of order 8.
+        let eight =
+            decompress_hex("26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05")
+                .unwrap();
+        assert!(!two.is_identity() && two.double().is_identity());
+        assert!(!four.double().is_identity() && four.double().double().is_identity());
+        assert!(!eight.double().double().is_identity());
+        for p in &[two, four, eight] {
+            assert!(p.is_on_curve() && p.is_small_order() && !p.is_in_subgroup());
+        }
+        let b = Point::generator();
+        let mixed = b + eight;
+        assert!(!mixed.is_in_subgroup());
+        assert_eq!(mixed.clear_cofactor(), b.mul_limbs(&[8]));
+        assert!(mixed.clear_cofactor().is_in_subgroup());
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        // y = p is not canonical.
+        assert_eq!(
+            decompress_hex("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f"),
+            None
+        );
+        // y = 2 has no x.
+        assert_eq!(
+            decompress_hex("0200000000000000000000000000000000000000000000000000000000000000"),
+            None
+        );
+        // x = 0 with the sign bit set.
+        assert_eq!(
+            decompress_hex("0100000000000000000000000000000000000000000000000000000000000080"),
+            None
+        );
+        assert_eq!(Point::decompress(&[]), None);
+        assert_eq!(decompress_hex("5866"), None);
+        assert!(Point::new(Fp25519::one(), Fp25519::one()).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn group_law(a in arb_scalar(), b in arb_scalar(), c in arb_scalar()) {
+            let g = Point::generator();
+            let (p, q, r) = (g.scalar_mul(&a), g.scalar_mul(&b), g.scalar_mul(&c));
+            prop_assert!(p.is_on_curve() && (p + q).is_on_curve());
+            prop_assert_eq!(p + q, q + p);
+            prop_assert_eq!((p + q) + r, p + (q + r));
+            prop_assert_eq!(p + p, p.double());
+            prop_assert_eq!(p.double(), g.scalar_mul(&(a + a)));
+            prop_assert_eq!(p + q, g.scalar_mul(&(a + b)));
+            prop_assert_eq!(p.scalar_mul(&b), g.scalar_mul(&(a * b)));
+            prop_assert!((p - p).is_identity());
+            prop_assert_eq!(p + Point::identity(), p);
+        }
+
+        #[test]
+        fn compression_roundtrips(a in arb_scalar()) {
+            let p = Point::generator().scalar_mul(&a);
+            let bytes = p.compress();
+            prop_assert_eq!(bytes.len(), 32);
+            prop_assert_eq!(Point::decompress(&bytes), Some(p));
+            prop_assert_eq!(Point::decompress(&(-p).compress()), Some(-p));
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! ElGamal encryption in the prime order subgroups of `subgroup` and `ffdhe`, in two flavours.
+//!
+//! * Multiplicative: a message `m` in the subgroup encrypts to `(g^r, m h^r)` for the public key
+//!   `h = g^x` and a fresh random `r`, and decrypts as `c2 / c1^x`. Multiplying two ciphertexts
+//!   componentwise encrypts the product of their messages.
+//! * Exponential: a message `m` mod q encrypts to `(g^r, g^m h^r)`, so the componentwise product
+//!   encrypts the sum of the messages, and raising a ciphertext to `k` encrypts `k m`. Decryption
+//!   only gets as far as `g^m`, and recovering `m` is a discrete log, so the messages have to be
+//!   small, like votes or counters. `decrypt_exponent` searches `0..=max` with baby-step
+//!   giant-step in about `2 sqrt(max)` multiplications.
+//!
+//! Both are only secure against passive attackers: anyone can maul a ciphertext into another one
+//! that decrypts to a related message.
+//!
+//! The secret key, the encryption randomness `r` and exponential messages only go through
+//! `SubgroupParams::exp_secret`, whose operations don't depend on the exponent, with the caveat
+//! on `PrimeField`. `scale` and `discrete_log` run in variable time.
+use field::{Field, PrimeField};
+use num_traits::{Inv, One};
+use rand::Rng;
+use std::collections::HashMap;
+use std::ops::Mul;
+use subgroup::SubgroupParams;
+
+/// An ElGamal ciphertext `(g^r, m h^r)` or `(g^r, g^m h^r)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ciphertext<E> {
+    pub c1: E,
+    pub c2: E,
+}
+
+///The componentwise product, which encrypts the product of multiplicative messages or the sum of
+///exponential ones.
+impl<E: Field> Mul for Ciphertext<E> {
+    type Output = Ciphertext<E>;
+
+    fn mul(self, other: Ciphertext<E>) -> Ciphertext<E> {
+        Ciphertext {
+            c1: self.c1 * other.c1,
+            c2: self.c2 * other.c2,
+        }
+    }
+}
+
+///Encrypt `message` to `public`. None unless `message` is a member of the subgroup, since which
+///coset it's in would show through the ciphertext.
+pub fn encrypt<P: SubgroupParams, R: Rng>(
+    public: &P::Element,
+    message: &P::Element,
+    rng: &mut R,
+) -> Option<Ciphertext<P::Element>> {
+    if !P::is_member(message) {
+        return None;
+    }
+    let r = P::Scalar::random(rng);
+    Some(Ciphertext {
+        c1: P::exp_secret(&P::generator(), &r),
+        c2: *message * P::exp_secret(public, &r),
+    })
+}
+
+///Decrypt a ciphertext from `encrypt`, as `c2 c1^(-secret)`.
+pub fn decrypt<P: SubgroupParams>(
+    secret: &P::Scalar,
+    ciphertext: &Ciphertext<P::Element>,
+) -> P::Element {
+    ciphertext.c2 * P::exp_secret(&ciphertext.c1, &-*secret)
+}
+
+///Encrypt `g^message` to `public`.
+pub fn encrypt_exponent<P: SubgroupParams, R: Rng>(
+    public: &P::Element,
+    message: &P::Scalar,
+    rng: &mut R,
+) -> Ciphertext<P::Element> {
+    let r = P::Scalar::random(rng);
+    let g = P::generator();
+    Ciphertext {
+        c1: P::exp_secret(&g, &r),
+        c2: P::exp_secret(&g, message) * P::exp_secret(public, &r),
+    }
+}
+
+///Decrypt a ciphertext from `encrypt_exponent`, or a product of them, to its message. None unless
+///the message is at most `max`.
+pub fn decrypt_exponent<P: SubgroupParams>(
+    secret: &P::Scalar,
+    ciphertext: &Ciphertext<P::Element>,
+    max: u64,
+) -> Option<u64> {
+    discrete_log::<P>(&decrypt::<P>(secret, ciphertext), max)
+}
+
+///Raise both parts of a ciphertext to `factor`. For an exponential ciphertext that multiplies the
+///message by `factor`; for a multiplicative one it raises the message to `factor`.
+pub fn scale<P: SubgroupParams>(
+    ciphertext: &Ciphertext<P::Element>,
+    factor: &P::Scalar,
+) -> Ciphertext<P::Element> {
+    Ciphertext {
+        c1: P::exp(&ciphertext.c1, factor),
+        c2: P::exp(&ciphertext.c2, factor),
+    }
+}
+
+///A fresh ciphertext of the same message, by multiplying in an encryption of 1 (or of 0, for
+///exponential ciphertexts), so it can't be linked to the original.
+pub fn rerandomize<P: SubgroupParams, R: Rng>(
+    public: &P::Element,
+    ciphertext: &Ciphertext<P::Element>,
+    rng: &mut R,
+) -> Ciphertext<P::Element> {
+    let r = P::Scalar::random(rng);
+    let one = Ciphertext {
+        c1: P::exp_secret(&P::generator(), &r),
+        c2: P::exp_secret(public, &r),
+    };
+    *ciphertext * one
+}
+
+///The `m` in `0..=max` with `g^m = x`, by baby-step giant-step: with `s = ceil(sqrt(max + 1))`,
+///it tabulates `g^j` for `j < s`, then multiplies `x` by `g^(-s)` until it lands in the table.
+///Takes time and memory proportional to `sqrt(max)`.
+pub fn discrete_log<P: SubgroupParams>(x: &P::Element, max: u64) -> Option<u64> {
+    let steps = (max as f64 + 1.0).sqrt().ceil() as u64;
+    let g = P::generator();
+    let mut table = HashMap::with_capacity(steps as usize);
+    let mut power = P::Element::one();
+    for j in 0..steps {
+        table.insert(power.to_limbs(), j);
+        power *= g;
+    }
+    // power is now g^steps.
+    let giant_step = power.inv();
+    let mut y = *x;
+    for i in 0..=max / steps {
+        if let Some(j) = table.get(&y.to_limbs()) {
+            let m = i * steps + j;
+            return if m <= max { Some(m) } else { None };
+        }
+        y *= giant_step;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dh::keypair;
+    use ffdhe::fp_ffdhe2048_q::FpFfdhe2048Q;
+    use ffdhe::Ffdhe2048;
+    use fp_safe_256::FpSafe256;
+    use fp_safe_256_q::FpSafe256Q;
+    use proptest::prelude::*;
+    use subgroup::SafePrime256;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn ffdhe2048_round_trip() {
+        let mut rng = rng_from_seed(48);
+        let (secret, public) = keypair::<Ffdhe2048, _>(&mut rng);
+        let message = Ffdhe2048::exp_generator(&FpFfdhe2048Q::from(31337u64));
+        let ciphertext = encrypt::<Ffdhe2048, _>(&public, &message, &mut rng).unwrap();
+        assert_eq!(decrypt::<Ffdhe2048>(&secret, &ciphertext), message);
+        let sum = encrypt_exponent::<Ffdhe2048, _>(&public, &FpFfdhe2048Q::from(40u64), &mut rng)
+            * encrypt_exponent::<Ffdhe2048, _>(&public, &FpFfdhe2048Q::from(2u64), &mut rng);
+        assert_eq!(decrypt_exponent::<Ffdhe2048>(&secret, &sum, 100), Some(42));
+    }
+
+    #[test]
+    fn rejects_non_members() {
+        let mut rng = rng_from_seed(49);
+        let (_, public) = keypair::<SafePrime256, _>(&mut rng);
+        // -1 isn't a square mod a safe prime.
+        let minus_one = -FpSafe256::one();
+        assert_eq!(
+            encrypt::<SafePrime256, _>(&public, &minus_one, &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn discrete_logs_up_to_max() {
+        let g = SafePrime256::generator();
+        for max in [0u64, 1, 2, 15, 16, 17, 1000].iter() {
+            for m in 0..=*max {
+                let x = g.pow_limbs(&[m]);
+                assert_eq!(discrete_log::<SafePrime256>(&x, *max), Some(m));
+            }
+            let x = g.pow_limbs(&[max + 1]);
+            assert_eq!(discrete_log::<SafePrime256>(&x, *max), None);
+        }
+        assert_eq!(
+            discrete_log::<SafePrime256>(&FpSafe256::from(3u64), 1000),
+            None
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn multiplicative_round_trip(seed in any::<u64>(), a in any::<u64>(), b in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            let (secret, public) = keypair::<SafePrime256, _>(&mut rng);
+            let a = SafePrime256::exp_generator(&FpSafe256Q::from(a));
+            let b = SafePrime256::exp_generator(&FpSafe256Q::from(b));
+            let ca = encrypt::<SafePrime256, _>(&public, &a, &mut rng).unwrap();
+            let cb = encrypt::<SafePrime256, _>(&public, &b, &mut rng).unwrap();
+            prop_assert_eq!(decrypt::<SafePrime256>(&secret, &ca), a);
+            prop_assert_eq!(decrypt::<SafePrime256>(&secret, &(ca * cb)), a * b);
+            let fresh = rerandomize::<SafePrime256, _>(&public, &ca, &mut rng);
+            prop_assert!(fresh != ca);
+            prop_assert_eq!(decrypt::<SafePrime256>(&secret, &fresh), a);
+        }
+
+        #[test]
+        fn exponential_is_additively_homomorphic(
+            seed in any::<u64>(),
+            a in 0u64..1000,
+            b in 0u64..1000,
+            k in 0u64..10,
+        ) {
+            let mut rng = rng_from_seed(seed);
+            let (secret, public) = keypair::<SafePrime256, _>(&mut rng);
+            let ca = encrypt_exponent::<SafePrime256, _>(&public, &FpSafe256Q::from(a), &mut rng);
+            let cb = encrypt_exponent::<SafePrime256, _>(&public, &FpSafe256Q::from(b), &mut rng);
+            prop_assert_eq!(decrypt_exponent::<SafePrime256>(&secret, &ca, 1000), Some(a));
+            let combined = scale::<SafePrime256>(&(ca * cb), &FpSafe256Q::from(k));
+            prop_assert_eq!(
+                decrypt_exponent::<SafePrime256>(&secret, &combined, 20000),
+                Some(k * (a + b))
+            );
+            let fresh = rerandomize::<SafePrime256, _>(&public, &ca, &mut rng);
+            prop_assert_eq!(decrypt_exponent::<SafePrime256>(&secret, &fresh, 1000), Some(a));
+        }
+    }
+}
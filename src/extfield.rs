@@ -0,0 +1,357 @@
+use field::PrimeField;
+use num_traits::{Inv, Pow};
+use poly::Poly;
+use rand::Rng;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::Arc;
+
+/// An element of the extension field Fp[x]/(f(x)) for a monic irreducible `f` chosen at runtime.
+/// The value is kept reduced mod `f`, and every element carries the (shared) modulus it was built
+/// with. Mixing elements with different moduli panics.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtField<F: PrimeField> {
+    value: Poly<F>,
+    modulus: Arc<Poly<F>>,
+}
+
+impl<F: PrimeField> fmt::Debug for ExtField<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExtField(")?;
+        let x: Vec<String> = self
+            .value
+            .coeffs()
+            .iter()
+            .map(|c| format!("{:?}", c))
+            .collect();
+        write!(f, "{}", x.join(", "))?;
+        write!(f, ")")
+    }
+}
+
+impl<F: PrimeField> ExtField<F> {
+    ///Create an element from a polynomial, reducing it mod `modulus`. `modulus` must be monic, of
+    ///degree at least 1, and irreducible for the result to be a field.
+    pub fn new(value: Poly<F>, modulus: Arc<Poly<F>>) -> ExtField<F> {
+        assert!(
+            modulus.coeffs().len() >= 2,
+            "The modulus must have degree at least 1."
+        );
+        assert!(
+            modulus.leading_coefficient().is_one(),
+            "The modulus must be monic."
+        );
+        let value = &value % &modulus;
+        ExtField { value, modulus }
+    }
+
+    ///Embed a base field element.
+    pub fn from_base(x: F, modulus: Arc<Poly<F>>) -> ExtField<F> {
+        ExtField::new(Poly::constant(x), modulus)
+    }
+
+    pub fn zero(modulus: Arc<Poly<F>>) -> ExtField<F> {
+        ExtField::new(Poly::zero(), modulus)
+    }
+
+    pub fn one(modulus: Arc<Poly<F>>) -> ExtField<F> {
+        ExtField::new(Poly::one(), modulus)
+    }
+
+    ///The element `x`, which generates the extension over Fp.
+    pub fn x(modulus: Arc<Poly<F>>) -> ExtField<F> {
+        ExtField::new(Poly::x(), modulus)
+    }
+
+    pub fn random<R: Rng>(modulus: Arc<Poly<F>>, rng: &mut R) -> ExtField<F> {
+        let coeffs = (0..modulus.coeffs().len() - 1)
+            .map(|_| F::random(rng))
+            .collect();
+        ExtField::new(Poly::new(coeffs), modulus)
+    }
+
+    ///Degree of the extension, which is the degree of the modulus.
+    pub fn degree(&self) -> usize {
+        self.modulus.coeffs().len() - 1
+    }
+
+    ///The reduced representative, of degree less than the modulus.
+    pub fn value(&self) -> &Poly<F> {
+        &self.value
+    }
+
+    pub fn modulus(&self) -> &Arc<Poly<F>> {
+        &self.modulus
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.value.is_one()
+    }
+
+    pub fn square(&self) -> ExtField<F> {
+        ExtField {
+            value: &(&self.value * &self.value) % &self.modulus,
+            modulus: self.modulus.clone(),
+        }
+    }
+
+    ///Raise the value to an exponent given in limbs, least significant first.
+    pub fn pow_limbs(&self, exp: &[u64]) -> ExtField<F> {
+        ExtField {
+            value: self.value.pow_mod(exp, &self.modulus),
+            modulus: self.modulus.clone(),
+        }
+    }
+
+    ///The Frobenius endomorphism `a -> a^p`.
+    pub fn frobenius(&self) -> ExtField<F> {
+        self.pow_limbs(F::PRIME)
+    }
+
+    ///Apply the Frobenius endomorphism `n` times, `a -> a^(p^n)`.
+    pub fn frobenius_n(&self, n: usize) -> ExtField<F> {
+        (0..n).fold(self.clone(), |acc, _| acc.frobenius())
+    }
+
+    fn check_modulus(&self, other: &ExtField<F>) {
+        assert!(
+            Arc::ptr_eq(&self.modulus, &other.modulus) || self.modulus == other.modulus,
+            "Extension field elements must share a modulus."
+        );
+    }
+}
+
+impl<F: PrimeField> Add for ExtField<F> {
+    type Output = ExtField<F>;
+    fn add(self, other: ExtField<F>) -> ExtField<F> {
+        self.check_modulus(&other);
+        ExtField {
+            value: self.value + other.value,
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<F: PrimeField> Sub for ExtField<F> {
+    type Output = ExtField<F>;
+    fn sub(self, other: ExtField<F>) -> ExtField<F> {
+        self.check_modulus(&other);
+        ExtField {
+            value: self.value - other.value,
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<F: PrimeField> Neg for ExtField<F> {
+    type Output = ExtField<F>;
+    fn neg(self) -> ExtField<F> {
+        ExtField {
+            value: -self.value,
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<F: PrimeField> Mul for ExtField<F> {
+    type Output = ExtField<F>;
+    fn mul(self, other: ExtField<F>) -> ExtField<F> {
+        self.check_modulus(&other);
+        ExtField {
+            value: &(&self.value * &other.value) % &self.modulus,
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<F: PrimeField> Mul<F> for ExtField<F> {
+    type Output = ExtField<F>;
+    fn mul(self, other: F) -> ExtField<F> {
+        ExtField {
+            value: self.value.scale(other),
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl<F: PrimeField> Inv for ExtField<F> {
+    type Output = ExtField<F>;
+    ///Inversion via the extended Euclidean algorithm on polynomials: with `s*a + t*f = 1` we have
+    ///`a^-1 = s mod f`.
+    fn inv(self) -> ExtField<F> {
+        assert!(!self.is_zero(), "You cannot divide by zero.");
+        let (g, s, _) = self.value.ext_gcd(&self.modulus);
+        assert!(
+            g.is_one(),
+            "Element is not invertible; the modulus is not irreducible."
+        );
+        ExtField::new(s, self.modulus)
+    }
+}
+
+impl<F: PrimeField> Div for ExtField<F> {
+    type Output = ExtField<F>;
+    fn div(self, rhs: ExtField<F>) -> ExtField<F> {
+        let inverse = rhs.inv();
+        self.mul(inverse)
+    }
+}
+
+impl<F: PrimeField> Pow<u64> for ExtField<F> {
+    type Output = ExtField<F>;
+    fn pow(self, rhs: u64) -> ExtField<F> {
+        self.pow_limbs(&[rhs])
+    }
+}
+
+///Check irreducibility of a monic polynomial with Rabin's test: `f` of degree `n` is irreducible
+///iff `x^(p^n) = x (mod f)` and `gcd(x^(p^(n/q)) - x, f) = 1` for every prime `q` dividing `n`.
+pub fn is_irreducible<F: PrimeField>(modulus: &Poly<F>) -> bool {
+    let n = match modulus.degree() {
+        Some(0) | None => return false,
+        Some(n) => n,
+    };
+    if n == 1 {
+        return true;
+    }
+    let modulus = Arc::new(modulus.monic());
+    let x = ExtField::x(modulus.clone());
+    // The Frobenius map is linear over Fp, so after one exponentiation to find x^p every later
+    // application is just a combination of the powers of x^p.
+    let xp = x.frobenius();
+    let mut xp_powers = vec![ExtField::one(modulus.clone())];
+    for i in 1..n {
+        let next = xp_powers[i - 1].clone() * xp.clone();
+        xp_powers.push(next);
+    }
+    let frobenius = |a: &ExtField<F>| {
+        a.value
+            .coeffs()
+            .iter()
+            .zip(xp_powers.iter())
+            .fold(ExtField::zero(modulus.clone()), |acc, (c, power)| {
+                acc + power.clone() * *c
+            })
+    };
+    let mut frobenius_powers = vec![x.clone()];
+    for i in 0..n {
+        let next = frobenius(&frobenius_powers[i]);
+        frobenius_powers.push(next);
+    }
+    if frobenius_powers[n] != x {
+        return false;
+    }
+    prime_factors(n).into_iter().all(|q| {
+        let diff = frobenius_powers[n / q].clone() - x.clone();
+        diff.value.gcd(&modulus).is_one()
+    })
+}
+
+///Find a random monic irreducible polynomial of the given degree. Roughly one in `degree` random
+///monic polynomials is irreducible.
+pub fn random_irreducible<F: PrimeField, R: Rng>(degree: usize, rng: &mut R) -> Poly<F> {
+    assert!(degree >= 1, "Degree must be at least 1.");
+    loop {
+        let mut coeffs: Vec<F> = (0..degree).map(|_| F::random(rng)).collect();
+        coeffs.push(F::one());
+        let candidate = Poly::new(coeffs);
+        if is_irreducible(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut factors = vec![];
+    let mut q = 2;
+    while q * q <= n {
+        if n.is_multiple_of(q) {
+            factors.push(q);
+            while n.is_multiple_of(q) {
+                n /= q;
+            }
+        }
+        q += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn known_irreducibles() {
+        // p = 3 mod 4 for Fp256, so x^2 + 1 is irreducible and x^2 - 1 is not
+        let x_squared_plus_one = Poly::new(vec![Fp256::from(1u64), 0u64.into(), 1u64.into()]);
+        assert!(is_irreducible(&x_squared_plus_one));
+        assert!(!is_irreducible(&Poly::new(vec![
+            Fp256::from(-1i64),
+            0u64.into(),
+            1u64.into()
+        ])));
+        // (x^2 + 1)(x^2 + 1) is reducible even though it has no roots
+        assert!(!is_irreducible(
+            &(&x_squared_plus_one * &x_squared_plus_one)
+        ));
+    }
+
+    #[test]
+    fn complex_multiplication() {
+        // Fp256[x]/(x^2 + 1) behaves like the complex numbers
+        let modulus = Arc::new(Poly::new(vec![Fp256::from(1u64), 0u64.into(), 1u64.into()]));
+        let i = ExtField::x(modulus.clone());
+        assert_eq!(i.square(), -ExtField::one(modulus.clone()));
+        assert_eq!(i.clone().pow(4), ExtField::one(modulus.clone()));
+        // conjugation is the Frobenius map
+        assert_eq!(i.frobenius(), -i);
+    }
+
+    proptest! {
+        #[test]
+        fn mul_inv_is_one(seed in any::<u64>(), degree in 1usize..5) {
+            let mut rng = rng_from_seed(seed);
+            let modulus = Arc::new(random_irreducible::<Fp256, _>(degree, &mut rng));
+            let a = ExtField::random(modulus.clone(), &mut rng);
+            prop_assume!(!a.is_zero());
+            prop_assert!((a.clone() * a.clone().inv()).is_one());
+            prop_assert_eq!(a.clone() / a, ExtField::one(modulus));
+        }
+
+        #[test]
+        fn field_axioms(seed in any::<u64>(), degree in 1usize..4) {
+            let mut rng = rng_from_seed(seed);
+            let modulus = Arc::new(random_irreducible::<Fp480, _>(degree, &mut rng));
+            let a = ExtField::random(modulus.clone(), &mut rng);
+            let b = ExtField::random(modulus.clone(), &mut rng);
+            let c = ExtField::random(modulus.clone(), &mut rng);
+            prop_assert_eq!(a.clone() * b.clone(), b.clone() * a.clone());
+            prop_assert_eq!((a.clone() * b.clone()) * c.clone(), a.clone() * (b.clone() * c.clone()));
+            prop_assert_eq!(a.clone() * (b.clone() + c.clone()), a.clone() * b.clone() + a.clone() * c.clone());
+            prop_assert_eq!(a.clone() - a.clone(), ExtField::zero(modulus));
+            prop_assert_eq!(a.clone().pow(3), a.clone() * a.clone() * a);
+        }
+
+        #[test]
+        fn frobenius_has_order_degree(seed in any::<u64>(), degree in 1usize..4) {
+            let mut rng = rng_from_seed(seed);
+            let modulus = Arc::new(random_irreducible::<Fp256, _>(degree, &mut rng));
+            let a = ExtField::random(modulus.clone(), &mut rng);
+            let b = ExtField::random(modulus, &mut rng);
+            prop_assert_eq!(a.frobenius_n(degree), a.clone());
+            // Frobenius is a field homomorphism
+            prop_assert_eq!((a.clone() * b.clone()).frobenius(), a.frobenius() * b.frobenius());
+        }
+    }
+}
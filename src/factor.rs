@@ -0,0 +1,292 @@
+//! Factoring univariate polynomials over prime fields: square-free decomposition, distinct-degree
+//! factorization and Cantor-Zassenhaus equal-degree splitting, which together give `factor` and
+//! `roots`.
+//!
+//! Everything here assumes the degree of the polynomial is less than p, which is always true for
+//! the fields this crate works with. (Otherwise a non-constant polynomial can have a zero
+//! derivative and the square-free step would need p-th roots.)
+use field::PrimeField;
+use poly::Poly;
+use rand::Rng;
+
+impl<F: PrimeField> Poly<F> {
+    ///Split a non-zero polynomial into pairwise coprime, monic, square-free factors `(g, i)` such that
+    ///the monic version of `self` is the product of the `g^i`. Factors which would be 1 are left out.
+    pub fn squarefree_decomposition(&self) -> Vec<(Poly<F>, usize)> {
+        assert!(!self.is_zero(), "The zero polynomial has no factorization.");
+        // Yun's algorithm
+        let f = self.monic();
+        let mut ret = Vec::new();
+        let mut g = f.gcd(&f.derivative());
+        let mut w = &f / &g;
+        let mut i = 1;
+        while w.degree().is_some_and(|d| d > 0) {
+            let y = w.gcd(&g);
+            let factor = &w / &y;
+            if !factor.is_one() {
+                ret.push((factor, i));
+            }
+            g = &g / &y;
+            w = y;
+            i += 1;
+        }
+        ret
+    }
+
+    ///Distinct-degree factorization of a monic, square-free polynomial. Each `(g, d)` in the result
+    ///is the product of all of the irreducible factors of degree `d`.
+    pub fn distinct_degree_factorization(&self) -> Vec<(Poly<F>, usize)> {
+        let mut f = self.monic();
+        let mut ret = Vec::new();
+        let x = Poly::x();
+        // h = x^(p^i) mod f
+        let mut h = &x % &f;
+        let mut i = 1;
+        while f.degree().is_some_and(|d| d >= 2 * i) {
+            h = h.pow_mod(F::PRIME, &f);
+            let g = f.gcd(&(&h - &x));
+            if !g.is_one() {
+                f = &f / &g;
+                h = &h % &f;
+                ret.push((g, i));
+            }
+            i += 1;
+        }
+        if let Some(d) = f.degree() {
+            if d > 0 {
+                ret.push((f, d));
+            }
+        }
+        ret
+    }
+
+    ///Cantor-Zassenhaus: split a monic, square-free polynomial whose irreducible factors all have
+    ///degree `degree` into those factors.
+    pub fn equal_degree_factorization<R: Rng>(&self, degree: usize, rng: &mut R) -> Vec<Poly<F>> {
+        let f = self.monic();
+        let n = f.degree().unwrap_or(0);
+        assert!(
+            degree > 0 && n.is_multiple_of(degree),
+            "The degree of the polynomial must be a multiple of the factor degree."
+        );
+        if n == degree {
+            return vec![f];
+        }
+        let exp = half_prime_power_minus_one::<F>(degree);
+        loop {
+            // A random a which is non-constant mod f. For each irreducible factor h, a^((p^d - 1)/2)
+            // mod h is 1 or -1 with (almost) even odds, so gcd(a^((p^d - 1)/2) - 1, f) splits f
+            // with probability about 1/2.
+            let a = Poly::new((0..n).map(|_| F::random(rng)).collect());
+            if a.degree().is_none_or(|d| d == 0) {
+                continue;
+            }
+            let mut g = f.gcd(&a);
+            if g.is_one() {
+                g = f.gcd(&(&a.pow_mod(&exp, &f) - &Poly::one()));
+            }
+            if g.degree().is_some_and(|d| d > 0 && d < n) {
+                let mut ret = g.equal_degree_factorization(degree, rng);
+                ret.extend((&f / &g).equal_degree_factorization(degree, rng));
+                return ret;
+            }
+        }
+    }
+
+    ///Factor a non-zero polynomial into monic irreducible factors with their multiplicities.
+    ///The leading coefficient is dropped, and the order of the factors isn't specified.
+    pub fn factor<R: Rng>(&self, rng: &mut R) -> Vec<(Poly<F>, usize)> {
+        let mut ret = Vec::new();
+        for (squarefree, multiplicity) in self.squarefree_decomposition() {
+            for (same_degree, degree) in squarefree.distinct_degree_factorization() {
+                for factor in same_degree.equal_degree_factorization(degree, rng) {
+                    ret.push((factor, multiplicity));
+                }
+            }
+        }
+        ret
+    }
+
+    ///Every distinct root of the polynomial in the field, in ascending order. `rng` drives the
+    ///splitting as in `factor`, so it changes how long this takes but not the answer. Panics on
+    ///the zero polynomial, since every element would be a root.
+    pub fn roots<R: Rng>(&self, rng: &mut R) -> Vec<F> {
+        assert!(
+            !self.is_zero(),
+            "Every element is a root of the zero polynomial."
+        );
+        // gcd(f, x^p - x) is the product of the distinct linear factors of f.
+        let f = self.monic();
+        let x = Poly::x();
+        let x_to_p = x.pow_mod(F::PRIME, &f);
+        let linear = f.gcd(&(&x_to_p - &x));
+        let mut ret = Vec::new();
+        if linear.degree().is_some_and(|d| d > 0) {
+            split_linear(&linear, rng, &mut ret);
+        }
+        ret.sort();
+        ret
+    }
+}
+
+///Push the roots of a monic polynomial which is a product of distinct linear factors.
+fn split_linear<F: PrimeField, R: Rng>(f: &Poly<F>, rng: &mut R, roots: &mut Vec<F>) {
+    match f.degree() {
+        Some(1) => roots.push(-f.coeff(0)),
+        Some(2) => {
+            // x^2 + bx + c has roots (-b +- sqrt(b^2 - 4c)) / 2. The discriminant is a
+            // quadratic residue because the roots are distinct and in the field.
+            let b = f.coeff(1);
+            let c = f.coeff(0);
+            let discriminant = b.square() - F::from(4u64) * c;
+            let root = discriminant
+                .sqrt()
+                .expect("Discriminant of a split quadratic must be a square.");
+            let half = F::from(2u64).inv();
+            roots.push((root - b) * half);
+            roots.push((-root - b) * half);
+        }
+        _ => {
+            for factor in f.equal_degree_factorization(1, rng) {
+                roots.push(-factor.coeff(0));
+            }
+        }
+    }
+}
+
+///(p^degree - 1) / 2 in limbs, least significant first.
+fn half_prime_power_minus_one<F: PrimeField>(degree: usize) -> Vec<u64> {
+    let mut power = vec![1u64];
+    for _ in 0..degree {
+        power = mul_limbs(&power, F::PRIME);
+    }
+    // p^degree is odd, so subtracting one just clears the bottom bit.
+    power[0] &= !1;
+    for i in 0..power.len() {
+        let high = power.get(i + 1).map_or(0, |next| next << 63);
+        power[i] = (power[i] >> 1) | high;
+    }
+    power
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut ret = vec![0u64; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, y) in b.iter().enumerate() {
+            let t = u128::from(*x) * u128::from(*y) + u128::from(ret[i + j]) + carry;
+            ret[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        ret[i + b.len()] = carry as u64;
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extfield::is_irreducible;
+    use fp_255::Fp255;
+    use fp_256::Fp256;
+    use num_traits::One;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+
+    fn p(coeffs: &[i64]) -> Poly<Fp256> {
+        Poly::new(coeffs.iter().map(|&c| Fp256::from(c)).collect())
+    }
+
+    #[test]
+    fn half_prime_power() {
+        // 2 * ((p - 1) / 2) + 1 = p
+        let half = half_prime_power_minus_one::<Fp255>(1);
+        let mut doubled = mul_limbs(&half, &[2]);
+        doubled[0] += 1;
+        assert_eq!(&doubled[..4], Fp255::PRIME);
+        assert_eq!(half_prime_power_minus_one::<Fp256>(3).len(), 13);
+    }
+
+    #[test]
+    fn roots_of_known_polynomials() {
+        // (x - 1)(x - 2)^2(x + 5)(x^2 + 1), where x^2 + 1 has no roots since p256 = 3 mod 4.
+        let f = p(&[-1, 1]) * p(&[-2, 1]) * p(&[-2, 1]) * p(&[5, 1]) * p(&[1, 0, 1]);
+        let mut expected = vec![Fp256::from(1u64), Fp256::from(2u64), Fp256::from(-5i64)];
+        expected.sort();
+        let mut rng = rng_from_seed(29);
+        assert_eq!(f.scale(Fp256::from(7u64)).roots(&mut rng), expected);
+        assert_eq!(p(&[1, 0, 1]).roots(&mut rng), vec![]);
+        assert_eq!(p(&[3]).roots(&mut rng), vec![]);
+        assert_eq!(p(&[0, 0, 1]).roots(&mut rng), vec![Fp256::from(0u64)]);
+    }
+
+    #[test]
+    fn factor_known_polynomial() {
+        let mut rng = rng_from_seed(29);
+        let f = p(&[-1, 1]) * p(&[1, 0, 1]) * p(&[1, 0, 1]) * p(&[3, 1]);
+        let mut factors = f.factor(&mut rng);
+        factors.sort_by_key(|(g, i)| (g.degree(), *i, g.coeff(0)));
+        assert_eq!(
+            factors,
+            vec![(p(&[3, 1]), 1), (p(&[-1, 1]), 1), (p(&[1, 0, 1]), 2)]
+        );
+    }
+
+    #[test]
+    fn distinct_degree_groups_factors() {
+        // x^2 + 1 and x^2 + 2 are both irreducible mod p256, since -1 and -2 are non-residues.
+        let quadratics = p(&[1, 0, 1]) * p(&[2, 0, 1]);
+        let f = &quadratics * &p(&[4, 1]);
+        assert_eq!(
+            f.distinct_degree_factorization(),
+            vec![(p(&[4, 1]), 1), (quadratics, 2)]
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn roots_of_product_of_linears(
+            ref rs in vec(any::<u64>(), 0..8),
+            lead in 1u64..,
+            seed in any::<u64>(),
+        ) {
+            let mut f = Poly::constant(Fp255::from(lead));
+            for r in rs {
+                f = &f * &Poly::new(vec![-Fp255::from(*r), Fp255::one()]);
+            }
+            let mut expected: Vec<Fp255> = rs.iter().map(|r| Fp255::from(*r)).collect();
+            expected.sort();
+            expected.dedup();
+            prop_assert_eq!(f.roots(&mut rng_from_seed(seed)), expected);
+        }
+
+        #[test]
+        fn factors_are_irreducible_and_multiply_back(
+            ref a in vec(any::<i64>(), 1..4),
+            ref b in vec(any::<i64>(), 1..6),
+            seed in any::<u64>(),
+        ) {
+            let monic = |coeffs: &Vec<i64>| {
+                let mut coeffs: Vec<Fp256> = coeffs.iter().map(|c| Fp256::from(*c)).collect();
+                coeffs.push(Fp256::one());
+                Poly::new(coeffs)
+            };
+            // a repeated factor gives the square-free step something to do
+            let a = monic(a);
+            let f = &(&a * &a) * &monic(b);
+            let mut rng = rng_from_seed(seed);
+            let mut product = Poly::one();
+            for (g, i) in f.factor(&mut rng) {
+                prop_assert!(is_irreducible(&g));
+                prop_assert!(g.leading_coefficient().is_one());
+                for _ in 0..i {
+                    product = &product * &g;
+                }
+            }
+            prop_assert_eq!(product, f);
+        }
+    }
+}
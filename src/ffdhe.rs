@@ -0,0 +1,2197 @@
+//! The finite field Diffie-Hellman groups of RFC 7919, `ffdhe2048` through `ffdhe8192`. Each p
+//! is a safe prime, so the squares mod p form a subgroup of prime order `q = (p - 1) / 2`, and
+//! it's generated by 2. The primes are pinned to the digits of e, so nobody could have picked them
+//! to hide a trapdoor. Each group also has a hashed second generator for Pedersen commitments in
+//! `vss`.
+use self::fp_ffdhe2048::FpFfdhe2048;
+use self::fp_ffdhe2048_q::FpFfdhe2048Q;
+use self::fp_ffdhe3072::FpFfdhe3072;
+use self::fp_ffdhe3072_q::FpFfdhe3072Q;
+use self::fp_ffdhe4096::FpFfdhe4096;
+use self::fp_ffdhe4096_q::FpFfdhe4096Q;
+use self::fp_ffdhe6144::FpFfdhe6144;
+use self::fp_ffdhe6144_q::FpFfdhe6144Q;
+use self::fp_ffdhe8192::FpFfdhe8192;
+use self::fp_ffdhe8192_q::FpFfdhe8192Q;
+use subgroup::SubgroupParams;
+use vss::PedersenParams;
+
+// p = 2^2048 - 2^1984 + (floor(2^1918 e) + 560316) 2^64 - 1
+fp!(
+    fp_ffdhe2048, // Name of mod
+    FpFfdhe2048,  // Name of class
+    2048,         // Number of bits for prime
+    32,           // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0x886b423861285c97, // get this from sage with p.digits(2^64)
+        0xc6f34a26c1b2effa,
+        0xc58ef1837d1683b2,
+        0x3bb5fcbc2ec22005,
+        0xc3fe3b1b4c6fad73,
+        0x8e4f1232eef28183,
+        0x9172fe9ce98583ff,
+        0xc03404cd28342f61,
+        0x9e02fce1cdf7e2ec,
+        0x0b07a7c8ee0a6d70,
+        0xae56ede76372bb19,
+        0x1d4f42a3de394df4,
+        0xb96adab760d7f468,
+        0xd108a94bb2c8e3fb,
+        0xbc0ab182b324fb61,
+        0x30acca4f483a797a,
+        0x1df158a136ade735,
+        0xe2a689daf3efe872,
+        0x984f0c70e0e68b77,
+        0xb557135e7f57c935,
+        0x856365553ded1af3,
+        0x2433f51f5f066ed0,
+        0xd3df1ed5d5fd6561,
+        0xf681b202aec4617a,
+        0x7d2fe363630c75d8,
+        0xcc939dce249b3ef9,
+        0xa9e13641146433fb,
+        0xd8b9c583ce2d3695,
+        0xafdc5620273d3cf1,
+        0xadf85458a2bb4a9a,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        1764253731295743905,
+        4355924517621471056,
+        11132381281931089680,
+        12396246310882146679,
+        3629020674666680926,
+        14859402785522170117,
+        7580449722333152353,
+        5132862210093399145,
+        10992739607770930607,
+        8037223596897747871,
+        14123619590052141742,
+        15039949459419906370,
+        4868927981316416008,
+        15911404266142908920,
+        17374484505052125826,
+        17584491368562418634,
+        11294255830758471079,
+        6009500166972758852,
+        17817411935113381583,
+        1317345750958594943,
+        9546972268681034615,
+        11512592695251473851,
+        17752560413190683472,
+        4677341488290290400,
+        7918371098303007340,
+        14281778396543219458,
+        13175027533522516316,
+        14008877247416026660,
+        11423551290981559399,
+        7668667070111999241,
+        5910881771259737445,
+        0,
+        1
+    ]
+);
+
+// q = (p - 1) / 2 for the p of fp_ffdhe2048
+fp!(
+    fp_ffdhe2048_q, // Name of mod
+    FpFfdhe2048Q,   // Name of class
+    2047,           // Number of bits for prime
+    32,             // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0x4435a11c30942e4b, // get this from sage with p.digits(2^64)
+        0x6379a51360d977fd,
+        0xe2c778c1be8b41d9,
+        0x9ddafe5e17611002,
+        0xe1ff1d8da637d6b9,
+        0xc7278919777940c1,
+        0xc8b97f4e74c2c1ff,
+        0x601a0266941a17b0,
+        0x4f017e70e6fbf176,
+        0x8583d3e4770536b8,
+        0x572b76f3b1b95d8c,
+        0x0ea7a151ef1ca6fa,
+        0xdcb56d5bb06bfa34,
+        0xe88454a5d96471fd,
+        0x5e0558c159927db0,
+        0x98566527a41d3cbd,
+        0x0ef8ac509b56f39a,
+        0xf15344ed79f7f439,
+        0xcc278638707345bb,
+        0xdaab89af3fabe49a,
+        0x42b1b2aa9ef68d79,
+        0x9219fa8faf833768,
+        0x69ef8f6aeafeb2b0,
+        0x7b40d901576230bd,
+        0xbe97f1b1b1863aec,
+        0xe649cee7124d9f7c,
+        0xd4f09b208a3219fd,
+        0xec5ce2c1e7169b4a,
+        0x57ee2b10139e9e78,
+        0xd6fc2a2c515da54d,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        3528507462591487812,
+        8711849035242942112,
+        3818018490152627744,
+        6345748548054741743,
+        7258041349333361853,
+        11272061497334788618,
+        15160899444666304707,
+        10265724420186798290,
+        3538735141832309598,
+        16074447193795495743,
+        9800495106394731868,
+        11633154845130261125,
+        9737855962632832017,
+        13376064458576266224,
+        16302224936394700037,
+        16722238663415285653,
+        4141767587807390543,
+        12019000333945517705,
+        17188079796517211550,
+        2634691501917189887,
+        647200463652517614,
+        4578441316793396087,
+        17058376752671815329,
+        9354682976580580801,
+        15836742196606014680,
+        10116812719376887300,
+        7903310993335481017,
+        9571010421122501705,
+        4400358508253567183,
+        15337334140223998483,
+        11821763542519474890,
+        0,
+        2
+    ]
+);
+
+// p = 2^3072 - 2^3008 + (floor(2^2942 e) + 2625351) 2^64 - 1
+fp!(
+    fp_ffdhe3072, // Name of mod
+    FpFfdhe3072,  // Name of class
+    3072,         // Number of bits for prime
+    48,           // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0x25e41d2b66c62e37, // get this from sage with p.digits(2^64)
+        0x3c1b20ee3fd59d7c,
+        0x0abcd06bfa53ddef,
+        0x1dbf9a42d5c4484e,
+        0xabc521979b0deada,
+        0xe86d2bc522363a0d,
+        0x5cae82ab9c9df69e,
+        0x64f2e21e71f54bff,
+        0xf4fd4452e2d74dd3,
+        0xb4130c93bc437944,
+        0xaefe130985139270,
+        0x598cb0fac186d91c,
+        0x7ad91d2691f7f7ee,
+        0x61b46fc9d6e6c907,
+        0xbc34f4def99c0238,
+        0xde355b3b6519035b,
+        0x886b4238611fcfdc,
+        0xc6f34a26c1b2effa,
+        0xc58ef1837d1683b2,
+        0x3bb5fcbc2ec22005,
+        0xc3fe3b1b4c6fad73,
+        0x8e4f1232eef28183,
+        0x9172fe9ce98583ff,
+        0xc03404cd28342f61,
+        0x9e02fce1cdf7e2ec,
+        0x0b07a7c8ee0a6d70,
+        0xae56ede76372bb19,
+        0x1d4f42a3de394df4,
+        0xb96adab760d7f468,
+        0xd108a94bb2c8e3fb,
+        0xbc0ab182b324fb61,
+        0x30acca4f483a797a,
+        0x1df158a136ade735,
+        0xe2a689daf3efe872,
+        0x984f0c70e0e68b77,
+        0xb557135e7f57c935,
+        0x856365553ded1af3,
+        0x2433f51f5f066ed0,
+        0xd3df1ed5d5fd6561,
+        0xf681b202aec4617a,
+        0x7d2fe363630c75d8,
+        0xcc939dce249b3ef9,
+        0xa9e13641146433fb,
+        0xd8b9c583ce2d3695,
+        0xafdc5620273d3cf1,
+        0xadf85458a2bb4a9a,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        18021261575510955360,
+        14571473877336601564,
+        3237263948324516711,
+        4948338266401566519,
+        6161896350742667575,
+        858112407984594266,
+        17843490455790718513,
+        6307032531170371647,
+        9080913782202917313,
+        2016611193290243196,
+        11723899600515801774,
+        2072493539101642121,
+        15656631083987996497,
+        12968253236851404938,
+        6601165481780564182,
+        9513959213515467417,
+        4199193444162651887,
+        4355924517622031371,
+        11132381281931089680,
+        12396246310882146679,
+        3629020674666680926,
+        14859402785522170117,
+        7580449722333152353,
+        5132862210093399145,
+        10992739607770930607,
+        8037223596897747871,
+        14123619590052141742,
+        15039949459419906370,
+        4868927981316416008,
+        15911404266142908920,
+        17374484505052125826,
+        17584491368562418634,
+        11294255830758471079,
+        6009500166972758852,
+        17817411935113381583,
+        1317345750958594943,
+        9546972268681034615,
+        11512592695251473851,
+        17752560413190683472,
+        4677341488290290400,
+        7918371098303007340,
+        14281778396543219458,
+        13175027533522516316,
+        14008877247416026660,
+        11423551290981559399,
+        7668667070111999241,
+        5910881771259737445,
+        0,
+        1
+    ]
+);
+
+// q = (p - 1) / 2 for the p of fp_ffdhe3072
+fp!(
+    fp_ffdhe3072_q, // Name of mod
+    FpFfdhe3072Q,   // Name of class
+    3071,           // Number of bits for prime
+    48,             // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0x12f20e95b363171b, // get this from sage with p.digits(2^64)
+        0x9e0d90771feacebe,
+        0x055e6835fd29eef7,
+        0x0edfcd216ae22427,
+        0xd5e290cbcd86f56d,
+        0x743695e2911b1d06,
+        0xae574155ce4efb4f,
+        0xb279710f38faa5ff,
+        0x7a7ea229716ba6e9,
+        0x5a098649de21bca2,
+        0x577f0984c289c938,
+        0x2cc6587d60c36c8e,
+        0xbd6c8e9348fbfbf7,
+        0x30da37e4eb736483,
+        0xde1a7a6f7cce011c,
+        0x6f1aad9db28c81ad,
+        0x4435a11c308fe7ee,
+        0x6379a51360d977fd,
+        0xe2c778c1be8b41d9,
+        0x9ddafe5e17611002,
+        0xe1ff1d8da637d6b9,
+        0xc7278919777940c1,
+        0xc8b97f4e74c2c1ff,
+        0x601a0266941a17b0,
+        0x4f017e70e6fbf176,
+        0x8583d3e4770536b8,
+        0x572b76f3b1b95d8c,
+        0x0ea7a151ef1ca6fa,
+        0xdcb56d5bb06bfa34,
+        0xe88454a5d96471fd,
+        0x5e0558c159927db0,
+        0x98566527a41d3cbd,
+        0x0ef8ac509b56f39a,
+        0xf15344ed79f7f439,
+        0xcc278638707345bb,
+        0xdaab89af3fabe49a,
+        0x42b1b2aa9ef68d79,
+        0x9219fa8faf833768,
+        0x69ef8f6aeafeb2b0,
+        0x7b40d901576230bd,
+        0xbe97f1b1b1863aec,
+        0xe649cee7124d9f7c,
+        0xd4f09b208a3219fd,
+        0xec5ce2c1e7169b4a,
+        0x57ee2b10139e9e78,
+        0xd6fc2a2c515da54d,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        17595779077312359107,
+        10696203680963651513,
+        6474527896649033423,
+        9896676532803133038,
+        12323792701485335150,
+        1716224815969188532,
+        17240236837871885410,
+        12614065062340743295,
+        18161827564405834626,
+        4033222386580486392,
+        5001055127322051932,
+        4144987078203284243,
+        12866518094266441378,
+        7489762399993258261,
+        13202330963561128365,
+        581174353321383218,
+        8398386888325303775,
+        8711849035244062742,
+        3818018490152627744,
+        6345748548054741743,
+        7258041349333361853,
+        11272061497334788618,
+        15160899444666304707,
+        10265724420186798290,
+        3538735141832309598,
+        16074447193795495743,
+        9800495106394731868,
+        11633154845130261125,
+        9737855962632832017,
+        13376064458576266224,
+        16302224936394700037,
+        16722238663415285653,
+        4141767587807390543,
+        12019000333945517705,
+        17188079796517211550,
+        2634691501917189887,
+        647200463652517614,
+        4578441316793396087,
+        17058376752671815329,
+        9354682976580580801,
+        15836742196606014680,
+        10116812719376887300,
+        7903310993335481017,
+        9571010421122501705,
+        4400358508253567183,
+        15337334140223998483,
+        11821763542519474890,
+        0,
+        2
+    ]
+);
+
+// p = 2^4096 - 2^4032 + (floor(2^3966 e) + 5736041) 2^64 - 1
+fp!(
+    fp_ffdhe4096, // Name of mod
+    FpFfdhe4096,  // Name of class
+    4096,         // Number of bits for prime
+    64,           // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0xc68a007e5e655f6a, // get this from sage with p.digits(2^64)
+        0x4db5a851f44182e1,
+        0x8ec9b55a7f88a46b,
+        0x0a8291cdcec97dcf,
+        0x2a4ecea9f98d0acc,
+        0x1a1db93d7140003c,
+        0x092999a333cb8b7a,
+        0x6dc778f971ad0038,
+        0xa907600a918130c4,
+        0xed6a1e012d9e6832,
+        0x7135c886efb4318a,
+        0x87f55ba57e31cc7a,
+        0x7763cf1d55034004,
+        0xac7d5f42d69f6d18,
+        0x7930e9e4e58857b6,
+        0x6e6f52c3164df4fb,
+        0x25e41d2b669e1ef1,
+        0x3c1b20ee3fd59d7c,
+        0x0abcd06bfa53ddef,
+        0x1dbf9a42d5c4484e,
+        0xabc521979b0deada,
+        0xe86d2bc522363a0d,
+        0x5cae82ab9c9df69e,
+        0x64f2e21e71f54bff,
+        0xf4fd4452e2d74dd3,
+        0xb4130c93bc437944,
+        0xaefe130985139270,
+        0x598cb0fac186d91c,
+        0x7ad91d2691f7f7ee,
+        0x61b46fc9d6e6c907,
+        0xbc34f4def99c0238,
+        0xde355b3b6519035b,
+        0x886b4238611fcfdc,
+        0xc6f34a26c1b2effa,
+        0xc58ef1837d1683b2,
+        0x3bb5fcbc2ec22005,
+        0xc3fe3b1b4c6fad73,
+        0x8e4f1232eef28183,
+        0x9172fe9ce98583ff,
+        0xc03404cd28342f61,
+        0x9e02fce1cdf7e2ec,
+        0x0b07a7c8ee0a6d70,
+        0xae56ede76372bb19,
+        0x1d4f42a3de394df4,
+        0xb96adab760d7f468,
+        0xd108a94bb2c8e3fb,
+        0xbc0ab182b324fb61,
+        0x30acca4f483a797a,
+        0x1df158a136ade735,
+        0xe2a689daf3efe872,
+        0x984f0c70e0e68b77,
+        0xb557135e7f57c935,
+        0x856365553ded1af3,
+        0x2433f51f5f066ed0,
+        0xd3df1ed5d5fd6561,
+        0xf681b202aec4617a,
+        0x7d2fe363630c75d8,
+        0xcc939dce249b3ef9,
+        0xa9e13641146433fb,
+        0xd8b9c583ce2d3695,
+        0xafdc5620273d3cf1,
+        0xadf85458a2bb4a9a,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        12089388422581636141,
+        15868122218748058319,
+        16493973961957724121,
+        2226361261235879174,
+        804143789463730685,
+        7169279544701988693,
+        14598676829356729025,
+        6920687352544613089,
+        14634832936911499744,
+        13224682019735730723,
+        12936621231293662018,
+        5107589913107329746,
+        2370794873526003872,
+        1812104860674547231,
+        11024557242750741735,
+        17010420760592396228,
+        10063591511079373971,
+        14571473877339226915,
+        3237263948324516711,
+        4948338266401566519,
+        6161896350742667575,
+        858112407984594266,
+        17843490455790718513,
+        6307032531170371647,
+        9080913782202917313,
+        2016611193290243196,
+        11723899600515801774,
+        2072493539101642121,
+        15656631083987996497,
+        12968253236851404938,
+        6601165481780564182,
+        9513959213515467417,
+        4199193444162651887,
+        4355924517622031371,
+        11132381281931089680,
+        12396246310882146679,
+        3629020674666680926,
+        14859402785522170117,
+        7580449722333152353,
+        5132862210093399145,
+        10992739607770930607,
+        8037223596897747871,
+        14123619590052141742,
+        15039949459419906370,
+        4868927981316416008,
+        15911404266142908920,
+        17374484505052125826,
+        17584491368562418634,
+        11294255830758471079,
+        6009500166972758852,
+        17817411935113381583,
+        1317345750958594943,
+        9546972268681034615,
+        11512592695251473851,
+        17752560413190683472,
+        4677341488290290400,
+        7918371098303007340,
+        14281778396543219458,
+        13175027533522516316,
+        14008877247416026660,
+        11423551290981559399,
+        7668667070111999241,
+        5910881771259737445,
+        0,
+        1
+    ]
+);
+
+// q = (p - 1) / 2 for the p of fp_ffdhe4096
+fp!(
+    fp_ffdhe4096_q, // Name of mod
+    FpFfdhe4096Q,   // Name of class
+    4095,           // Number of bits for prime
+    64,             // Number of limbs (ceil(bits/64))
+    [
+        0x7fffffffffffffff, // prime number in limbs, least sig first
+        0xe345003f2f32afb5, // get this from sage with p.digits(2^64)
+        0xa6dad428fa20c170,
+        0xc764daad3fc45235,
+        0x054148e6e764bee7,
+        0x15276754fcc68566,
+        0x0d0edc9eb8a0001e,
+        0x0494ccd199e5c5bd,
+        0x36e3bc7cb8d6801c,
+        0x5483b00548c09862,
+        0x76b50f0096cf3419,
+        0x389ae44377da18c5,
+        0x43faadd2bf18e63d,
+        0x3bb1e78eaa81a002,
+        0x563eafa16b4fb68c,
+        0xbc9874f272c42bdb,
+        0xb737a9618b26fa7d,
+        0x12f20e95b34f0f78,
+        0x9e0d90771feacebe,
+        0x055e6835fd29eef7,
+        0x0edfcd216ae22427,
+        0xd5e290cbcd86f56d,
+        0x743695e2911b1d06,
+        0xae574155ce4efb4f,
+        0xb279710f38faa5ff,
+        0x7a7ea229716ba6e9,
+        0x5a098649de21bca2,
+        0x577f0984c289c938,
+        0x2cc6587d60c36c8e,
+        0xbd6c8e9348fbfbf7,
+        0x30da37e4eb736483,
+        0xde1a7a6f7cce011c,
+        0x6f1aad9db28c81ad,
+        0x4435a11c308fe7ee,
+        0x6379a51360d977fd,
+        0xe2c778c1be8b41d9,
+        0x9ddafe5e17611002,
+        0xe1ff1d8da637d6b9,
+        0xc7278919777940c1,
+        0xc8b97f4e74c2c1ff,
+        0x601a0266941a17b0,
+        0x4f017e70e6fbf176,
+        0x8583d3e4770536b8,
+        0x572b76f3b1b95d8c,
+        0x0ea7a151ef1ca6fa,
+        0xdcb56d5bb06bfa34,
+        0xe88454a5d96471fd,
+        0x5e0558c159927db0,
+        0x98566527a41d3cbd,
+        0x0ef8ac509b56f39a,
+        0xf15344ed79f7f439,
+        0xcc278638707345bb,
+        0xdaab89af3fabe49a,
+        0x42b1b2aa9ef68d79,
+        0x9219fa8faf833768,
+        0x69ef8f6aeafeb2b0,
+        0x7b40d901576230bd,
+        0xbe97f1b1b1863aec,
+        0xe649cee7124d9f7c,
+        0xd4f09b208a3219fd,
+        0xec5ce2c1e7169b4a,
+        0x57ee2b10139e9e78,
+        0xd6fc2a2c515da54d,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        5732032771453720669,
+        13289500363786565023,
+        14541203850205896627,
+        4452722522471758349,
+        1608287578927461370,
+        14338559089403977386,
+        10750609585003906434,
+        13841374705089226179,
+        10822921800113447872,
+        8002619965761909831,
+        7426498388877772421,
+        10215179826214659493,
+        4741589747052007744,
+        3624209721349094462,
+        3602370411791931854,
+        15574097447475240841,
+        1680438948449196327,
+        10696203680968902215,
+        6474527896649033423,
+        9896676532803133038,
+        12323792701485335150,
+        1716224815969188532,
+        17240236837871885410,
+        12614065062340743295,
+        18161827564405834626,
+        4033222386580486392,
+        5001055127322051932,
+        4144987078203284243,
+        12866518094266441378,
+        7489762399993258261,
+        13202330963561128365,
+        581174353321383218,
+        8398386888325303775,
+        8711849035244062742,
+        3818018490152627744,
+        6345748548054741743,
+        7258041349333361853,
+        11272061497334788618,
+        15160899444666304707,
+        10265724420186798290,
+        3538735141832309598,
+        16074447193795495743,
+        9800495106394731868,
+        11633154845130261125,
+        9737855962632832017,
+        13376064458576266224,
+        16302224936394700037,
+        16722238663415285653,
+        4141767587807390543,
+        12019000333945517705,
+        17188079796517211550,
+        2634691501917189887,
+        647200463652517614,
+        4578441316793396087,
+        17058376752671815329,
+        9354682976580580801,
+        15836742196606014680,
+        10116812719376887300,
+        7903310993335481017,
+        9571010421122501705,
+        4400358508253567183,
+        15337334140223998483,
+        11821763542519474890,
+        0,
+        2
+    ]
+);
+
+// p = 2^6144 - 2^6080 + (floor(2^6014 e) + 15705020) 2^64 - 1
+fp!(
+    fp_ffdhe6144, // Name of mod
+    FpFfdhe6144,  // Name of class
+    6144,         // Number of bits for prime
+    96,           // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0xa40e329cd0e40e65, // get this from sage with p.digits(2^64)
+        0xa41d570d7938dad4,
+        0x62a69526d43161c1,
+        0x3fdd4a8e9adb1e69,
+        0x5b3b71f9dc6b80d6,
+        0xec9d1810c6272b04,
+        0x8ccf2dd5cacef403,
+        0xe49f5235c95b9117,
+        0x505dc82db854338a,
+        0x62292c311562a846,
+        0xd72b03746ae77f5e,
+        0xf9c9091b462d538c,
+        0x0ae8db5847a67cbe,
+        0xb3a739c122611682,
+        0xeeaac0232a281bf6,
+        0x94c6651e77caf992,
+        0x763e4e4b94b2bbc1,
+        0x587e38da0077d9b4,
+        0x7fb29f8c183023c3,
+        0x0abec1fff9e3a26e,
+        0xa00ef092350511e3,
+        0xb855322edb6340d8,
+        0xa52471f7a9a96910,
+        0x388147fb4cfdb477,
+        0x9b1f5c3e4e46041f,
+        0xcdad0657fccfec71,
+        0xb38e8c334c701c3a,
+        0x917bdd64b1c0fd4c,
+        0x3bb454329b7624c8,
+        0x23ba4442caf53ea6,
+        0x4e677d2c38532a3a,
+        0x0bfd64b645036c7a,
+        0xc68a007e5e0dd902,
+        0x4db5a851f44182e1,
+        0x8ec9b55a7f88a46b,
+        0x0a8291cdcec97dcf,
+        0x2a4ecea9f98d0acc,
+        0x1a1db93d7140003c,
+        0x092999a333cb8b7a,
+        0x6dc778f971ad0038,
+        0xa907600a918130c4,
+        0xed6a1e012d9e6832,
+        0x7135c886efb4318a,
+        0x87f55ba57e31cc7a,
+        0x7763cf1d55034004,
+        0xac7d5f42d69f6d18,
+        0x7930e9e4e58857b6,
+        0x6e6f52c3164df4fb,
+        0x25e41d2b669e1ef1,
+        0x3c1b20ee3fd59d7c,
+        0x0abcd06bfa53ddef,
+        0x1dbf9a42d5c4484e,
+        0xabc521979b0deada,
+        0xe86d2bc522363a0d,
+        0x5cae82ab9c9df69e,
+        0x64f2e21e71f54bff,
+        0xf4fd4452e2d74dd3,
+        0xb4130c93bc437944,
+        0xaefe130985139270,
+        0x598cb0fac186d91c,
+        0x7ad91d2691f7f7ee,
+        0x61b46fc9d6e6c907,
+        0xbc34f4def99c0238,
+        0xde355b3b6519035b,
+        0x886b4238611fcfdc,
+        0xc6f34a26c1b2effa,
+        0xc58ef1837d1683b2,
+        0x3bb5fcbc2ec22005,
+        0xc3fe3b1b4c6fad73,
+        0x8e4f1232eef28183,
+        0x9172fe9ce98583ff,
+        0xc03404cd28342f61,
+        0x9e02fce1cdf7e2ec,
+        0x0b07a7c8ee0a6d70,
+        0xae56ede76372bb19,
+        0x1d4f42a3de394df4,
+        0xb96adab760d7f468,
+        0xd108a94bb2c8e3fb,
+        0xbc0ab182b324fb61,
+        0x30acca4f483a797a,
+        0x1df158a136ade735,
+        0xe2a689daf3efe872,
+        0x984f0c70e0e68b77,
+        0xb557135e7f57c935,
+        0x856365553ded1af3,
+        0x2433f51f5f066ed0,
+        0xd3df1ed5d5fd6561,
+        0xf681b202aec4617a,
+        0x7d2fe363630c75d8,
+        0xcc939dce249b3ef9,
+        0xa9e13641146433fb,
+        0xd8b9c583ce2d3695,
+        0xafdc5620273d3cf1,
+        0xadf85458a2bb4a9a,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        4587400002545651447,
+        7482845384705857964,
+        8432752562308738979,
+        15039634812988197508,
+        9954004435575232761,
+        1155614455125259214,
+        11990223885976042002,
+        17767411330310108344,
+        15588135528589016796,
+        12025861203791547525,
+        13687394559439128497,
+        3913699932278166562,
+        10705465765796759602,
+        4320623672097294809,
+        10487031150409955527,
+        16214750150960232762,
+        4776815471424748512,
+        5651042482924451184,
+        16798791122011643035,
+        17963522525656711714,
+        2296446700735091236,
+        3596094239087876667,
+        597336757968590902,
+        14773245752977141909,
+        142657745873799274,
+        9193888026677225379,
+        8957427305031041532,
+        908535180759660651,
+        14335482462257508859,
+        15923975026562558605,
+        6427298723038388955,
+        4940045923740842373,
+        11225430985055631116,
+        15868122218753794360,
+        16493973961957724121,
+        2226361261235879174,
+        804143789463730685,
+        7169279544701988693,
+        14598676829356729025,
+        6920687352544613089,
+        14634832936911499744,
+        13224682019735730723,
+        12936621231293662018,
+        5107589913107329746,
+        2370794873526003872,
+        1812104860674547231,
+        11024557242750741735,
+        17010420760592396228,
+        10063591511079373971,
+        14571473877339226915,
+        3237263948324516711,
+        4948338266401566519,
+        6161896350742667575,
+        858112407984594266,
+        17843490455790718513,
+        6307032531170371647,
+        9080913782202917313,
+        2016611193290243196,
+        11723899600515801774,
+        2072493539101642121,
+        15656631083987996497,
+        12968253236851404938,
+        6601165481780564182,
+        9513959213515467417,
+        4199193444162651887,
+        4355924517622031371,
+        11132381281931089680,
+        12396246310882146679,
+        3629020674666680926,
+        14859402785522170117,
+        7580449722333152353,
+        5132862210093399145,
+        10992739607770930607,
+        8037223596897747871,
+        14123619590052141742,
+        15039949459419906370,
+        4868927981316416008,
+        15911404266142908920,
+        17374484505052125826,
+        17584491368562418634,
+        11294255830758471079,
+        6009500166972758852,
+        17817411935113381583,
+        1317345750958594943,
+        9546972268681034615,
+        11512592695251473851,
+        17752560413190683472,
+        4677341488290290400,
+        7918371098303007340,
+        14281778396543219458,
+        13175027533522516316,
+        14008877247416026660,
+        11423551290981559399,
+        7668667070111999241,
+        5910881771259737445,
+        0,
+        1
+    ]
+);
+
+// q = (p - 1) / 2 for the p of fp_ffdhe6144
+fp!(
+    fp_ffdhe6144_q, // Name of mod
+    FpFfdhe6144Q,   // Name of class
+    6143,           // Number of bits for prime
+    96,             // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0x5207194e68720732, // get this from sage with p.digits(2^64)
+        0xd20eab86bc9c6d6a,
+        0xb1534a936a18b0e0,
+        0x1feea5474d6d8f34,
+        0x2d9db8fcee35c06b,
+        0xf64e8c0863139582,
+        0xc66796eae5677a01,
+        0x724fa91ae4adc88b,
+        0x282ee416dc2a19c5,
+        0x311496188ab15423,
+        0x6b9581ba3573bfaf,
+        0x7ce4848da316a9c6,
+        0x05746dac23d33e5f,
+        0x59d39ce091308b41,
+        0x7755601195140dfb,
+        0xca63328f3be57cc9,
+        0x3b1f2725ca595de0,
+        0xac3f1c6d003becda,
+        0x3fd94fc60c1811e1,
+        0x855f60fffcf1d137,
+        0x500778491a8288f1,
+        0x5c2a99176db1a06c,
+        0xd29238fbd4d4b488,
+        0x9c40a3fda67eda3b,
+        0xcd8fae1f2723020f,
+        0x66d6832bfe67f638,
+        0x59c74619a6380e1d,
+        0x48bdeeb258e07ea6,
+        0x1dda2a194dbb1264,
+        0x11dd2221657a9f53,
+        0x2733be961c29951d,
+        0x05feb25b2281b63d,
+        0xe345003f2f06ec81,
+        0xa6dad428fa20c170,
+        0xc764daad3fc45235,
+        0x054148e6e764bee7,
+        0x15276754fcc68566,
+        0x0d0edc9eb8a0001e,
+        0x0494ccd199e5c5bd,
+        0x36e3bc7cb8d6801c,
+        0x5483b00548c09862,
+        0x76b50f0096cf3419,
+        0x389ae44377da18c5,
+        0x43faadd2bf18e63d,
+        0x3bb1e78eaa81a002,
+        0x563eafa16b4fb68c,
+        0xbc9874f272c42bdb,
+        0xb737a9618b26fa7d,
+        0x12f20e95b34f0f78,
+        0x9e0d90771feacebe,
+        0x055e6835fd29eef7,
+        0x0edfcd216ae22427,
+        0xd5e290cbcd86f56d,
+        0x743695e2911b1d06,
+        0xae574155ce4efb4f,
+        0xb279710f38faa5ff,
+        0x7a7ea229716ba6e9,
+        0x5a098649de21bca2,
+        0x577f0984c289c938,
+        0x2cc6587d60c36c8e,
+        0xbd6c8e9348fbfbf7,
+        0x30da37e4eb736483,
+        0xde1a7a6f7cce011c,
+        0x6f1aad9db28c81ad,
+        0x4435a11c308fe7ee,
+        0x6379a51360d977fd,
+        0xe2c778c1be8b41d9,
+        0x9ddafe5e17611002,
+        0xe1ff1d8da637d6b9,
+        0xc7278919777940c1,
+        0xc8b97f4e74c2c1ff,
+        0x601a0266941a17b0,
+        0x4f017e70e6fbf176,
+        0x8583d3e4770536b8,
+        0x572b76f3b1b95d8c,
+        0x0ea7a151ef1ca6fa,
+        0xdcb56d5bb06bfa34,
+        0xe88454a5d96471fd,
+        0x5e0558c159927db0,
+        0x98566527a41d3cbd,
+        0x0ef8ac509b56f39a,
+        0xf15344ed79f7f439,
+        0xcc278638707345bb,
+        0xdaab89af3fabe49a,
+        0x42b1b2aa9ef68d79,
+        0x9219fa8faf833768,
+        0x69ef8f6aeafeb2b0,
+        0x7b40d901576230bd,
+        0xbe97f1b1b1863aec,
+        0xe649cee7124d9f7c,
+        0xd4f09b208a3219fd,
+        0xec5ce2c1e7169b4a,
+        0x57ee2b10139e9e78,
+        0xd6fc2a2c515da54d,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        9174800005091302897,
+        14965690769411715928,
+        16865505124617477958,
+        11632525552266843400,
+        1461264797440913907,
+        2311228910250518429,
+        5533703698242532388,
+        17088078586910665073,
+        12729526983468481977,
+        5604978333873543435,
+        8928045045168705379,
+        7827399864556333125,
+        2964187457883967588,
+        8641247344194589619,
+        2527318227110359438,
+        13982756228210913909,
+        9553630942849497025,
+        11302084965848902368,
+        15150838170313734454,
+        17480300977603871813,
+        4592893401470182473,
+        7192188478175753334,
+        1194673515937181804,
+        11099747432244732202,
+        285315491747598549,
+        18387776053354450758,
+        17914854610062083064,
+        1817070361519321302,
+        10224220850805466102,
+        13401205979415565595,
+        12854597446076777911,
+        9880091847481684746,
+        4004117896401710616,
+        13289500363798037105,
+        14541203850205896627,
+        4452722522471758349,
+        1608287578927461370,
+        14338559089403977386,
+        10750609585003906434,
+        13841374705089226179,
+        10822921800113447872,
+        8002619965761909831,
+        7426498388877772421,
+        10215179826214659493,
+        4741589747052007744,
+        3624209721349094462,
+        3602370411791931854,
+        15574097447475240841,
+        1680438948449196327,
+        10696203680968902215,
+        6474527896649033423,
+        9896676532803133038,
+        12323792701485335150,
+        1716224815969188532,
+        17240236837871885410,
+        12614065062340743295,
+        18161827564405834626,
+        4033222386580486392,
+        5001055127322051932,
+        4144987078203284243,
+        12866518094266441378,
+        7489762399993258261,
+        13202330963561128365,
+        581174353321383218,
+        8398386888325303775,
+        8711849035244062742,
+        3818018490152627744,
+        6345748548054741743,
+        7258041349333361853,
+        11272061497334788618,
+        15160899444666304707,
+        10265724420186798290,
+        3538735141832309598,
+        16074447193795495743,
+        9800495106394731868,
+        11633154845130261125,
+        9737855962632832017,
+        13376064458576266224,
+        16302224936394700037,
+        16722238663415285653,
+        4141767587807390543,
+        12019000333945517705,
+        17188079796517211550,
+        2634691501917189887,
+        647200463652517614,
+        4578441316793396087,
+        17058376752671815329,
+        9354682976580580801,
+        15836742196606014680,
+        10116812719376887300,
+        7903310993335481017,
+        9571010421122501705,
+        4400358508253567183,
+        15337334140223998483,
+        11821763542519474890,
+        0,
+        2
+    ]
+);
+
+// p = 2^8192 - 2^8128 + (floor(2^8062 e) + 10965728) 2^64 - 1
+fp!(
+    fp_ffdhe8192, // Name of mod
+    FpFfdhe8192,  // Name of class
+    8192,         // Number of bits for prime
+    128,          // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0xd68c8bb7c5c6424c, // get this from sage with p.digits(2^64)
+        0x011e2a94838ff88c,
+        0x0822e506a9f4614e,
+        0x97d11d49f7a8443d,
+        0xa6bbfde530677f0d,
+        0x2f741ef8c1fe86fe,
+        0xfafabe1c5d71a87e,
+        0xded2fbabfbe58a30,
+        0xb6855dfe72b0a66e,
+        0x1efc8ce0ba8a4fe8,
+        0x83f81d4a3f2fa457,
+        0xa1fe3075a577e231,
+        0xd5b8019488d9c0a0,
+        0x624816cdad9a95f9,
+        0x99e9e31650c1217b,
+        0x51aa691e0e423cfc,
+        0x1c217e6c3826e52c,
+        0x51a8a93109703fee,
+        0xbb7099876a460e74,
+        0x541fc68c9c86b022,
+        0x59160cc046fd8251,
+        0x2846c0ba35c35f5c,
+        0x54504ac78b758282,
+        0x29388839d2af05e4,
+        0xcb2c0f1cc01bd702,
+        0x555b2f747c932665,
+        0x86b63142a3ab8829,
+        0x0b8cc3bdf64b10ef,
+        0x687feb69edd1cc5e,
+        0xfdb23fcec9509d43,
+        0x1e425a31d951ae64,
+        0x36ad004cf600c838,
+        0xa40e329ccff46aaa,
+        0xa41d570d7938dad4,
+        0x62a69526d43161c1,
+        0x3fdd4a8e9adb1e69,
+        0x5b3b71f9dc6b80d6,
+        0xec9d1810c6272b04,
+        0x8ccf2dd5cacef403,
+        0xe49f5235c95b9117,
+        0x505dc82db854338a,
+        0x62292c311562a846,
+        0xd72b03746ae77f5e,
+        0xf9c9091b462d538c,
+        0x0ae8db5847a67cbe,
+        0xb3a739c122611682,
+        0xeeaac0232a281bf6,
+        0x94c6651e77caf992,
+        0x763e4e4b94b2bbc1,
+        0x587e38da0077d9b4,
+        0x7fb29f8c183023c3,
+        0x0abec1fff9e3a26e,
+        0xa00ef092350511e3,
+        0xb855322edb6340d8,
+        0xa52471f7a9a96910,
+        0x388147fb4cfdb477,
+        0x9b1f5c3e4e46041f,
+        0xcdad0657fccfec71,
+        0xb38e8c334c701c3a,
+        0x917bdd64b1c0fd4c,
+        0x3bb454329b7624c8,
+        0x23ba4442caf53ea6,
+        0x4e677d2c38532a3a,
+        0x0bfd64b645036c7a,
+        0xc68a007e5e0dd902,
+        0x4db5a851f44182e1,
+        0x8ec9b55a7f88a46b,
+        0x0a8291cdcec97dcf,
+        0x2a4ecea9f98d0acc,
+        0x1a1db93d7140003c,
+        0x092999a333cb8b7a,
+        0x6dc778f971ad0038,
+        0xa907600a918130c4,
+        0xed6a1e012d9e6832,
+        0x7135c886efb4318a,
+        0x87f55ba57e31cc7a,
+        0x7763cf1d55034004,
+        0xac7d5f42d69f6d18,
+        0x7930e9e4e58857b6,
+        0x6e6f52c3164df4fb,
+        0x25e41d2b669e1ef1,
+        0x3c1b20ee3fd59d7c,
+        0x0abcd06bfa53ddef,
+        0x1dbf9a42d5c4484e,
+        0xabc521979b0deada,
+        0xe86d2bc522363a0d,
+        0x5cae82ab9c9df69e,
+        0x64f2e21e71f54bff,
+        0xf4fd4452e2d74dd3,
+        0xb4130c93bc437944,
+        0xaefe130985139270,
+        0x598cb0fac186d91c,
+        0x7ad91d2691f7f7ee,
+        0x61b46fc9d6e6c907,
+        0xbc34f4def99c0238,
+        0xde355b3b6519035b,
+        0x886b4238611fcfdc,
+        0xc6f34a26c1b2effa,
+        0xc58ef1837d1683b2,
+        0x3bb5fcbc2ec22005,
+        0xc3fe3b1b4c6fad73,
+        0x8e4f1232eef28183,
+        0x9172fe9ce98583ff,
+        0xc03404cd28342f61,
+        0x9e02fce1cdf7e2ec,
+        0x0b07a7c8ee0a6d70,
+        0xae56ede76372bb19,
+        0x1d4f42a3de394df4,
+        0xb96adab760d7f468,
+        0xd108a94bb2c8e3fb,
+        0xbc0ab182b324fb61,
+        0x30acca4f483a797a,
+        0x1df158a136ade735,
+        0xe2a689daf3efe872,
+        0x984f0c70e0e68b77,
+        0xb557135e7f57c935,
+        0x856365553ded1af3,
+        0x2433f51f5f066ed0,
+        0xd3df1ed5d5fd6561,
+        0xf681b202aec4617a,
+        0x7d2fe363630c75d8,
+        0xcc939dce249b3ef9,
+        0xa9e13641146433fb,
+        0xd8b9c583ce2d3695,
+        0xafdc5620273d3cf1,
+        0xadf85458a2bb4a9a,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        9792245861424174856,
+        11411139423357329532,
+        2322842974120607408,
+        4988942428398319762,
+        9974993187272670182,
+        14738926959040569796,
+        749668180329962586,
+        15966687287201743686,
+        5322760251516599767,
+        8502332357484113363,
+        6896887722356097183,
+        5118322806398030152,
+        4866738871134953886,
+        9741714673849140949,
+        2625526228414073856,
+        11169158464967882325,
+        12649313245018388997,
+        6773156000987565524,
+        3828517192012954943,
+        14781624633822420083,
+        1506280021349053923,
+        6229544020838093143,
+        14885213287781513388,
+        5913711564270238029,
+        2208083112358499758,
+        4429238586275139183,
+        10125154126220076269,
+        15858431159924329109,
+        10377668895365469119,
+        167398006304101515,
+        2245987207017828874,
+        2296621881323717650,
+        647594422991903231,
+        7482845384721562984,
+        8432752562308738979,
+        15039634812988197508,
+        9954004435575232761,
+        1155614455125259214,
+        11990223885976042002,
+        17767411330310108344,
+        15588135528589016796,
+        12025861203791547525,
+        13687394559439128497,
+        3913699932278166562,
+        10705465765796759602,
+        4320623672097294809,
+        10487031150409955527,
+        16214750150960232762,
+        4776815471424748512,
+        5651042482924451184,
+        16798791122011643035,
+        17963522525656711714,
+        2296446700735091236,
+        3596094239087876667,
+        597336757968590902,
+        14773245752977141909,
+        142657745873799274,
+        9193888026677225379,
+        8957427305031041532,
+        908535180759660651,
+        14335482462257508859,
+        15923975026562558605,
+        6427298723038388955,
+        4940045923740842373,
+        11225430985055631116,
+        15868122218753794360,
+        16493973961957724121,
+        2226361261235879174,
+        804143789463730685,
+        7169279544701988693,
+        14598676829356729025,
+        6920687352544613089,
+        14634832936911499744,
+        13224682019735730723,
+        12936621231293662018,
+        5107589913107329746,
+        2370794873526003872,
+        1812104860674547231,
+        11024557242750741735,
+        17010420760592396228,
+        10063591511079373971,
+        14571473877339226915,
+        3237263948324516711,
+        4948338266401566519,
+        6161896350742667575,
+        858112407984594266,
+        17843490455790718513,
+        6307032531170371647,
+        9080913782202917313,
+        2016611193290243196,
+        11723899600515801774,
+        2072493539101642121,
+        15656631083987996497,
+        12968253236851404938,
+        6601165481780564182,
+        9513959213515467417,
+        4199193444162651887,
+        4355924517622031371,
+        11132381281931089680,
+        12396246310882146679,
+        3629020674666680926,
+        14859402785522170117,
+        7580449722333152353,
+        5132862210093399145,
+        10992739607770930607,
+        8037223596897747871,
+        14123619590052141742,
+        15039949459419906370,
+        4868927981316416008,
+        15911404266142908920,
+        17374484505052125826,
+        17584491368562418634,
+        11294255830758471079,
+        6009500166972758852,
+        17817411935113381583,
+        1317345750958594943,
+        9546972268681034615,
+        11512592695251473851,
+        17752560413190683472,
+        4677341488290290400,
+        7918371098303007340,
+        14281778396543219458,
+        13175027533522516316,
+        14008877247416026660,
+        11423551290981559399,
+        7668667070111999241,
+        5910881771259737445,
+        0,
+        1
+    ]
+);
+
+// q = (p - 1) / 2 for the p of fp_ffdhe8192
+fp!(
+    fp_ffdhe8192_q, // Name of mod
+    FpFfdhe8192Q,   // Name of class
+    8191,           // Number of bits for prime
+    128,            // Number of limbs (ceil(bits/64))
+    [
+        0x7fffffffffffffff, // prime number in limbs, least sig first
+        0x6b4645dbe2e32126, // get this from sage with p.digits(2^64)
+        0x008f154a41c7fc46,
+        0x8411728354fa30a7,
+        0xcbe88ea4fbd4221e,
+        0x535dfef29833bf86,
+        0x17ba0f7c60ff437f,
+        0x7d7d5f0e2eb8d43f,
+        0x6f697dd5fdf2c518,
+        0x5b42aeff39585337,
+        0x8f7e46705d4527f4,
+        0xc1fc0ea51f97d22b,
+        0x50ff183ad2bbf118,
+        0xeadc00ca446ce050,
+        0xb1240b66d6cd4afc,
+        0x4cf4f18b286090bd,
+        0x28d5348f07211e7e,
+        0x0e10bf361c137296,
+        0x28d4549884b81ff7,
+        0x5db84cc3b523073a,
+        0xaa0fe3464e435811,
+        0x2c8b0660237ec128,
+        0x1423605d1ae1afae,
+        0x2a282563c5bac141,
+        0x149c441ce95782f2,
+        0xe596078e600deb81,
+        0xaaad97ba3e499332,
+        0xc35b18a151d5c414,
+        0x05c661defb258877,
+        0xb43ff5b4f6e8e62f,
+        0x7ed91fe764a84ea1,
+        0x0f212d18eca8d732,
+        0x1b5680267b00641c,
+        0x5207194e67fa3555,
+        0xd20eab86bc9c6d6a,
+        0xb1534a936a18b0e0,
+        0x1feea5474d6d8f34,
+        0x2d9db8fcee35c06b,
+        0xf64e8c0863139582,
+        0xc66796eae5677a01,
+        0x724fa91ae4adc88b,
+        0x282ee416dc2a19c5,
+        0x311496188ab15423,
+        0x6b9581ba3573bfaf,
+        0x7ce4848da316a9c6,
+        0x05746dac23d33e5f,
+        0x59d39ce091308b41,
+        0x7755601195140dfb,
+        0xca63328f3be57cc9,
+        0x3b1f2725ca595de0,
+        0xac3f1c6d003becda,
+        0x3fd94fc60c1811e1,
+        0x855f60fffcf1d137,
+        0x500778491a8288f1,
+        0x5c2a99176db1a06c,
+        0xd29238fbd4d4b488,
+        0x9c40a3fda67eda3b,
+        0xcd8fae1f2723020f,
+        0x66d6832bfe67f638,
+        0x59c74619a6380e1d,
+        0x48bdeeb258e07ea6,
+        0x1dda2a194dbb1264,
+        0x11dd2221657a9f53,
+        0x2733be961c29951d,
+        0x05feb25b2281b63d,
+        0xe345003f2f06ec81,
+        0xa6dad428fa20c170,
+        0xc764daad3fc45235,
+        0x054148e6e764bee7,
+        0x15276754fcc68566,
+        0x0d0edc9eb8a0001e,
+        0x0494ccd199e5c5bd,
+        0x36e3bc7cb8d6801c,
+        0x5483b00548c09862,
+        0x76b50f0096cf3419,
+        0x389ae44377da18c5,
+        0x43faadd2bf18e63d,
+        0x3bb1e78eaa81a002,
+        0x563eafa16b4fb68c,
+        0xbc9874f272c42bdb,
+        0xb737a9618b26fa7d,
+        0x12f20e95b34f0f78,
+        0x9e0d90771feacebe,
+        0x055e6835fd29eef7,
+        0x0edfcd216ae22427,
+        0xd5e290cbcd86f56d,
+        0x743695e2911b1d06,
+        0xae574155ce4efb4f,
+        0xb279710f38faa5ff,
+        0x7a7ea229716ba6e9,
+        0x5a098649de21bca2,
+        0x577f0984c289c938,
+        0x2cc6587d60c36c8e,
+        0xbd6c8e9348fbfbf7,
+        0x30da37e4eb736483,
+        0xde1a7a6f7cce011c,
+        0x6f1aad9db28c81ad,
+        0x4435a11c308fe7ee,
+        0x6379a51360d977fd,
+        0xe2c778c1be8b41d9,
+        0x9ddafe5e17611002,
+        0xe1ff1d8da637d6b9,
+        0xc7278919777940c1,
+        0xc8b97f4e74c2c1ff,
+        0x601a0266941a17b0,
+        0x4f017e70e6fbf176,
+        0x8583d3e4770536b8,
+        0x572b76f3b1b95d8c,
+        0x0ea7a151ef1ca6fa,
+        0xdcb56d5bb06bfa34,
+        0xe88454a5d96471fd,
+        0x5e0558c159927db0,
+        0x98566527a41d3cbd,
+        0x0ef8ac509b56f39a,
+        0xf15344ed79f7f439,
+        0xcc278638707345bb,
+        0xdaab89af3fabe49a,
+        0x42b1b2aa9ef68d79,
+        0x9219fa8faf833768,
+        0x69ef8f6aeafeb2b0,
+        0x7b40d901576230bd,
+        0xbe97f1b1b1863aec,
+        0xe649cee7124d9f7c,
+        0xd4f09b208a3219fd,
+        0xec5ce2c1e7169b4a,
+        0x57ee2b10139e9e78,
+        0xd6fc2a2c515da54d,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        1137747649138798099,
+        4375534773005107449,
+        4645685948241214817,
+        9977884856796639524,
+        1503242300835788748,
+        11031109844371587977,
+        1499336360659925173,
+        13486630500693935756,
+        10645520503033199535,
+        17004664714968226726,
+        13793775444712194366,
+        10236645612796060304,
+        9733477742269907772,
+        1036685273988730282,
+        5251052456828147713,
+        3891572856226213034,
+        6851882416327226379,
+        13546312001975131049,
+        7657034384025909886,
+        11116505193935288550,
+        3012560042698107847,
+        12459088041676186286,
+        11323682501853475160,
+        11827423128540476059,
+        4416166224716999516,
+        8858477172550278366,
+        1803564178730600922,
+        13270118246139106603,
+        2308593717021386623,
+        334796012608203031,
+        4491974414035657748,
+        4593243762647435300,
+        1295188845983806462,
+        14965690769443125968,
+        16865505124617477958,
+        11632525552266843400,
+        1461264797440913907,
+        2311228910250518429,
+        5533703698242532388,
+        17088078586910665073,
+        12729526983468481977,
+        5604978333873543435,
+        8928045045168705379,
+        7827399864556333125,
+        2964187457883967588,
+        8641247344194589619,
+        2527318227110359438,
+        13982756228210913909,
+        9553630942849497025,
+        11302084965848902368,
+        15150838170313734454,
+        17480300977603871813,
+        4592893401470182473,
+        7192188478175753334,
+        1194673515937181804,
+        11099747432244732202,
+        285315491747598549,
+        18387776053354450758,
+        17914854610062083064,
+        1817070361519321302,
+        10224220850805466102,
+        13401205979415565595,
+        12854597446076777911,
+        9880091847481684746,
+        4004117896401710616,
+        13289500363798037105,
+        14541203850205896627,
+        4452722522471758349,
+        1608287578927461370,
+        14338559089403977386,
+        10750609585003906434,
+        13841374705089226179,
+        10822921800113447872,
+        8002619965761909831,
+        7426498388877772421,
+        10215179826214659493,
+        4741589747052007744,
+        3624209721349094462,
+        3602370411791931854,
+        15574097447475240841,
+        1680438948449196327,
+        10696203680968902215,
+        6474527896649033423,
+        9896676532803133038,
+        12323792701485335150,
+        1716224815969188532,
+        17240236837871885410,
+        12614065062340743295,
+        18161827564405834626,
+        4033222386580486392,
+        5001055127322051932,
+        4144987078203284243,
+        12866518094266441378,
+        7489762399993258261,
+        13202330963561128365,
+        581174353321383218,
+        8398386888325303775,
+        8711849035244062742,
+        3818018490152627744,
+        6345748548054741743,
+        7258041349333361853,
+        11272061497334788618,
+        15160899444666304707,
+        10265724420186798290,
+        3538735141832309598,
+        16074447193795495743,
+        9800495106394731868,
+        11633154845130261125,
+        9737855962632832017,
+        13376064458576266224,
+        16302224936394700037,
+        16722238663415285653,
+        4141767587807390543,
+        12019000333945517705,
+        17188079796517211550,
+        2634691501917189887,
+        647200463652517614,
+        4578441316793396087,
+        17058376752671815329,
+        9354682976580580801,
+        15836742196606014680,
+        10116812719376887300,
+        7903310993335481017,
+        9571010421122501705,
+        4400358508253567183,
+        15337334140223998483,
+        11821763542519474890,
+        0,
+        2
+    ]
+);
+
+/// The group of RFC 7919's `ffdhe2048`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ffdhe2048;
+
+impl SubgroupParams for Ffdhe2048 {
+    type Element = FpFfdhe2048;
+    type Scalar = FpFfdhe2048Q;
+
+    fn generator() -> FpFfdhe2048 {
+        FpFfdhe2048::from(2u64)
+    }
+}
+
+impl PedersenParams for Ffdhe2048 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Ffdhe2048";
+
+    ///The hashed value squared, since p is a safe prime.
+    fn second_generator() -> FpFfdhe2048 {
+        FpFfdhe2048::new([
+            0x9ddfb067305be137,
+            0x1b7464a0dd476090,
+            0xe0bd89de029e5e74,
+            0x65b264b9f41c9890,
+            0xd5edf59862bc1e0f,
+            0xab7c97bbec34da7c,
+            0x759a3ea2db368e25,
+            0xe80f60ef3fdd3f31,
+            0xa66f34d2502e09c8,
+            0x359c8c846d3f7899,
+            0xb146277caf153ea7,
+            0x9b59760ca07ad1a1,
+            0xe54af6f2e23eefce,
+            0xe4919ac2e1d8c585,
+            0x3833113ea346e1a6,
+            0x895361ff44746c84,
+            0xd5362d480f01c873,
+            0xd4dbb08b337bd6e1,
+            0x5dae8f1ad5aaf907,
+            0x4e01641b845fd670,
+            0xea14756cb29f722b,
+            0x9ead17540dddecc8,
+            0x3a702b0cb2258e97,
+            0xcd96aa94ee1ca631,
+            0x865d8d4a3c5beaba,
+            0xe7cc730b8f3719e2,
+            0xf7dbed1eb16e61d4,
+            0xca12dbf0fab8d16a,
+            0xe09d7b59ac1e92cd,
+            0x4b4f8ddb78d02b4f,
+            0x4272f464ac71cf40,
+            0x75735afe7f12f24d,
+        ])
+    }
+}
+
+/// The group of RFC 7919's `ffdhe3072`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ffdhe3072;
+
+impl SubgroupParams for Ffdhe3072 {
+    type Element = FpFfdhe3072;
+    type Scalar = FpFfdhe3072Q;
+
+    fn generator() -> FpFfdhe3072 {
+        FpFfdhe3072::from(2u64)
+    }
+}
+
+impl PedersenParams for Ffdhe3072 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Ffdhe3072";
+
+    ///The hashed value squared, since p is a safe prime.
+    fn second_generator() -> FpFfdhe3072 {
+        FpFfdhe3072::new([
+            0xefd2cd31d975fb40,
+            0xb126ce0e329e3f48,
+            0x4f903ccdf19fb856,
+            0x0a9e5732a3ad45f6,
+            0x466e267b62d313de,
+            0x2d093c3a78308326,
+            0x5220bb3f2f443b43,
+            0xe91aa417b9e2bda4,
+            0x12fe9a95ac2f798a,
+            0xf79d99bcfbdcfdf5,
+            0x79e8fec353118bba,
+            0xbb48f4fe066c83d1,
+            0x60172c206c9f23ff,
+            0x3b2b8722bd21c584,
+            0xe478c76d12c2cbc5,
+            0x4aee8607b3edb198,
+            0xabb0ee6bdc96b131,
+            0xf125ae40232f5bb8,
+            0x36f5472a93971754,
+            0x0146a4cc71f80e0b,
+            0x3dbbba1ddd2b0761,
+            0x86f7c367cc7d0a4d,
+            0xb834df9f4178931e,
+            0x2b916b5a2f0acd92,
+            0xf3d731e5214a7490,
+            0xc6b5efe83d1cd2b0,
+            0x373e5684e6f15d98,
+            0x5a6c6716234bef1f,
+            0xae161f1e5bf68282,
+            0x242337df4e6b9796,
+            0xded101387531b895,
+            0x9352e2342792c31e,
+            0x17beb8c314a1747a,
+            0xbb430696bd7a0283,
+            0x23394ac02cabae27,
+            0x53c70278954c4cea,
+            0x2fc4dba458ebaebd,
+            0x83de9f7e81a26fc3,
+            0xfe18508dea885cf9,
+            0xd95f39ac4d12095e,
+            0x3db7aef3394fe176,
+            0x08c087bb770af0bc,
+            0xf68f8c5676f4ff35,
+            0x6841421fbe3597ed,
+            0x8ecad4bc0c4048c2,
+            0x7e85dabf75093ed4,
+            0x87b19244234692af,
+            0x9e16ab03823b052f,
+        ])
+    }
+}
+
+/// The group of RFC 7919's `ffdhe4096`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ffdhe4096;
+
+impl SubgroupParams for Ffdhe4096 {
+    type Element = FpFfdhe4096;
+    type Scalar = FpFfdhe4096Q;
+
+    fn generator() -> FpFfdhe4096 {
+        FpFfdhe4096::from(2u64)
+    }
+}
+
+impl PedersenParams for Ffdhe4096 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Ffdhe4096";
+
+    ///The hashed value squared, since p is a safe prime.
+    fn second_generator() -> FpFfdhe4096 {
+        FpFfdhe4096::new([
+            0x4456a84224b64954,
+            0xeb15ac01562638ae,
+            0x3ca08cd9714a564d,
+            0xd9fc4f20363a0242,
+            0x446f6d4872f7cd05,
+            0x81636456c1930140,
+            0xdb96417a15e9112b,
+            0xe3172110ade13523,
+            0x669709514cefbd21,
+            0x9da99bfa5a19da7a,
+            0x06fc1dd584588403,
+            0x0df974d2f042efdc,
+            0xd89a63d043f40859,
+            0xc1359f10f628e02c,
+            0x7c0144567b5b4a7a,
+            0x43e5eed0cbe4df3a,
+            0xc435ba18610e3a35,
+            0x39ea16ed749fcd44,
+            0x25ea049a6ae3912f,
+            0x95b5729e415c8877,
+            0xff4235e5886c5560,
+            0xee4125fc98b5b8a6,
+            0x71f3f0e9b5a97779,
+            0x9e9e16226b025aca,
+            0x7bd90a00c044caca,
+            0x44cef00e469a552f,
+            0xa70228cf6c5fd7df,
+            0xa69cc9d465d5ec6e,
+            0xbd647c2555c05b0f,
+            0x5dbe0c1e4c6d9f77,
+            0xfec4480e00806132,
+            0x00a20015cb738459,
+            0xf56b8979ab319303,
+            0xb2b2030a03f90c08,
+            0x3a0c6038e8717dfc,
+            0xdb02016b318f3e54,
+            0xc9950ac6c9ce9920,
+            0xb0a4779d4646ff45,
+            0x1e3060830804a78d,
+            0xd427e44159d84f9a,
+            0x123d9ff0fddd5457,
+            0x259afeae57957637,
+            0xcccea78c8a9da1fc,
+            0xe9c7ef16b619e8bd,
+            0x54eeb414dc5dc6bb,
+            0x419387d54246c0b3,
+            0x1876f0cc5ea1be32,
+            0xf70218ba618c6f4a,
+            0xe6ddde2b5852d2f9,
+            0x21c9aaa8ffbadef8,
+            0xb303a3c52528cf04,
+            0x1f31897d4489f7ba,
+            0xa16a09f989ed9ee3,
+            0x101b8bef0cad8cb7,
+            0xc649f95659a2c702,
+            0xe3ee52925f731bb1,
+            0x93245c8c40b7f294,
+            0x0cc31273fe8e4533,
+            0xe70c98a59f94620f,
+            0x6b1e4ff3ab633844,
+            0x47e647b2f6ceb778,
+            0xaccf1dbbb1e81631,
+            0x844cc1bacd3a06d6,
+            0x7f5ca20b522ed17b,
+        ])
+    }
+}
+
+/// The group of RFC 7919's `ffdhe6144`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ffdhe6144;
+
+impl SubgroupParams for Ffdhe6144 {
+    type Element = FpFfdhe6144;
+    type Scalar = FpFfdhe6144Q;
+
+    fn generator() -> FpFfdhe6144 {
+        FpFfdhe6144::from(2u64)
+    }
+}
+
+impl PedersenParams for Ffdhe6144 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Ffdhe6144";
+
+    ///The hashed value squared, since p is a safe prime.
+    fn second_generator() -> FpFfdhe6144 {
+        FpFfdhe6144::new([
+            0x53e1eb78d398f6db,
+            0x5fe960197e9abb2d,
+            0xab05fb42bcdd22f7,
+            0xcf4b1c92fe6855c6,
+            0xe0e2964cbb8b4d6b,
+            0x5eb9440227d80661,
+            0x7cd6c0a173d56dd8,
+            0x4fe7f6781baf86c0,
+            0x23f4d74da419b25d,
+            0xa3dd41e424e6657b,
+            0xd401364437023854,
+            0x8a1c8a58255d6c3a,
+            0xf56cc52e72668a91,
+            0x2815a56c7d4705f1,
+            0x4baa6e20968f1258,
+            0xb08f704f5d8ebc53,
+            0x4960bf341d14ef5a,
+            0x62f75d6606f01d1d,
+            0x9fe239c96f877fd6,
+            0xf65399e789fdd510,
+            0x0f6c10e134cb452a,
+            0x66eb5cfb2da17d6b,
+            0x3e607f69fdcb6e22,
+            0xc5ad754e941b64d1,
+            0x2156b6ae127a6cd9,
+            0x1079b77c5372949a,
+            0xce40d09b0a676d6b,
+            0x8d076656038d13ec,
+            0x68c8e4355b129ee7,
+            0x6d4a44ee598139c3,
+            0xd10b08b57eb42254,
+            0x9652f06ed1b2410d,
+            0xd4648a651fea85e5,
+            0xc7906dac5e434715,
+            0xbc92787af59af4c5,
+            0x7cbb56fd1a07fb37,
+            0x288341f94a486edb,
+            0x7ade597c7f720269,
+            0xf3febf5fb31fd91b,
+            0x858b6082499cbc3b,
+            0xf873192bf47b66e9,
+            0x7311281229edbb00,
+            0xab39b588d24c02e3,
+            0x35b33fae903e761b,
+            0x775f5526a001b7ad,
+            0x791db05b303c509a,
+            0xb9dd78bb32332753,
+            0xae6291238cf0b4b8,
+            0xbb8df5092c123572,
+            0x6877cf5880ea91f2,
+            0x9bd3cd3bec89c065,
+            0x8182b70930ecbb38,
+            0x202d0fee4d982cb6,
+            0xa249eb6b4340c882,
+            0x2f6bfcd95f45a02b,
+            0x3ccc50e4f0790239,
+            0xdbf1bccc69464ab3,
+            0x20da380b700acf88,
+            0xa7baac82d716938b,
+            0xe7018a2360b1402b,
+            0xaaa5d10dcc0427fe,
+            0x8afc31b2076bcdad,
+            0x1c25e464951de03e,
+            0x64c6c89101865e45,
+            0x00f64ab18cf1b031,
+            0xe9bb9828eb7659a2,
+            0xe443fbee51b8cd1d,
+            0x36868ba8577a16e8,
+            0x4455ac547821b16a,
+            0xe9c69ff2d47a9267,
+            0xb31d51e512aa61d1,
+            0xafed40c06ad2ff8b,
+            0x7c451a9c5cb09e16,
+            0x618e5e99102b6fef,
+            0x8fa6053f9c370e4f,
+            0x20a852a5e94712b9,
+            0xfe8b47f9ec472106,
+            0x650f92b04d49b4a8,
+            0x6cb1a249d43d810c,
+            0xbdd386776589e78d,
+            0x697007c781dfaa3c,
+            0xe9e7183a743e80e5,
+            0xfe5198bb5bb251d2,
+            0x177c5cab53c78f13,
+            0xcb08ce6e4fbc739d,
+            0x7726d113e07265aa,
+            0x5fededd794805597,
+            0xf125794732021938,
+            0x2936d6cf9a3432c1,
+            0x4bc3e2cfaaecf8e8,
+            0x139ebcc7c5a09020,
+            0xc09aac34df2a8fdc,
+            0x4587f65be19f1175,
+            0x4fdf3be303e11c6f,
+            0xd7f20e2115be3569,
+            0x24dd45361881f5c8,
+        ])
+    }
+}
+
+/// The group of RFC 7919's `ffdhe8192`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ffdhe8192;
+
+impl SubgroupParams for Ffdhe8192 {
+    type Element = FpFfdhe8192;
+    type Scalar = FpFfdhe8192Q;
+
+    fn generator() -> FpFfdhe8192 {
+        FpFfdhe8192::from(2u64)
+    }
+}
+
+impl PedersenParams for Ffdhe8192 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Ffdhe8192";
+
+    ///The hashed value squared, since p is a safe prime.
+    fn second_generator() -> FpFfdhe8192 {
+        FpFfdhe8192::new([
+            0x8b32025ed2a482ce,
+            0xe1c7f1637530991e,
+            0x82b3825b16a56de6,
+            0x31e2643212dc3b76,
+            0xd9b8fa439cbf8dfd,
+            0x5523b26069cd2d4b,
+            0xee2dd56c9c302f28,
+            0x65e814686e7c2414,
+            0xe9b5876555f5393f,
+            0x649ec115e4f55b42,
+            0x209b999aea2f297a,
+            0x28a3c5b4f92426c8,
+            0x979d155f56c33896,
+            0x994cb562232977ec,
+            0xa789c5c26adec300,
+            0x8761b110a95abbb8,
+            0x5211e94e92e87339,
+            0x074edc35f7f75efd,
+            0xb318c62a0f881689,
+            0x05550ba9d1d794ca,
+            0x9af19b119da11193,
+            0x28ff6e432641e108,
+            0xc9b46bb07fab5f66,
+            0xa54a2b41a06af215,
+            0xe146a2342e952dd7,
+            0xd84f007877a0153a,
+            0x74fc4ca3a2592eab,
+            0x6f0a60902f89e710,
+            0xe2a2a5b3327b9b84,
+            0xe19b7b7c6581af78,
+            0x7cd445f8ae0c335a,
+            0x942a62bc16b92a67,
+            0xf902664cb521520d,
+            0xc576efa4fde4ed71,
+            0xb95c2c0f3cebe5e1,
+            0x8ea09bb49735c01a,
+            0xc2ff7b7fd5e40304,
+            0x5b8d0ffe8c1da5d9,
+            0x70c9a51155f77447,
+            0x59e2a454ff7dd97e,
+            0x7dd96c3e6ec35271,
+            0xf13319ba337d88d2,
+            0xe9908416e9fba75f,
+            0x6e5c85858601f905,
+            0xf5dfc643c63242d9,
+            0x0e0319f2e0b6a63e,
+            0x9a41842457861d1a,
+            0xa1bc4c32f4f18f45,
+            0x0515c8cfab87223f,
+            0xc95c2775c10f89ac,
+            0x5b86cea24b005154,
+            0xc634b6ba99cfedf0,
+            0x2648b5c17655ef4c,
+            0x8667b2dfa5eddaa1,
+            0xb68bd050bcd679fc,
+            0xd818f6a15f1f2fdc,
+            0x8870f5d4205049f4,
+            0x99e9f0b86970eb40,
+            0x070a960e07502c45,
+            0xa386b3c16bf934fa,
+            0x80f63010404bd0f8,
+            0xf55114b2e35c262d,
+            0x3b9d9ea03c6d01ea,
+            0x86121c173b42af5c,
+            0x25cac575d1e2d7b2,
+            0x9992177c91cd5151,
+            0x954d97a6fc3bd6dc,
+            0x42c4a24153bee6a0,
+            0xf4a13ef812e0e35e,
+            0xe526b8b97d11ca7e,
+            0x47ef332226e527dd,
+            0x125a7dc7365f3f58,
+            0xbc23a77bb6bb8bb0,
+            0xdd5a99368c2526dc,
+            0x5416b441eb990db0,
+            0x12fda2a378bea6e2,
+            0x7c5085b4852ea3e1,
+            0xaeb43332753dbc0b,
+            0xd2c658254c2e1b22,
+            0x6fa121efcda1f3cb,
+            0x9ff4a488a1a8a50e,
+            0x24e01b531338df34,
+            0x9d44ae2de0457928,
+            0xea3b88c9beab6d81,
+            0x4de4da760b448517,
+            0xc203f9b4ff1267a7,
+            0x7108c5dfae745eeb,
+            0xf9ec95bbcacb818e,
+            0x6cd4e7633c0c47f8,
+            0x9a56976d8a342e7d,
+            0x9e3b379ec5f073a8,
+            0x9a1948dd1d833d9b,
+            0x9e83a2a882678c9b,
+            0x4640564804a9c094,
+            0xd3bd5b705d16eba4,
+            0x326231f7a1b14bee,
+            0x8815e912932e3eea,
+            0x9a30ccee6dedd864,
+            0x496e09708a120133,
+            0x60de302d9ef6a631,
+            0xef4323bc124a9a46,
+            0xb5b29955fa576127,
+            0x1389f29add63b010,
+            0xba3c8e90855c23c0,
+            0xa193c3a50b1d8abd,
+            0x4f444f892e8e1594,
+            0xcc2bbe3aa162b478,
+            0xfe2d982d7dc0d54e,
+            0x92ef8171ed3dd9f4,
+            0x32642f1911899b57,
+            0xe7d891e8788457d1,
+            0x9b24bd0fac770d45,
+            0xd12c297581125e35,
+            0x27399aa8ce23e46c,
+            0x021e831fd71b6a70,
+            0x24fca329c00e12ac,
+            0xc3e83f8d6367726a,
+            0xc2bb64ede1810915,
+            0x77cfa36839c8cba1,
+            0x40b1cecc4626b514,
+            0xe4237e05e6285dde,
+            0xfbf8488c4c627e30,
+            0xcbe4ab40c173cb33,
+            0xe0eb23fa8cd8ef14,
+            0x440e0fca9050eb9c,
+            0x61217e0ea2bbb1bf,
+            0x760018decb887a8a,
+            0x0e7150bb53b7d119,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::{Field, PrimeField};
+    use hash_to_field::hash_to_field;
+    use sha2::Sha256;
+
+    #[test]
+    fn groups_are_generated_by_2() {
+        assert!(Ffdhe2048::check_params());
+        assert!(Ffdhe3072::check_params());
+        assert!(Ffdhe4096::check_params());
+        assert!(Ffdhe6144::check_params());
+        assert!(Ffdhe8192::check_params());
+    }
+
+    #[test]
+    fn primes_match_rfc_7919() {
+        // The 64 bits after the leading ones, and the 64 before the trailing ones, from the hex
+        // of each prime in the RFC's appendix A.
+        fn ends(prime: &[u64]) -> (u64, u64) {
+            (prime[prime.len() - 2], prime[1])
+        }
+        assert_eq!(
+            ends(FpFfdhe2048::PRIME),
+            (0xadf85458a2bb4a9a, 0x886b423861285c97)
+        );
+        assert_eq!(
+            ends(FpFfdhe3072::PRIME),
+            (0xadf85458a2bb4a9a, 0x25e41d2b66c62e37)
+        );
+        assert_eq!(
+            ends(FpFfdhe4096::PRIME),
+            (0xadf85458a2bb4a9a, 0xc68a007e5e655f6a)
+        );
+        assert_eq!(
+            ends(FpFfdhe6144::PRIME),
+            (0xadf85458a2bb4a9a, 0xa40e329cd0e40e65)
+        );
+        assert_eq!(
+            ends(FpFfdhe8192::PRIME),
+            (0xadf85458a2bb4a9a, 0xd68c8bb7c5c6424c)
+        );
+    }
+
+    #[test]
+    fn second_generators_are_hashed() {
+        fn check<P: PedersenParams>()
+        where
+            P::Element: PrimeField,
+        {
+            let u: P::Element = hash_to_field::<_, Sha256>(b"h", P::SECOND_GENERATOR_DST, 1)[0];
+            let h = P::second_generator();
+            assert_eq!(h, u.square());
+            assert!(P::is_member(&h));
+            assert_ne!(h, P::generator());
+        }
+        check::<Ffdhe2048>();
+        check::<Ffdhe3072>();
+        check::<Ffdhe4096>();
+        check::<Ffdhe6144>();
+        check::<Ffdhe8192>();
+    }
+}
@@ -0,0 +1,368 @@
+use digits::util::{bit_length, limb_window};
+use num_traits::{Inv, One, Zero};
+use rand::Rng;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The arithmetic every field in the crate supports. Generic code (extension fields,
+/// polynomials, ...) is written against this trait rather than a particular `fp!` type.
+pub trait Field:
+    Copy
+    + Debug
+    + Eq
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Inv<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+{
+    ///Square the value.
+    fn square(&self) -> Self;
+
+    ///Uniformly random element of the field.
+    fn random<R: Rng>(rng: &mut R) -> Self;
+
+    ///Raise the value to an exponent given in limbs, least significant first. The exponent
+    ///can be larger than the field, which is what Frobenius maps and group orders need.
+    fn pow_limbs(&self, exp: &[u64]) -> Self {
+        let mut ret = Self::one();
+        for limb in exp.iter().rev() {
+            for i in (0..64).rev() {
+                ret = ret.square();
+                if (limb >> i) & 1 == 1 {
+                    ret *= *self;
+                }
+            }
+        }
+        ret
+    }
+
+    ///The product of `base^exp` over `terms`, with exponents in limbs as for `pow_limbs`. This is
+    ///Straus's (or Shamir's) trick: the exponents are cut into windows of the same width and
+    ///walked together from the top, so all the bases share one chain of squarings and each
+    ///nonzero window costs one multiplication from a small table of powers of its base.
+    fn multi_pow(terms: &[(Self, &[u64])]) -> Self {
+        let bits = terms.iter().map(|t| bit_length(t.1)).max().unwrap_or(0);
+        // Each table costs 2^w multiplications and each exponent about bits / w more.
+        let window = (1..8).min_by_key(|w| (1 << w) + bits / w).unwrap();
+        let tables: Vec<Vec<Self>> = terms
+            .iter()
+            .map(|t| {
+                let mut table = vec![Self::one(), t.0];
+                for d in 2..1 << window {
+                    let next = table[d - 1] * t.0;
+                    table.push(next);
+                }
+                table
+            })
+            .collect();
+        let mut ret = Self::one();
+        for i in (0..bits.div_ceil(window)).rev() {
+            for _ in 0..window {
+                ret = ret.square();
+            }
+            for (table, t) in tables.iter().zip(terms) {
+                let digit = limb_window(t.1, i * window, window);
+                if digit != 0 {
+                    ret *= table[digit];
+                }
+            }
+        }
+        ret
+    }
+}
+
+/// A field of prime order, as generated by `fp!`.
+///
+/// The methods here that are described as constant-time, and the code built on them, do the same
+/// field operations for every input, but they are only as constant-time as the field arithmetic
+/// underneath, which uses gridiron's Barrett reduction. Its final subtractions of p depend on the
+/// values.
+pub trait PrimeField: Field + Ord + From<u64> {
+    ///The prime in limbs, least significant first.
+    const PRIME: &'static [u64];
+    ///Number of bits in the prime.
+    const PRIMEBITS: usize;
+
+    ///The value in limbs, least significant first.
+    fn to_limbs(&self) -> Vec<u64>;
+
+    ///One of the square roots of the value, or None if it isn't a square.
+    fn sqrt(&self) -> Option<Self>;
+
+    ///Non-adjacent form of the value, least significant digit first, with `PRIMEBITS + 1` digits.
+    fn create_naf(&self) -> Vec<i8>;
+
+    ///Width-w non-adjacent form of the value, least significant digit first, with
+    ///`PRIMEBITS + 1` digits. See `digits::naf`.
+    fn create_wnaf(&self, w: usize) -> Vec<i8>;
+
+    ///Joint sparse form of the value and `other`, least significant column first, with
+    ///`PRIMEBITS + 1` columns. See `digits::naf`.
+    fn create_jsf(&self, other: &Self) -> Vec<(i8, i8)>;
+
+    ///`b` if `choice` is true and `a` otherwise, without branching on `choice`.
+    fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self;
+
+    ///Swap `a` and `b` if `choice` is true, without branching on `choice`.
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        let x = Self::conditional_select(a, b, choice);
+        let y = Self::conditional_select(b, a, choice);
+        *a = x;
+        *b = y;
+    }
+
+    ///The inverse as `self^(p - 2)`, and zero for zero. `inv` runs the binary extended Euclidean
+    ///algorithm, which branches on the value; here the exponent is public, so the squarings and
+    ///multiplications are the same for every value. It's slower than `inv`, and meant for
+    ///secrets such as signature nonces.
+    fn inv_constant_time(&self) -> Self {
+        let mut exponent = Self::PRIME.to_vec();
+        let mut borrow = 2;
+        for limb in exponent.iter_mut() {
+            let (x, overflow) = limb.overflowing_sub(borrow);
+            *limb = x;
+            borrow = overflow as u64;
+        }
+        self.pow_limbs(&exponent)
+    }
+
+    ///`pow_limbs` for a secret exponent of at most `bits` bits, where `bits` is public, such as
+    ///the size of a group order. The exponent is taken 4 bits at a time, and each window does the
+    ///same squarings and one multiplication by an entry of a table of `self^0` to `self^15`,
+    ///read in full and kept with `conditional_select`. Which operations happen doesn't depend on
+    ///the exponent. Bits of `exp` from `bits` up are ignored.
+    fn pow_constant_time(&self, exp: &[u64], bits: usize) -> Self {
+        const WINDOW: usize = 4;
+        let mut table = vec![Self::one(), *self];
+        for d in 2..1 << WINDOW {
+            let next = table[d - 1] * *self;
+            table.push(next);
+        }
+        let mut ret = Self::one();
+        for i in (0..bits.div_ceil(WINDOW)).rev() {
+            for _ in 0..WINDOW {
+                ret = ret.square();
+            }
+            // The last window can run past `bits`.
+            let width = WINDOW.min(bits - i * WINDOW);
+            let digit = limb_window(exp, i * WINDOW, width);
+            let mut entry = table[0];
+            for (d, x) in table.iter().enumerate().skip(1) {
+                entry = Self::conditional_select(&entry, x, d == digit);
+            }
+            ret *= entry;
+        }
+        ret
+    }
+
+    ///Big-endian bytes of the value, as `to_bytes_array` gives them.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    ///The inverse of `to_bytes`. None if `bytes` is the wrong length or encodes a value which
+    ///isn't less than the prime.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+
+    ///Big-endian bytes of any length reduced mod the prime, as when a hash becomes a field
+    ///element.
+    fn from_bytes_reduced(bytes: &[u8]) -> Self {
+        let radix = Self::from(256u64);
+        bytes.iter().fold(Self::zero(), |acc, b| {
+            acc * radix + Self::from(u64::from(*b))
+        })
+    }
+
+    ///The sign of the value as RFC 9380 defines it for prime fields: true if it's odd.
+    fn sgn0(&self) -> bool {
+        self.to_limbs()[0] & 1 == 1
+    }
+
+    ///`(true, sqrt(u / v))` if `u / v` is a square and `(false, sqrt(z u / v))` if not, for a
+    ///non-square `z`, as RFC 9380 uses to find a square root and learn whether there is one in
+    ///one go. Zero counts as a square, and `v = 0` gives `(u == 0, 0)`. This is the algorithm
+    ///of its appendix F.2.1.1, which does the same operations for every `u` and `v`, choosing
+    ///between results with `conditional_select`.
+    fn sqrt_ratio(u: &Self, v: &Self, z: &Self) -> (bool, Self) {
+        let p = Self::PRIME;
+        // p - 1 = 2^c1 c2 with c2 odd. c3 = (c2 - 1) / 2 is p shifted right by c1 + 1 bits.
+        let mut c1 = 1;
+        while (p[c1 / 64] >> (c1 % 64)) & 1 == 0 {
+            c1 += 1;
+        }
+        let (skip, bits) = ((c1 + 1) / 64, (c1 + 1) % 64);
+        let c3: Vec<u64> = (skip..p.len())
+            .map(|i| {
+                let high = p
+                    .get(i + 1)
+                    .map_or(0, |h| if bits == 0 { 0 } else { h << (64 - bits) });
+                (p[i] >> bits) | high
+            })
+            .collect();
+        let z_c3 = z.pow_limbs(&c3);
+        // z^c2 and z^((c2 + 1) / 2)
+        let mut tv1 = z_c3.square() * *z;
+        let c7 = z_c3 * *z;
+        // v^(2^c1 - 1)
+        let mut tv2 = (0..c1).fold(Self::one(), |acc, _| acc.square() * *v);
+        let mut tv3 = tv2.square() * *v;
+        let mut tv5 = (*u * tv3).pow_limbs(&c3) * tv2;
+        tv2 = tv5 * *v;
+        tv3 = tv5 * *u;
+        let mut tv4 = tv3 * tv2;
+        // tv4^(2^(c1 - 1)) is 1 exactly when u / v is a nonzero square.
+        tv5 = (1..c1).fold(tv4, |acc, _| acc.square());
+        let is_square = tv5.is_one() | u.is_zero();
+        tv2 = tv3 * c7;
+        tv5 = tv4 * tv1;
+        tv3 = Self::conditional_select(&tv2, &tv3, is_square);
+        tv4 = Self::conditional_select(&tv5, &tv4, is_square);
+        for k in (2..=c1).rev() {
+            tv5 = (2..k).fold(tv4, |acc, _| acc.square());
+            let e1 = tv5.is_one();
+            tv2 = tv3 * tv1;
+            tv1 = tv1.square();
+            tv5 = tv4 * tv1;
+            tv3 = Self::conditional_select(&tv2, &tv3, e1);
+            tv4 = Self::conditional_select(&tv5, &tv4, e1);
+        }
+        (is_square, tv3)
+    }
+}
+
+///Invert every element of `values` in place with a single field inversion (Montgomery's trick),
+///at the cost of three multiplications per element. Panics if any element is zero.
+pub fn batch_inverse<F: Field>(values: &mut [F]) {
+    // prefix[i] = values[0] * ... * values[i - 1]
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut acc = F::one();
+    for v in values.iter() {
+        assert!(!v.is_zero(), "You cannot divide by zero.");
+        prefix.push(acc);
+        acc *= *v;
+    }
+    let mut inv = acc.inv();
+    for (v, p) in values.iter_mut().zip(prefix).rev() {
+        let next = inv * *v;
+        *v = inv * p;
+        inv = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_255::Fp255;
+    use fp_25519::Fp25519;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    ///Check `sqrt_ratio` against `sqrt` for the smallest non-square z.
+    fn check_sqrt_ratio<F: PrimeField>(u: &[u8], v: &[u8]) -> Result<(), TestCaseError> {
+        let z = (2u64..).map(F::from).find(|z| z.sqrt().is_none()).unwrap();
+        let (u, v) = (F::from_bytes_reduced(u), F::from_bytes_reduced(v));
+        let (is_square, root) = F::sqrt_ratio(&u, &v, &z);
+        if v.is_zero() {
+            prop_assert_eq!((is_square, root), (u.is_zero(), F::zero()));
+        } else {
+            prop_assert_eq!(is_square, (u / v).sqrt().is_some());
+            let expected = if is_square { u } else { z * u };
+            prop_assert_eq!(root.square() * v, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn multi_pow_edge_cases() {
+        let two = Fp256::from(2u64);
+        assert_eq!(Fp256::multi_pow(&[]), Fp256::one());
+        assert_eq!(Fp256::multi_pow(&[(two, &[])]), Fp256::one());
+        assert_eq!(Fp256::multi_pow(&[(two, &[10])]), Fp256::from(1024u64));
+        assert_eq!(
+            Fp256::multi_pow(&[(two, &[0, 1]), (Fp256::zero(), &[3])]),
+            Fp256::zero()
+        );
+    }
+
+    #[test]
+    fn sgn0_is_parity() {
+        assert!(Fp256::one().sgn0());
+        assert!(!Fp256::zero().sgn0());
+        // p - 1 is even.
+        assert!(!(-Fp256::one()).sgn0());
+        assert!((-Fp256::from(2u64)).sgn0());
+    }
+
+    proptest! {
+        #[test]
+        fn sqrt_ratio_matches_sqrt(u in any::<[u8; 32]>(), v in any::<[u8; 32]>(), zeros in 0u8..4) {
+            // Fp256 has p = 3 mod 4, Fp25519 p = 5 mod 8 and Fp255 p = 1 mod 2^32.
+            let u = if zeros & 1 == 1 { [0u8; 32] } else { u };
+            let v = if zeros & 2 == 2 { [0u8; 32] } else { v };
+            check_sqrt_ratio::<Fp256>(&u, &v)?;
+            check_sqrt_ratio::<Fp25519>(&u, &v)?;
+            check_sqrt_ratio::<Fp255>(&u, &v)?;
+        }
+
+        #[test]
+        fn inv_constant_time_matches_inv(a in any::<[u8; 32]>()) {
+            // Fp255's prime has a bottom limb of 0xffffffff00000001, so subtracting 2 borrows.
+            let a = Fp255::from_bytes_reduced(&a);
+            prop_assert_eq!(a.inv_constant_time(), if a.is_zero() { a } else { a.inv() });
+            let b = Fp256::from_bytes_reduced(&a.to_bytes());
+            prop_assert_eq!(b.inv_constant_time(), if b.is_zero() { b } else { b.inv() });
+        }
+
+        #[test]
+        fn pow_constant_time_matches_pow_limbs(ref exp in vec(any::<u64>(), 0..5), bits in 0usize..300, x in any::<u64>()) {
+            let x = Fp256::from(x);
+            let mut truncated = exp.clone();
+            for (i, limb) in truncated.iter_mut().enumerate() {
+                if bits <= 64 * i {
+                    *limb = 0;
+                } else if bits < 64 * (i + 1) {
+                    *limb &= (1 << (bits - 64 * i)) - 1;
+                }
+            }
+            prop_assert_eq!(x.pow_constant_time(exp, bits), x.pow_limbs(&truncated));
+        }
+
+        #[test]
+        fn batch_inverse_matches_inv(ref values in vec(1u64.., 0..20)) {
+            let mut values: Vec<Fp256> = values.iter().map(|v| Fp256::from(*v)).collect();
+            let expected: Vec<Fp256> = values.iter().map(|v| v.inv()).collect();
+            batch_inverse(&mut values);
+            prop_assert_eq!(values, expected);
+        }
+
+        #[test]
+        fn multi_pow_matches_pow_limbs(ref terms in vec((any::<u64>(), vec(any::<u64>(), 0..9)), 0..5)) {
+            let terms: Vec<(Fp480, &[u64])> = terms
+                .iter()
+                .map(|(b, e)| (Fp480::from(*b), &e[..]))
+                .collect();
+            let expected = terms
+                .iter()
+                .fold(Fp480::one(), |acc, (b, e)| acc * b.pow_limbs(e));
+            prop_assert_eq!(Fp480::multi_pow(&terms), expected);
+        }
+
+        #[test]
+        fn from_bytes_reduced_matches_wide_reduction(a in any::<[u8; 32]>(), b in any::<[u8; 32]>()) {
+            let mut wide = [0u8; 64];
+            wide[..32].copy_from_slice(&a);
+            wide[32..].copy_from_slice(&b);
+            prop_assert_eq!(Fp256::from_bytes_reduced(&wide), Fp256::from(wide));
+            let x = Fp256::from(a);
+            prop_assert_eq!(Fp256::from_bytes_reduced(&x.to_bytes()), x);
+            prop_assert_eq!(Fp256::from_bytes_reduced(&[]), Fp256::zero());
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Exponentiation of one base to many exponents, as with the generators of ElGamal and Pedersen
+//! commitments. `FixedBase` precomputes `g^(d 2^(w i))` for every `w` bit digit `d` and window
+//! `i` once, after which `g^e` is the product of one table entry per window of `e`: no squarings
+//! and about `bits / w` multiplications, against `bits` squarings and `bits / 2` multiplications
+//! for `pow_limbs`.
+use digits::util::{bit_length, limb_window};
+use field::PrimeField;
+
+/// A base with the table of its powers. See the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBase<F: PrimeField> {
+    base: F,
+    window: usize,
+    bits: usize,
+    // table[i][d] = base^(d 2^(window i))
+    table: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> FixedBase<F> {
+    ///Precompute the table for exponents of up to `bits` bits, taken `window` bits at a time.
+    ///It holds `ceil(bits / window) 2^window` field elements. Panics unless `window` is between
+    ///1 and 16.
+    pub fn new(base: F, bits: usize, window: usize) -> FixedBase<F> {
+        assert!(
+            (1..=16).contains(&window),
+            "The window must be between 1 and 16 bits."
+        );
+        let windows = bits.div_ceil(window);
+        let mut table = Vec::with_capacity(windows);
+        let mut b = base;
+        for _ in 0..windows {
+            let mut row = Vec::with_capacity(1 << window);
+            row.push(F::one());
+            for d in 1..1 << window {
+                let next = row[d - 1] * b;
+                row.push(next);
+            }
+            b = row[(1 << window) - 1] * b;
+            table.push(row);
+        }
+        FixedBase {
+            base,
+            window,
+            bits,
+            table,
+        }
+    }
+
+    pub fn base(&self) -> F {
+        self.base
+    }
+
+    ///The largest exponent size the table covers, in bits.
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    ///`base^exp` for an exponent in limbs, least significant first. Skips the zero digits and
+    ///indexes the table by digit, so the time and memory accesses depend on `exp`; use
+    ///`pow_constant_time` for secret exponents. Panics if `exp` has more than `bits` bits.
+    pub fn pow(&self, exp: &[u64]) -> F {
+        self.check_exponent(exp);
+        let mut ret = F::one();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = limb_window(exp, i * self.window, self.window);
+            if digit != 0 {
+                ret *= row[digit];
+            }
+        }
+        ret
+    }
+
+    ///`base^exp` like `pow`, but reading every entry of each row and keeping the right one with
+    ///`PrimeField::conditional_select`, and multiplying even for zero digits. Which entries
+    ///are read and how many multiplications happen don't depend on `exp`; see `PrimeField` for
+    ///the caveat on the multiplications themselves. Panics if `exp` has more than `bits` bits,
+    ///which only depends on the public bound.
+    pub fn pow_constant_time(&self, exp: &[u64]) -> F {
+        self.check_exponent(exp);
+        let mut ret = F::one();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = limb_window(exp, i * self.window, self.window);
+            let mut entry = row[0];
+            for (d, x) in row.iter().enumerate().skip(1) {
+                entry = F::conditional_select(&entry, x, d == digit);
+            }
+            ret *= entry;
+        }
+        ret
+    }
+
+    fn check_exponent(&self, exp: &[u64]) {
+        assert!(
+            bit_length(exp) <= self.bits,
+            "The exponent has more bits than the table covers."
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::Field;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use num_traits::One;
+    use proptest::prelude::*;
+
+    #[test]
+    fn small_exponents() {
+        let g = Fp480::from(3u64);
+        let table = FixedBase::new(g, 20, 3);
+        assert_eq!(table.base(), g);
+        assert_eq!(table.bits(), 20);
+        assert!(table.pow(&[]).is_one());
+        assert!(table.pow_constant_time(&[0]).is_one());
+        assert_eq!(table.pow(&[1]), g);
+        assert_eq!(table.pow(&[(1 << 20) - 1]), g.pow_limbs(&[(1 << 20) - 1]));
+        assert_eq!(
+            table.pow_constant_time(&[0b1011_0110]),
+            g.pow_limbs(&[0b1011_0110])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "more bits than the table covers")]
+    fn exponent_too_large() {
+        FixedBase::new(Fp256::from(2u64), 64, 4).pow(&[1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "between 1 and 16")]
+    fn window_too_large() {
+        FixedBase::new(Fp256::from(2u64), 64, 17);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn matches_pow_limbs(base in any::<u64>(), exp in any::<[u64; 8]>(), window in 1usize..9) {
+            let mut exp = exp.to_vec();
+            exp[7] &= (1 << (Fp480::PRIMEBITS - 448)) - 1;
+            let g = Fp480::from(base);
+            let table = FixedBase::new(g, Fp480::PRIMEBITS, window);
+            let expected = g.pow_limbs(&exp);
+            prop_assert_eq!(table.pow(&exp), expected);
+            prop_assert_eq!(table.pow_constant_time(&exp), expected);
+        }
+    }
+}
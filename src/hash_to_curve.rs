@@ -0,0 +1,512 @@
+//! Hashing to elliptic curves as in RFC 9380. `hash_to_field` turns the message into field
+//! elements, a map sends each one to a curve point, and clearing the cofactor lands the result in
+//! the prime order group. The `hash_to_curve_*` functions map two field elements and add the
+//! points, which makes the result indistinguishable from a random oracle. The `encode_to_curve_*`
+//! functions map just one, which is cheaper but only reaches some of the points.
+//!
+//! * The simplified SWU map of Brier et al. (section 6.6.2) works on Weierstrass curves with `a`
+//!   and `b` both nonzero, like P-256. Curves with `a = 0`, like secp256k1, get it through an
+//!   isogeny from a curve that has both.
+//! * Elligator 2 (section 6.7.1) works on Montgomery curves `K t^2 = s^3 + J s^2 + s`, and
+//!   through the birational map from one on twisted Edwards curves like edwards25519.
+//!
+//! The maps do the same operations for every input, with `PrimeField::sqrt_ratio` for their one
+//! square root, `PrimeField::inv_constant_time` for their divisions, and
+//! `PrimeField::conditional_select` where the RFC says CMOV. Only the exceptional inputs of an
+//! isogeny, which a hash hits with negligible probability, take a different path.
+use edwards::{Ed25519, EdwardsParams, EdwardsPoint};
+use field::{Field, PrimeField};
+use fp_25519::Fp25519;
+use fp_p256::FpP256;
+use fp_secp256k1::FpSecp256k1;
+use hash_to_field::hash_to_field;
+use hmac::digest::{BlockInput, Digest};
+use num_traits::{One, Zero};
+use poly::Poly;
+use weierstrass::{Affine, Secp256k1, WeierstrassParams, P256};
+
+/// A Weierstrass curve the simplified SWU map reaches, directly or through an isogeny.
+pub trait SswuParams: WeierstrassParams {
+    ///The non-square Z that RFC 9380 fixes for the curve's suites.
+    fn z() -> Self::Base;
+
+    ///The `a` of the curve the map lands on, which is this one unless it goes through an
+    ///isogeny. It and `isogenous_b` have to be nonzero.
+    fn isogenous_a() -> Self::Base {
+        Self::a()
+    }
+
+    fn isogenous_b() -> Self::Base {
+        Self::b()
+    }
+
+    ///The isogeny from the curve of `isogenous_a` and `isogenous_b` to this one, which is the
+    ///identity unless they differ.
+    fn isogeny(x: Self::Base, y: Self::Base) -> Affine<Self> {
+        Affine::new_unchecked(x, y)
+    }
+}
+
+impl SswuParams for P256 {
+    fn z() -> FpP256 {
+        -FpP256::from(10u64)
+    }
+}
+
+///secp256k1 has `a = 0`, so the map goes through the 3-isogeny from
+///`y^2 = x^3 + A' x + 1771` of RFC 9380 appendix E.1.
+impl SswuParams for Secp256k1 {
+    fn z() -> FpSecp256k1 {
+        -FpSecp256k1::from(11u64)
+    }
+
+    fn isogenous_a() -> FpSecp256k1 {
+        FpSecp256k1::new([
+            0x405447c01a444533,
+            0xe953d363cb6f0e5d,
+            0xa08a5558f0f5d272,
+            0x3f8731abdd661adc,
+        ])
+    }
+
+    fn isogenous_b() -> FpSecp256k1 {
+        FpSecp256k1::from(1771u64)
+    }
+
+    fn isogeny(x: FpSecp256k1, y: FpSecp256k1) -> Affine<Secp256k1> {
+        let x_num = Poly::new(vec![
+            FpSecp256k1::new([
+                0x8e38e38daaaaa8c7,
+                0x38e38e38e38e38e3,
+                0xe38e38e38e38e38e,
+                0x8e38e38e38e38e38,
+            ]),
+            FpSecp256k1::new([
+                0xdfff1044f17c6581,
+                0xd595d2fc0bf63b92,
+                0xb9f315cea7fd44c5,
+                0x07d3d4c80bc321d5,
+            ]),
+            FpSecp256k1::new([
+                0x4ecbd0b53d9dd262,
+                0xe4506144037c4031,
+                0xe2a413deca25caec,
+                0x534c328d23f234e6,
+            ]),
+            FpSecp256k1::new([
+                0x8e38e38daaaaa88c,
+                0x38e38e38e38e38e3,
+                0xe38e38e38e38e38e,
+                0x8e38e38e38e38e38,
+            ]),
+        ]);
+        let x_den = Poly::new(vec![
+            FpSecp256k1::new([
+                0x9fe6b745781eb49b,
+                0x86cd409542f8487d,
+                0x9ca34ccbb7b640dd,
+                0xd35771193d94918a,
+            ]),
+            FpSecp256k1::new([
+                0xc52a56612a8c6d14,
+                0x06d36b641f5e41bb,
+                0xf7c4b2d51b542254,
+                0xedadc6f64383dc1d,
+            ]),
+            FpSecp256k1::one(),
+        ]);
+        let y_num = Poly::new(vec![
+            FpSecp256k1::new([
+                0xa12f684b8e38e23c,
+                0x2f684bda12f684bd,
+                0x684bda12f684bda1,
+                0x4bda12f684bda12f,
+            ]),
+            FpSecp256k1::new([
+                0xdffc90fc201d71a3,
+                0x647ab046d686da6f,
+                0xa9d0a54b12a0a6d5,
+                0xc75e0c32d5cb7c0f,
+            ]),
+            FpSecp256k1::new([
+                0xa765e85a9ecee931,
+                0x722830a201be2018,
+                0x715209ef6512e576,
+                0x29a6194691f91a73,
+            ]),
+            FpSecp256k1::new([
+                0x84bda12f38e38d84,
+                0xbda12f684bda12f6,
+                0xa12f684bda12f684,
+                0x2f684bda12f684bd,
+            ]),
+        ]);
+        let y_den = Poly::new(vec![
+            -FpSecp256k1::from(756u64),
+            FpSecp256k1::new([
+                0xdfb425d2685c2573,
+                0x9467c1bfc8e8d978,
+                0xd5e9e6632722c298,
+                0x7a06534bb8bdb49f,
+            ]),
+            FpSecp256k1::new([
+                0xa7bf8192bfd2a76f,
+                0x0a3d21162f0d6299,
+                0xf3a70c3fa8fe337e,
+                0x6484aa716545ca2c,
+            ]),
+            FpSecp256k1::one(),
+        ]);
+        rational_map(x, y, &x_num, &x_den, &y_num, &y_den)
+    }
+}
+
+///The isogeny `(x_num(x) / x_den(x), y y_num(x) / y_den(x))`, or the identity at the roots of
+///the denominators, which are the points of the kernel.
+pub fn rational_map<C: WeierstrassParams>(
+    x: C::Base,
+    y: C::Base,
+    x_num: &Poly<C::Base>,
+    x_den: &Poly<C::Base>,
+    y_num: &Poly<C::Base>,
+    y_den: &Poly<C::Base>,
+) -> Affine<C>
+where
+    C::Base: PrimeField,
+{
+    let (x_den, y_den) = (x_den.evaluate(x), y_den.evaluate(x));
+    if x_den.is_zero() || y_den.is_zero() {
+        return Affine::identity();
+    }
+    Affine::new_unchecked(
+        x_num.evaluate(x) * x_den.inv_constant_time(),
+        y * y_num.evaluate(x) * y_den.inv_constant_time(),
+    )
+}
+
+///The simplified SWU map of `u` onto the curve, through the isogeny if there is one. This is the
+///straight-line version of RFC 9380 appendix F.2.
+pub fn map_to_curve_sswu<C: SswuParams>(u: &C::Base) -> Affine<C>
+where
+    C::Base: PrimeField,
+{
+    let (a, b, z) = (C::isogenous_a(), C::isogenous_b(), C::z());
+    let tv1 = z * u.square();
+    let mut tv2 = tv1.square() + tv1;
+    let tv3 = b * (tv2 + C::Base::one());
+    let tv4 = a * C::Base::conditional_select(&z, &-tv2, !tv2.is_zero());
+    tv2 = tv3.square();
+    let mut tv6 = tv4.square();
+    tv2 = (tv2 + a * tv6) * tv3;
+    tv6 *= tv4;
+    tv2 += b * tv6;
+    // x1 = tv3 / tv4, with g(x1) = tv2 / tv6, and x2 = tv1 x1.
+    let (is_gx1_square, y1) = C::Base::sqrt_ratio(&tv2, &tv6, &z);
+    let x = C::Base::conditional_select(&(tv1 * tv3), &tv3, is_gx1_square);
+    let mut y = C::Base::conditional_select(&(tv1 * *u * y1), &y1, is_gx1_square);
+    y = C::Base::conditional_select(&-y, &y, u.sgn0() == y.sgn0());
+    C::isogeny(x * tv4.inv_constant_time(), y)
+}
+
+///Hash `msg` to a point in the prime order group with the simplified SWU map, as the
+///`*_XMD:*_SSWU_RO_` suites of RFC 9380 do for `D` the suite's hash.
+pub fn hash_to_curve_sswu<C, D>(msg: &[u8], dst: &[u8]) -> Affine<C>
+where
+    C: SswuParams,
+    C::Base: PrimeField,
+    D: Digest + BlockInput,
+{
+    let u = hash_to_field::<C::Base, D>(msg, dst, 2);
+    let q = map_to_curve_sswu::<C>(&u[0]).to_projective()
+        + map_to_curve_sswu::<C>(&u[1]).to_projective();
+    q.clear_cofactor().to_affine_constant_time()
+}
+
+///Encode `msg` as a point in the prime order group with one simplified SWU map, as the
+///`*_XMD:*_SSWU_NU_` suites do.
+pub fn encode_to_curve_sswu<C, D>(msg: &[u8], dst: &[u8]) -> Affine<C>
+where
+    C: SswuParams,
+    C::Base: PrimeField,
+    D: Digest + BlockInput,
+{
+    let u = hash_to_field::<C::Base, D>(msg, dst, 1);
+    map_to_curve_sswu::<C>(&u[0])
+        .to_projective()
+        .clear_cofactor()
+        .to_affine_constant_time()
+}
+
+/// A twisted Edwards curve Elligator 2 reaches through the Montgomery curve
+/// `K t^2 = s^3 + J s^2 + s` it's birationally equivalent to.
+pub trait Elligator2Params: EdwardsParams {
+    fn montgomery_j() -> Self::Base;
+    fn montgomery_k() -> Self::Base;
+    ///The non-square Z that RFC 9380 fixes for the curve's suites.
+    fn z() -> Self::Base;
+    ///The birational map from the Montgomery curve, which sends the points it isn't defined on
+    ///to the identity.
+    fn from_montgomery(s: Self::Base, t: Self::Base) -> EdwardsPoint<Self>;
+}
+
+///edwards25519 through curve25519, `t^2 = s^3 + 486662 s^2 + s`, with the map
+///`(x, y) = (sqrt(-486664) s / t, (s - 1) / (s + 1))` of RFC 7748.
+impl Elligator2Params for Ed25519 {
+    fn montgomery_j() -> Fp25519 {
+        Fp25519::from(486662u64)
+    }
+
+    fn montgomery_k() -> Fp25519 {
+        Fp25519::one()
+    }
+
+    fn z() -> Fp25519 {
+        Fp25519::from(2u64)
+    }
+
+    fn from_montgomery(s: Fp25519, t: Fp25519) -> EdwardsPoint<Ed25519> {
+        // The even square root of -486664.
+        let c1 = Fp25519::new([
+            0xcc6e04aaff457e06,
+            0xc5a1d3d14b7d1a82,
+            0xd27b08dc03fc4f7e,
+            0x0f26edf460a006bb,
+        ]);
+        let (xd, yd) = (t, s + Fp25519::one());
+        let exceptional = (xd * yd).is_zero();
+        let one = Fp25519::one();
+        let xn = Fp25519::conditional_select(&(c1 * s), &Fp25519::zero(), exceptional);
+        let xd = Fp25519::conditional_select(&xd, &one, exceptional);
+        let yn = Fp25519::conditional_select(&(s - one), &one, exceptional);
+        let yd = Fp25519::conditional_select(&yd, &one, exceptional);
+        // One inversion for both denominators.
+        let inv = (xd * yd).inv_constant_time();
+        EdwardsPoint::new_unchecked(xn * yd * inv, yn * xd * inv)
+    }
+}
+
+///The Elligator 2 map of `u` onto the Montgomery curve `k t^2 = s^3 + j s^2 + s`, for the
+///non-square `z`, as `(s, t)`. This is the straight-line version of RFC 9380 appendix F.3.
+pub fn map_to_curve_elligator2<F: PrimeField>(u: &F, j: &F, k: &F, z: &F) -> (F, F) {
+    let c1 = *j / *k;
+    let c2 = (*k).square().inv();
+    let mut tv1 = *z * u.square();
+    tv1 = F::conditional_select(&tv1, &F::zero(), tv1 == -F::one());
+    let x1 = -c1 * (tv1 + F::one()).inv_constant_time();
+    let gx1 = ((x1 + c1) * x1 + c2) * x1;
+    let x2 = -x1 - c1;
+    // When gx1 isn't a square, y1 = sqrt(z gx1) and u y1 is a root of gx2 = tv1 gx1, unless tv1
+    // was zeroed above and gx2 = 0. That saves the second square root of appendix F.3.
+    let (is_gx1_square, y1) = F::sqrt_ratio(&gx1, &F::one(), z);
+    let y2 = F::conditional_select(&(*u * y1), &F::zero(), tv1.is_zero());
+    let x = F::conditional_select(&x2, &x1, is_gx1_square);
+    let mut y = F::conditional_select(&y2, &y1, is_gx1_square);
+    y = F::conditional_select(&y, &-y, is_gx1_square ^ y.sgn0());
+    (x * *k, y * *k)
+}
+
+///The Elligator 2 map of `u` onto a twisted Edwards curve, through its Montgomery form.
+pub fn map_to_edwards_elligator2<C: Elligator2Params>(u: &C::Base) -> EdwardsPoint<C> {
+    let (s, t) = map_to_curve_elligator2(u, &C::montgomery_j(), &C::montgomery_k(), &C::z());
+    C::from_montgomery(s, t)
+}
+
+///Hash `msg` to a point in the prime order group with Elligator 2, as the `*_XMD:*_ELL2_RO_`
+///suites of RFC 9380 do for `D` the suite's hash.
+pub fn hash_to_curve_elligator2<C, D>(msg: &[u8], dst: &[u8]) -> EdwardsPoint<C>
+where
+    C: Elligator2Params,
+    D: Digest + BlockInput,
+{
+    let u = hash_to_field::<C::Base, D>(msg, dst, 2);
+    (map_to_edwards_elligator2::<C>(&u[0]) + map_to_edwards_elligator2::<C>(&u[1])).clear_cofactor()
+}
+
+///Encode `msg` as a point in the prime order group with one Elligator 2 map, as the
+///`*_XMD:*_ELL2_NU_` suites do.
+pub fn encode_to_curve_elligator2<C, D>(msg: &[u8], dst: &[u8]) -> EdwardsPoint<C>
+where
+    C: Elligator2Params,
+    D: Digest + BlockInput,
+{
+    let u = hash_to_field::<C::Base, D>(msg, dst, 1);
+    map_to_edwards_elligator2::<C>(&u[0]).clear_cofactor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use sha2::{Sha256, Sha512};
+    use test_util::to_hex;
+
+    fn hex<F: PrimeField>(x: &F) -> String {
+        to_hex(&x.to_bytes())
+    }
+
+    fn affine_hex<C: WeierstrassParams>(p: &Affine<C>) -> (String, String)
+    where
+        C::Base: PrimeField,
+    {
+        let (x, y) = p.coordinates().unwrap();
+        (hex(&x), hex(&y))
+    }
+
+    fn edwards_hex<C: EdwardsParams>(p: &EdwardsPoint<C>) -> (String, String) {
+        let (x, y) = p.coordinates();
+        (hex(&x), hex(&y))
+    }
+
+    fn pair(x: &str, y: &str) -> (String, String) {
+        (x.to_string(), y.to_string())
+    }
+
+    #[test]
+    fn p256_vectors() {
+        // RFC 9380 appendix J.1.
+        let p = hash_to_curve_sswu::<P256, Sha256>(
+            b"",
+            b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_",
+        );
+        assert_eq!(
+            affine_hex(&p),
+            pair(
+                "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+                "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415"
+            )
+        );
+        let p = hash_to_curve_sswu::<P256, Sha256>(
+            b"abc",
+            b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_",
+        );
+        assert_eq!(
+            affine_hex(&p),
+            pair(
+                "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+                "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e"
+            )
+        );
+        let p = encode_to_curve_sswu::<P256, Sha256>(
+            b"",
+            b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_NU_",
+        );
+        assert_eq!(
+            affine_hex(&p),
+            pair(
+                "f871caad25ea3b59c16cf87c1894902f7e7b2c822c3d3f73596c5ace8ddd14d1",
+                "87b9ae23335bee057b99bac1e68588b18b5691af476234b8971bc4f011ddc99b"
+            )
+        );
+    }
+
+    #[test]
+    fn secp256k1_vectors() {
+        // RFC 9380 appendix J.8.1.
+        let dst = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+        assert_eq!(
+            affine_hex(&hash_to_curve_sswu::<Secp256k1, Sha256>(b"", dst)),
+            pair(
+                "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+                "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067"
+            )
+        );
+        assert_eq!(
+            affine_hex(&hash_to_curve_sswu::<Secp256k1, Sha256>(b"abc", dst)),
+            pair(
+                "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+                "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6"
+            )
+        );
+    }
+
+    #[test]
+    fn edwards25519_vectors() {
+        // RFC 9380 appendix J.5.
+        let p = hash_to_curve_elligator2::<Ed25519, Sha512>(
+            b"",
+            b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_",
+        );
+        assert_eq!(
+            edwards_hex(&p),
+            pair(
+                "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+                "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21"
+            )
+        );
+        let p = encode_to_curve_elligator2::<Ed25519, Sha512>(
+            b"",
+            b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_NU_",
+        );
+        assert_eq!(
+            edwards_hex(&p),
+            pair(
+                "1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da",
+                "222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b"
+            )
+        );
+    }
+
+    #[test]
+    fn curve25519_elligator2() {
+        let (j, k, z) = (
+            Fp25519::from(486662u64),
+            Fp25519::one(),
+            Fp25519::from(2u64),
+        );
+        let (s, t) = map_to_curve_elligator2(&Fp25519::from(12345u64), &j, &k, &z);
+        assert_eq!(
+            (hex(&s), hex(&t)),
+            pair(
+                "13cfd192713c5c299856dce6a1096046d75ad7851334a544bcc501362944fc7f",
+                "13bf31fcfc307c9a42771396fa9a59326e431d357fe46563371bd5cecf2b1cb7"
+            )
+        );
+        // u = 0 lands on the point (0, 0) of order 2, which has no Edwards image but the identity.
+        let (s, t) = map_to_curve_elligator2(&Fp25519::zero(), &j, &k, &z);
+        assert_eq!((s, t), (Fp25519::zero(), Fp25519::zero()));
+        assert!(Ed25519::from_montgomery(s, t).is_identity());
+    }
+
+    #[test]
+    fn isogeny_kernel_maps_to_identity() {
+        // x_den is (x - x0)^2 for the x0 of the points of order 3 in the kernel, and y_den has the
+        // factor x - x0 too.
+        let x0 = FpSecp256k1::new([
+            0x1d6ad4ce6ab9c5a5,
+            0xfc964a4df050df22,
+            0x041da6957255eed5,
+            0x89291c84de3e11f1,
+        ]);
+        assert!(Secp256k1::isogeny(x0, FpSecp256k1::one()).is_identity());
+        let p = Secp256k1::isogeny(x0 + FpSecp256k1::one(), FpSecp256k1::one());
+        assert!(!p.is_identity());
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn sswu_lands_on_the_curve(u in any::<[u8; 32]>()) {
+            let p = map_to_curve_sswu::<P256>(&FpP256::from_bytes_reduced(&u));
+            prop_assert!(p.is_on_curve() && !p.is_identity());
+            let p = map_to_curve_sswu::<Secp256k1>(&FpSecp256k1::from_bytes_reduced(&u));
+            prop_assert!(p.is_on_curve() && !p.is_identity());
+        }
+
+        #[test]
+        fn elligator2_lands_on_the_curve(u in any::<[u8; 32]>()) {
+            let u = Fp25519::from_bytes_reduced(&u);
+            let (j, k, z) = (Fp25519::from(486662u64), Fp25519::one(), Fp25519::from(2u64));
+            let (s, t) = map_to_curve_elligator2(&u, &j, &k, &z);
+            prop_assert_eq!(k * t.square(), ((s + j) * s + Fp25519::one()) * s);
+            // The sign of t follows whether the first candidate for s worked.
+            let tv1 = z * u.square();
+            let x1 = if tv1 == -k { -j } else { -j / (tv1 + k) };
+            if !t.is_zero() {
+                prop_assert_eq!(t.sgn0(), s == x1);
+            }
+            prop_assert!(map_to_edwards_elligator2::<Ed25519>(&u).is_on_curve());
+            let p = hash_to_curve_elligator2::<Ed25519, Sha512>(&u.to_bytes(), b"dst");
+            prop_assert!(p.is_in_subgroup());
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Hashing to prime fields as in RFC 9380, the first step of hashing to a curve.
+//!
+//! `expand_message_xmd` stretches a message into as many uniform bytes as asked for with a
+//! Merkle-Damgard hash such as SHA-256, keyed by a domain separation tag (DST) so that different
+//! protocols, or different uses in one protocol, never get the same output. `hash_to_field` cuts
+//! those bytes into chunks of `L = ceil((ceil(log2 p) + k) / 8)` bytes and reduces each mod p. The
+//! k extra bits make the result within `2^-k` of uniform; RFC 9380 uses k = 128 for nearly every
+//! suite, and the 224 of `hash_to_field_with_security` for curve448.
+use field::PrimeField;
+use hmac::digest::generic_array::typenum::Unsigned;
+use hmac::digest::{BlockInput, Digest};
+
+/// The security parameter k of `hash_to_field`, in bits.
+pub const SECURITY_BITS: usize = 128;
+
+/// A DST longer than this is hashed down first, as RFC 9380 section 5.3.3 says.
+const MAX_DST_BYTES: usize = 255;
+
+///`count` field elements hashed from `msg` under the domain separation tag `dst`, with the
+///default security parameter `SECURITY_BITS`. The RFC 9380 suites pass the same `dst` here as
+///to the rest of the hash to curve.
+pub fn hash_to_field<F, D>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F>
+where
+    F: PrimeField,
+    D: Digest + BlockInput,
+{
+    hash_to_field_with_security::<F, D>(msg, dst, count, SECURITY_BITS)
+}
+
+///`hash_to_field` with a security parameter of `security_bits` instead of `SECURITY_BITS`.
+pub fn hash_to_field_with_security<F, D>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    security_bits: usize,
+) -> Vec<F>
+where
+    F: PrimeField,
+    D: Digest + BlockInput,
+{
+    let len = (F::PRIMEBITS + security_bits).div_ceil(8);
+    expand_message_xmd::<D>(msg, dst, count * len)
+        .chunks(len)
+        .map(F::from_bytes_reduced)
+        .collect()
+}
+
+///`len` uniform bytes from `msg` and the domain separation tag `dst`, per RFC 9380 section 5.3.1.
+///Panics if `len` is over 65535 or needs more than 255 hash outputs.
+pub fn expand_message_xmd<D: Digest + BlockInput>(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let hash_len = D::output_size();
+    let blocks = len.div_ceil(hash_len);
+    assert!(
+        blocks <= 255 && len <= 0xffff,
+        "expand_message_xmd can't produce {} bytes.",
+        len
+    );
+    let long_dst;
+    let dst = if dst.len() > MAX_DST_BYTES {
+        long_dst = D::new().chain(b"H2C-OVERSIZE-DST-").chain(dst).result();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    // DST_prime is the tag followed by its length.
+    let dst_len = [dst.len() as u8];
+    let b0 = D::new()
+        .chain(vec![0u8; D::BlockSize::to_usize()])
+        .chain(msg)
+        .chain([(len >> 8) as u8, len as u8, 0])
+        .chain(dst)
+        .chain(dst_len)
+        .result();
+    let mut bi = D::new()
+        .chain(&b0)
+        .chain([1u8])
+        .chain(dst)
+        .chain(dst_len)
+        .result();
+    let mut ret = bi.to_vec();
+    for i in 2..=blocks {
+        let xored: Vec<u8> = b0.iter().zip(bi.iter()).map(|(x, y)| x ^ y).collect();
+        bi = D::new()
+            .chain(xored)
+            .chain([i as u8])
+            .chain(dst)
+            .chain(dst_len)
+            .result();
+        ret.extend_from_slice(&bi);
+    }
+    ret.truncate(len);
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_25519::Fp25519;
+    use fp_381::Fp381;
+    use fp_p256::FpP256;
+    use fp_secp256k1::FpSecp256k1;
+    use proptest::prelude::*;
+    use sha2::{Sha256, Sha512};
+    use test_util::to_hex;
+
+    fn field_hex<F: PrimeField>(values: &[F]) -> Vec<String> {
+        values
+            .iter()
+            .map(|x| to_hex(&x.to_bytes()).trim_start_matches('0').to_string())
+            .collect()
+    }
+
+    #[test]
+    fn expand_message_xmd_vectors() {
+        // RFC 9380 appendix K.1 and K.3.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            to_hex(&expand_message_xmd::<Sha256>(b"", dst, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            to_hex(&expand_message_xmd::<Sha256>(b"abc", dst, 0x20)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        assert_eq!(
+            to_hex(&expand_message_xmd::<Sha512>(b"", dst, 0x20)),
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"
+        );
+    }
+
+    #[test]
+    fn hash_to_field_vectors() {
+        // The u values of the RFC 9380 appendix J suites for the empty message.
+        assert_eq!(
+            field_hex(&hash_to_field::<FpP256, Sha256>(
+                b"",
+                b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_",
+                2
+            )),
+            vec![
+                "ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009",
+                "8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a",
+            ]
+        );
+        assert_eq!(
+            field_hex(&hash_to_field::<Fp381, Sha256>(
+                b"",
+                b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_",
+                2
+            )),
+            vec![
+                "ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+                "19b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+            ]
+        );
+        assert_eq!(
+            field_hex(&hash_to_field::<Fp25519, Sha512>(
+                b"",
+                b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_",
+                2
+            )),
+            vec![
+                "3fef4813c8cb5f98c6eef88fae174e6e7d5380de2b007799ac7ee712d203f3a",
+                "780bdddd137290c8f589dc687795aafae35f6b674668d92bf92ae793e6a60c75",
+            ]
+        );
+        assert_eq!(
+            field_hex(&hash_to_field::<FpSecp256k1, Sha256>(
+                b"",
+                b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_",
+                2
+            )),
+            vec![
+                "6b0f9910dd2ba71c78f2ee9f04d73b5f4c5f7fc773a701abea1e573cab002fb3",
+                "1ae6c212e08fe1a5937f6202f929a2cc8ef4ee5b9782db68b0d5799fd8f09e16",
+            ]
+        );
+    }
+
+    #[test]
+    fn long_dst_is_hashed() {
+        let long = [b'a'; 256];
+        let short = Sha256::new()
+            .chain(b"H2C-OVERSIZE-DST-")
+            .chain(&long[..])
+            .result();
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"msg", &long, 64),
+            expand_message_xmd::<Sha256>(b"msg", &short, 64)
+        );
+        assert_ne!(
+            expand_message_xmd::<Sha256>(b"msg", &long[..255], 64),
+            expand_message_xmd::<Sha256>(b"msg", &long[..254], 64)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "can't produce")]
+    fn too_many_bytes() {
+        expand_message_xmd::<Sha256>(b"", b"dst", 255 * 32 + 1);
+    }
+
+    proptest! {
+        #[test]
+        fn lengths_are_domain_separated(ref msg in any::<Vec<u8>>(), len in 1usize..300) {
+            // The length is hashed in, so a shorter output isn't a prefix of a longer one.
+            let bytes = expand_message_xmd::<Sha512>(msg, b"dst", len);
+            prop_assert_eq!(bytes.len(), len);
+            let longer = expand_message_xmd::<Sha512>(msg, b"dst", len + 1);
+            prop_assert!(bytes[..] != longer[..len]);
+        }
+    }
+}
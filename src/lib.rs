@@ -1,9 +1,11 @@
+extern crate hmac;
 extern crate num_traits;
-#[cfg(test)]
 extern crate rand;
 #[cfg(test)]
 #[macro_use]
 extern crate proptest;
+#[cfg(test)]
+extern crate sha2;
 
 #[macro_use]
 pub mod digits {
@@ -11,9 +13,41 @@ pub mod digits {
     pub mod unsigned;
     #[macro_use]
     pub mod ff;
+    pub mod naf;
     pub mod util;
 }
 
+pub mod dh;
+pub mod ecdsa;
+pub mod edwards;
+pub mod elgamal;
+pub mod extfield;
+pub mod factor;
+pub mod ffdhe;
+pub mod field;
+pub mod fixed_base;
+pub mod hash_to_curve;
+pub mod hash_to_field;
+pub mod matrix;
+pub mod montgomery;
+pub mod mpc;
+pub mod msm;
+pub mod multipoly;
+pub mod ntt;
+pub mod pairing;
+pub mod poly;
+pub mod power_series;
+pub mod rs;
+pub mod schnorr;
+pub mod shamir;
+pub mod sparse;
+pub mod subgroup;
+#[cfg(test)]
+mod test_util;
+pub mod tower;
+pub mod vss;
+pub mod weierstrass;
+
 const BITSPERBYTE: usize = 8;
 const U64BYTES: usize = 8;
 
@@ -76,6 +110,357 @@ fp!(
     ]
 );
 
+// p = 52435875175126190479447740508185965837690552500527637822603658699938581184513
+//   = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+// This is the scalar field of BLS12-381. p - 1 is divisible by 2^32, which makes it
+// suitable for number-theoretic transforms.
+fp!(
+    fp_255, // Name of mod
+    Fp255,  // Name of class
+    255,    // Number of bits for prime
+    4,      // Number of limbs (ceil(bits/64))
+    [
+        0xffffffff00000001, // prime number in limbs, least sig first
+        0x53bda402fffe5bfe, // get this from sage with p.digits(2^64)
+        0x3339d80809a1d805,
+        0x73eda753299d7d48
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        4788304978035696531,
+        7279011843745230193,
+        4086414915577876179,
+        3841734232051169148,
+        2
+    ]
+);
+
+// p = 57896044618658097711785492504343953926634992332820282019728792003956565016447
+//   = 2^255 + 196479
+// This is a safe prime: q = (p - 1) / 2 is also prime, so the squares mod p form a subgroup
+// of prime order q, generated by 4. Exponents in that subgroup live in fp_safe_256_q.
+fp!(
+    fp_safe_256, // Name of mod
+    FpSafe256,   // Name of class
+    256,         // Number of bits for prime
+    4,           // Number of limbs (ceil(bits/64))
+    [
+        0x2ff7f, // prime number in limbs, least sig first
+        0x0,     // get this from sage with p.digits(2^64)
+        0x0,
+        0x8000000000000000
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        18446744073708765700,
+        18446744073709551615,
+        18446744073709551615,
+        18446744073709551615,
+        1
+    ]
+);
+
+// q = 28948022309329048855892746252171976963317496166410141009864396001978282508223
+//   = (p - 1) / 2 for the p of fp_safe_256
+fp!(
+    fp_safe_256_q, // Name of mod
+    FpSafe256Q,    // Name of class
+    255,           // Number of bits for prime
+    4,             // Number of limbs (ceil(bits/64))
+    [
+        0x17fbf, // prime number in limbs, least sig first
+        0x0,     // get this from sage with p.digits(2^64)
+        0x0,
+        0x4000000000000000
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        18446744073707979792,
+        18446744073709551615,
+        18446744073709551615,
+        18446744073709551615,
+        3
+    ]
+);
+
+// p = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+//   = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47
+// The base field of the BN254 (alt_bn128) pairing-friendly curve.
+fp!(
+    fp_bn254, // Name of mod
+    FpBn254,  // Name of class
+    254,      // Number of bits for prime
+    4,        // Number of limbs (ceil(bits/64))
+    [
+        0x3c208c16d87cfd47, // prime number in limbs, least sig first
+        0x97816a916871ca8d, // get this from sage with p.digits(2^64)
+        0xb85045b68181585d,
+        0x30644e72e131a029
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        17559210185782267473,
+        16818096282501693574,
+        12714969645072974138,
+        5352323811773532794,
+        5
+    ]
+);
+
+// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+//   = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
+// The scalar field of BN254, which is the order of its G1 and G2 groups.
+fp!(
+    fr_bn254, // Name of mod
+    FrBn254,  // Name of class
+    254,      // Number of bits for prime
+    4,        // Number of limbs (ceil(bits/64))
+    [
+        0x43e1f593f0000001, // prime number in limbs, least sig first
+        0x2833e84879b97091, // get this from sage with p.digits(2^64)
+        0xb85045b68181585d,
+        0x30644e72e131a029
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        2337432441630659161,
+        1461508241694919398,
+        12714969645072974151,
+        5352323811773532794,
+        5
+    ]
+);
+
+// p = 57896044618658097711785492504343953926634992332820282019728792003956564819949
+//   = 2^255 - 19
+// The base field of Curve25519 and Ed25519.
+fp!(
+    fp_25519, // Name of mod
+    Fp25519,  // Name of class
+    255,      // Number of bits for prime
+    4,        // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffed, // prime number in limbs, least sig first
+        0xffffffffffffffff, // get this from sage with p.digits(2^64)
+        0xffffffffffffffff,
+        0x7fffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [76, 0, 0, 0, 2]
+);
+
+// l = 7237005577332262213973186563042994240857116359379907606001950938285454250989
+//   = 2^252 + 27742317777372353535851937790883648493
+// The order of the Ed25519 base point, so its scalar field.
+fp!(
+    fr_25519, // Name of mod
+    Fr25519,  // Name of class
+    253,      // Number of bits for prime
+    4,        // Number of limbs (ceil(bits/64))
+    [
+        0x5812631a5cf5d3ed, // prime number in limbs, least sig first
+        0x14def9dea2f79cd6, // get this from sage with p.digits(2^64)
+        0x0,
+        0x1000000000000000
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        17121812371768873755,
+        2379626136568277415,
+        18446744073709551595,
+        18446744073709551615,
+        15
+    ]
+);
+
+// p = 726838724295606890549323807888004534353641360687318060281490199180612328166730772686396383698676545930088884461843637361053498018365439
+//   = 2^448 - 2^224 - 1
+// The base field of Curve448.
+fp!(
+    fp_448, // Name of mod
+    Fp448,  // Name of class
+    448,    // Number of bits for prime
+    7,      // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0xffffffffffffffff, // get this from sage with p.digits(2^64)
+        0xffffffffffffffff,
+        0xfffffffeffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [2, 0, 0, 4294967296, 0, 0, 0, 1]
+);
+
+// p = 4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787
+//   = 0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab
+// The base field of the BLS12-381 pairing-friendly curve.
+fp!(
+    fp_381, // Name of mod
+    Fp381,  // Name of class
+    381,    // Number of bits for prime
+    6,      // Number of limbs (ceil(bits/64))
+    [
+        0xb9feffffffffaaab, // prime number in limbs, least sig first
+        0x1eabfffeb153ffff, // get this from sage with p.digits(2^64)
+        0x6730d2a0f6b0f624,
+        0x64774b84f38512bf,
+        0x4b1ba7b6434bacd7,
+        0x1a0111ea397fe69a
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        1432716382009670190,
+        11056732498555945083,
+        16088953401368869331,
+        1982274466626971979,
+        2886837102847638057,
+        15579590430509909454,
+        9
+    ]
+);
+
+// p = 115792089237316195423570985008687907853269984665640564039457584007908834671663
+//   = 2^256 - 2^32 - 977
+// The base field of secp256k1, the curve of Bitcoin and Ethereum signatures.
+fp!(
+    fp_secp256k1, // Name of mod
+    FpSecp256k1,  // Name of class
+    256,          // Number of bits for prime
+    4,            // Number of limbs (ceil(bits/64))
+    [
+        0xfffffffefffffc2f, // prime number in limbs, least sig first
+        0xffffffffffffffff, // get this from sage with p.digits(2^64)
+        0xffffffffffffffff,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [4294968273, 0, 0, 0, 1]
+);
+
+// n = 115792089237316195423570985008687907852837564279074904382605163141518161494337
+//   = 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141
+// The order of the secp256k1 group, so its scalar field.
+fp!(
+    fr_secp256k1, // Name of mod
+    FrSecp256k1,  // Name of class
+    256,          // Number of bits for prime
+    4,            // Number of limbs (ceil(bits/64))
+    [
+        0xbfd25e8cd0364141, // prime number in limbs, least sig first
+        0xbaaedce6af48a03b, // get this from sage with p.digits(2^64)
+        0xfffffffffffffffe,
+        0xffffffffffffffff
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [4624529908474429120, 4994812053365940164, 1, 0, 1]
+);
+
+// p = 115792089210356248762697446949407573530086143415290314195533631308867097853951
+//   = 2^256 - 2^224 + 2^192 + 2^96 - 1
+// The base field of the NIST P-256 curve (secp256r1).
+fp!(
+    fp_p256, // Name of mod
+    FpP256,  // Name of class
+    256,     // Number of bits for prime
+    4,       // Number of limbs (ceil(bits/64))
+    [
+        0xffffffffffffffff, // prime number in limbs, least sig first
+        0xffffffff,         // get this from sage with p.digits(2^64)
+        0x0,
+        0xffffffff00000001
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [3, 18446744069414584319, 18446744069414584318, 4294967295, 1]
+);
+
+// n = 115792089210356248762697446949407573529996955224135760342422259061068512044369
+//   = 0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551
+// The order of the P-256 group, so its scalar field.
+fp!(
+    fr_p256, // Name of mod
+    FrP256,  // Name of class
+    256,     // Number of bits for prime
+    4,       // Number of limbs (ceil(bits/64))
+    [
+        0xf3b9cac2fc632551, // prime number in limbs, least sig first
+        0xbce6faada7179e84, // get this from sage with p.digits(2^64)
+        0xffffffffffffffff,
+        0xffffffff00000000
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        85565669623438334,
+        4834901528447446049,
+        18446744069414584319,
+        4294967295,
+        1
+    ]
+);
+
+// p = 7526549450832917606187304376969634768865221531653236752398677278921954072893878931913007787255482075114522032598736108109449599688142107407790481670719407
+//   = q (2^256 + 546) + 1 for the q of fp_256
+// A Schnorr group prime: the subgroup of order q is small next to p, unlike the squares mod a
+// safe prime.
+fp!(
+    fp_schnorr_512, // Name of mod
+    FpSchnorr512,   // Name of class
+    512,            // Number of bits for prime
+    8,              // Number of limbs (ceil(bits/64))
+    [
+        0xf5a7bf208e50c7af, // prime number in limbs, least sig first
+        0x5f39ce07c38d5b2f, // get this from sage with p.digits(2^64)
+        0x82b6e13ffd5d805e,
+        0x800e06c530c8027d,
+        0x185cac6c5e089799,
+        0xee5b88d120b5b59e,
+        0xaa6fecb86184dc21,
+        0x8fb501e34aa387f9
+    ],
+    // barrett reduction for reducing values up to twice
+    // the number of prime bits (double limbs):
+    // floor(2^(64*numlimbs*2)/p)
+    [
+        16683554413248452809,
+        11779472134270549362,
+        9764983998034366396,
+        4040465660946151194,
+        4057416362780366842,
+        12897237271039966353,
+        2174143271902072370,
+        14414317039193118239,
+        1
+    ]
+);
+
 impl From<[u8; 64]> for fp_256::Fp256 {
     fn from(src: [u8; 64]) -> Self {
         // our input is the exact length we need for our
@@ -528,6 +913,35 @@ mod lib {
         assert_eq!(c / b, a);
         assert_eq!(c / a, b);
     }
+    #[test]
+    fn barrett_reduction_prime_filling_its_limbs() {
+        // p = 2^448 - 2^224 - 1 uses every bit of its seven limbs, so the remainder before the
+        // final subtractions can overflow them.
+        // x = 12345 * p + 2^224 + 5, where the quotient estimate is one too small.
+        let x = [
+            0xffffffffffffcfcc,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0xffffcfc7ffffffff,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0x3038,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ];
+        assert_eq!(
+            fp_448::reduce_barrett(&x),
+            [0x5, 0x0, 0x0, 0x100000000, 0x0, 0x0, 0x0]
+        );
+        let minus_one = -fp_448::Fp448::one();
+        assert_eq!(minus_one * minus_one, fp_448::Fp448::one());
+    }
+
     #[test]
     fn barrett_reduction() {
         // max
@@ -0,0 +1,474 @@
+//! Dense matrices over any field in the crate, with Gaussian elimination based rank, determinant,
+//! inverse and linear system solving.
+//!
+//! Elimination is done fraction-free (rows are combined with multiplications only) and the pivots
+//! are inverted together at the end with `batch_inverse`, so reducing a matrix costs a single
+//! field inversion however large it is.
+use field::{batch_inverse, Field};
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+/// A `rows` x `cols` matrix stored in row-major order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<F: Field> {
+    rows: usize,
+    cols: usize,
+    data: Vec<F>,
+}
+
+/// The solutions of a linear system `A x = b`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution<F: Field> {
+    ///There is no `x` with `A x = b`.
+    Inconsistent,
+    ///Every solution is `particular` plus a linear combination of the `kernel` vectors, which
+    ///are a basis for the null space of `A`. The solution is unique if `kernel` is empty.
+    Consistent {
+        particular: Vec<F>,
+        kernel: Vec<Vec<F>>,
+    },
+}
+
+impl<F: Field> Matrix<F> {
+    ///Create a matrix from its entries in row-major order. Panics unless there are exactly
+    ///`rows * cols` of them.
+    pub fn new(rows: usize, cols: usize, data: Vec<F>) -> Matrix<F> {
+        assert_eq!(data.len(), rows * cols, "Matrix data has the wrong length.");
+        Matrix { rows, cols, data }
+    }
+
+    ///Create a matrix from a vector of rows. Panics if the rows aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<F>>) -> Matrix<F> {
+        let cols = rows.first().map_or(0, |r| r.len());
+        let num_rows = rows.len();
+        let mut data = Vec::with_capacity(num_rows * cols);
+        for row in rows {
+            assert_eq!(row.len(), cols, "All rows must be the same length.");
+            data.extend(row);
+        }
+        Matrix::new(num_rows, cols, data)
+    }
+
+    pub fn zero(rows: usize, cols: usize) -> Matrix<F> {
+        Matrix::new(rows, cols, vec![F::zero(); rows * cols])
+    }
+
+    pub fn identity(n: usize) -> Matrix<F> {
+        let mut ret = Matrix::zero(n, n);
+        for i in 0..n {
+            ret[(i, i)] = F::one();
+        }
+        ret
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[F] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Matrix<F> {
+        let mut data = Vec::with_capacity(self.data.len());
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                data.push(self[(i, j)]);
+            }
+        }
+        Matrix::new(self.cols, self.rows, data)
+    }
+
+    pub fn scale(&self, c: F) -> Matrix<F> {
+        Matrix::new(
+            self.rows,
+            self.cols,
+            self.data.iter().map(|x| *x * c).collect(),
+        )
+    }
+
+    ///The matrix-vector product `A v`. Panics if `v` doesn't have `cols` entries.
+    pub fn mul_vec(&self, v: &[F]) -> Vec<F> {
+        assert_eq!(v.len(), self.cols, "Vector has the wrong length.");
+        (0..self.rows)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .zip(v)
+                    .fold(F::zero(), |acc, (a, b)| acc + *a * *b)
+            })
+            .collect()
+    }
+
+    ///The reduced row echelon form and the columns which hold its pivots.
+    pub fn rref(&self) -> (Matrix<F>, Vec<usize>) {
+        let (mut m, pivots, _) = self.echelon();
+        let mut pivot_values: Vec<F> = pivots.iter().enumerate().map(|(i, &c)| m[(i, c)]).collect();
+        batch_inverse(&mut pivot_values);
+        for (i, (&c, inv)) in pivots.iter().zip(pivot_values).enumerate() {
+            for j in c..m.cols {
+                m[(i, j)] *= inv;
+            }
+        }
+        // Clear above each pivot, which are all one now.
+        for (i, &c) in pivots.iter().enumerate().rev() {
+            for k in 0..i {
+                let factor = m[(k, c)];
+                if factor.is_zero() {
+                    continue;
+                }
+                for j in c..m.cols {
+                    let below = m[(i, j)];
+                    m[(k, j)] -= factor * below;
+                }
+            }
+        }
+        (m, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.echelon().1.len()
+    }
+
+    ///The determinant. Panics if the matrix isn't square.
+    pub fn determinant(&self) -> F {
+        assert!(self.is_square(), "Only square matrices have determinants.");
+        let (echelon, pivots, scale) = self.echelon();
+        if pivots.len() < self.rows {
+            return F::zero();
+        }
+        // The echelon form is upper triangular with det = scale * det(self).
+        let diagonal = (0..self.rows).fold(F::one(), |acc, i| acc * echelon[(i, i)]);
+        diagonal / scale
+    }
+
+    ///The inverse, or None if the matrix is singular. Panics if the matrix isn't square.
+    pub fn inverse(&self) -> Option<Matrix<F>> {
+        assert!(self.is_square(), "Only square matrices have inverses.");
+        let n = self.rows;
+        let augmented = self.augment(&Matrix::identity(n));
+        let (reduced, pivots) = augmented.rref();
+        if pivots.len() < n || pivots[n - 1] >= n {
+            return None;
+        }
+        let mut data = Vec::with_capacity(n * n);
+        for i in 0..n {
+            data.extend_from_slice(&reduced.row(i)[n..]);
+        }
+        Some(Matrix::new(n, n, data))
+    }
+
+    ///A basis for the null space `{x : A x = 0}`.
+    pub fn kernel(&self) -> Vec<Vec<F>> {
+        let (reduced, pivots) = self.rref();
+        kernel_from_rref(&reduced, &pivots, self.cols)
+    }
+
+    ///Solve `A x = b`. Panics if `b` doesn't have `rows` entries.
+    pub fn solve(&self, b: &[F]) -> Solution<F> {
+        assert_eq!(b.len(), self.rows, "Right hand side has the wrong length.");
+        let column = Matrix::new(self.rows, 1, b.to_vec());
+        let (reduced, pivots) = self.augment(&column).rref();
+        if pivots.last() == Some(&self.cols) {
+            // a row reading 0 = 1
+            return Solution::Inconsistent;
+        }
+        let mut particular = vec![F::zero(); self.cols];
+        for (i, &p) in pivots.iter().enumerate() {
+            particular[p] = reduced[(i, self.cols)];
+        }
+        Solution::Consistent {
+            particular,
+            kernel: kernel_from_rref(&reduced, &pivots, self.cols),
+        }
+    }
+
+    ///The matrix with the columns of `other` appended on the right.
+    pub fn augment(&self, other: &Matrix<F>) -> Matrix<F> {
+        assert_eq!(self.rows, other.rows, "Matrices must have the same rows.");
+        let mut data = Vec::with_capacity(self.data.len() + other.data.len());
+        for i in 0..self.rows {
+            data.extend_from_slice(self.row(i));
+            data.extend_from_slice(other.row(i));
+        }
+        Matrix::new(self.rows, self.cols + other.cols, data)
+    }
+
+    ///Fraction-free forward elimination to row echelon form. Returns the echelon form, the pivot
+    ///columns and the factor `s` with `det(echelon) = s * det(self)`.
+    fn echelon(&self) -> (Matrix<F>, Vec<usize>, F) {
+        let mut m = self.clone();
+        let mut pivots = Vec::new();
+        let mut scale = F::one();
+        for col in 0..m.cols {
+            let r = pivots.len();
+            if r == m.rows {
+                break;
+            }
+            let found = (r..m.rows).find(|&i| !m[(i, col)].is_zero());
+            let p = match found {
+                Some(p) => p,
+                None => continue,
+            };
+            if p != r {
+                m.swap_rows(p, r);
+                scale = -scale;
+            }
+            let pivot = m[(r, col)];
+            for i in r + 1..m.rows {
+                let factor = m[(i, col)];
+                if factor.is_zero() {
+                    continue;
+                }
+                // row_i <- pivot * row_i - factor * row_r
+                for j in col..m.cols {
+                    let above = m[(r, j)];
+                    m[(i, j)] = pivot * m[(i, j)] - factor * above;
+                }
+                scale *= pivot;
+            }
+            pivots.push(col);
+        }
+        (m, pivots, scale)
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..self.cols {
+            self.data.swap(a * self.cols + j, b * self.cols + j);
+        }
+    }
+}
+
+///Null space basis from a reduced row echelon form: one vector per free column among the first
+///`cols` columns.
+fn kernel_from_rref<F: Field>(reduced: &Matrix<F>, pivots: &[usize], cols: usize) -> Vec<Vec<F>> {
+    (0..cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut v = vec![F::zero(); cols];
+            v[free] = F::one();
+            for (i, &p) in pivots.iter().enumerate() {
+                v[p] = -reduced[(i, free)];
+            }
+            v
+        })
+        .collect()
+}
+
+impl<F: Field> Index<(usize, usize)> for Matrix<F> {
+    type Output = F;
+    #[inline]
+    fn index(&self, (i, j): (usize, usize)) -> &F {
+        assert!(i < self.rows && j < self.cols, "Matrix index out of range.");
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<F: Field> IndexMut<(usize, usize)> for Matrix<F> {
+    #[inline]
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut F {
+        assert!(i < self.rows && j < self.cols, "Matrix index out of range.");
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<F: Field> Add<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn add(self, other: &Matrix<F>) -> Matrix<F> {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "Matrices must be the same shape."
+        );
+        Matrix::new(
+            self.rows,
+            self.cols,
+            self.data
+                .iter()
+                .zip(other.data.iter())
+                .map(|(a, b)| *a + *b)
+                .collect(),
+        )
+    }
+}
+
+impl<F: Field> Sub<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn sub(self, other: &Matrix<F>) -> Matrix<F> {
+        self + &(-other)
+    }
+}
+
+impl<F: Field> Mul<&Matrix<F>> for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn mul(self, other: &Matrix<F>) -> Matrix<F> {
+        assert_eq!(
+            self.cols, other.rows,
+            "Matrix dimensions don't match for multiplication."
+        );
+        let mut ret = Matrix::zero(self.rows, other.cols);
+        for i in 0..self.rows {
+            for (k, a) in self.row(i).iter().enumerate() {
+                if a.is_zero() {
+                    continue;
+                }
+                for j in 0..other.cols {
+                    ret[(i, j)] += *a * other[(k, j)];
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl<F: Field> Neg for &Matrix<F> {
+    type Output = Matrix<F>;
+    fn neg(self) -> Matrix<F> {
+        Matrix::new(
+            self.rows,
+            self.cols,
+            self.data.iter().map(|x| -*x).collect(),
+        )
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! matrix_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<F: Field> $imp for Matrix<F> {
+        type Output = Matrix<F>;
+        #[inline]
+        fn $method(self, other: Matrix<F>) -> Matrix<F> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+matrix_owned_ops! { Add, add; Sub, sub; Mul, mul }
+
+impl<F: Field> Neg for Matrix<F> {
+    type Output = Matrix<F>;
+    #[inline]
+    fn neg(self) -> Matrix<F> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn m(rows: usize, cols: usize, data: &[i64]) -> Matrix<Fp256> {
+        Matrix::new(rows, cols, data.iter().map(|&x| Fp256::from(x)).collect())
+    }
+
+    fn v(data: &[i64]) -> Vec<Fp256> {
+        data.iter().map(|&x| Fp256::from(x)).collect()
+    }
+
+    ///Square matrices with small entries, so singular ones turn up often.
+    fn arb_matrix(n: usize) -> impl Strategy<Value = Matrix<Fp256>> {
+        vec(-2i64..3, n * n).prop_map(move |data| m(n, n, &data))
+    }
+
+    #[test]
+    fn known_determinant_and_inverse() {
+        let a = m(3, 3, &[2, 3, 1, 1, 2, 1, 1, 1, 1]);
+        assert_eq!(a.determinant(), Fp256::from(1u64));
+        assert_eq!(a.inverse(), Some(m(3, 3, &[1, -2, 1, 0, 1, -1, -1, 1, 1])));
+        // rows 0 and 2 are multiples of each other
+        let singular = m(3, 3, &[1, 2, 3, 0, 1, 4, 2, 4, 6]);
+        assert_eq!(singular.determinant(), Fp256::from(0u64));
+        assert_eq!(singular.inverse(), None);
+        assert_eq!(singular.rank(), 2);
+        // swapping two rows of the identity negates the determinant
+        assert_eq!(m(2, 2, &[0, 1, 1, 0]).determinant(), Fp256::from(-1i64));
+    }
+
+    #[test]
+    fn rref_of_known_matrix() {
+        let a = m(2, 3, &[2, 4, 6, 1, 3, 5]);
+        let (reduced, pivots) = a.rref();
+        assert_eq!(reduced, m(2, 3, &[1, 0, -1, 0, 1, 2]));
+        assert_eq!(pivots, vec![0, 1]);
+        assert_eq!(a.transpose(), m(3, 2, &[2, 1, 4, 3, 6, 5]));
+    }
+
+    #[test]
+    fn solve_known_systems() {
+        // x + y = 3, x - y = 1
+        let a = m(2, 2, &[1, 1, 1, -1]);
+        assert_eq!(
+            a.solve(&v(&[3, 1])),
+            Solution::Consistent {
+                particular: v(&[2, 1]),
+                kernel: vec![],
+            }
+        );
+        // x + y = 1, 2x + 2y = 3 has no solution
+        let a = m(2, 2, &[1, 1, 2, 2]);
+        assert_eq!(a.solve(&v(&[1, 3])), Solution::Inconsistent);
+        // ... but 2x + 2y = 2 gives the line (1, 0) + t(-1, 1)
+        assert_eq!(
+            a.solve(&v(&[1, 2])),
+            Solution::Consistent {
+                particular: v(&[1, 0]),
+                kernel: vec![v(&[-1, 1])],
+            }
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn inverse_is_inverse(a in arb_matrix(4)) {
+            match a.inverse() {
+                Some(inverse) => {
+                    prop_assert_eq!(&a * &inverse, Matrix::identity(4));
+                    prop_assert_eq!(&inverse * &a, Matrix::identity(4));
+                    prop_assert_eq!(a.determinant() * inverse.determinant(), Fp256::from(1u64));
+                }
+                None => {
+                    prop_assert_eq!(a.determinant(), Fp256::from(0u64));
+                    prop_assert!(a.rank() < 4);
+                }
+            }
+        }
+
+        #[test]
+        fn determinant_is_multiplicative(a in arb_matrix(3), b in arb_matrix(3)) {
+            prop_assert_eq!((&a * &b).determinant(), a.determinant() * b.determinant());
+            prop_assert_eq!(a.transpose().determinant(), a.determinant());
+            prop_assert_eq!(a.transpose().rank(), a.rank());
+        }
+
+        #[test]
+        fn solutions_solve(ref data in vec(-2i64..3, 12), ref b in vec(-2i64..3, 3)) {
+            let a = m(3, 4, data);
+            let b = v(b);
+            let kernel = a.kernel();
+            prop_assert_eq!(kernel.len() + a.rank(), 4);
+            for k in kernel.iter() {
+                prop_assert_eq!(a.mul_vec(k), v(&[0, 0, 0]));
+            }
+            match a.solve(&b) {
+                Solution::Consistent { particular, kernel: solve_kernel } => {
+                    prop_assert_eq!(a.mul_vec(&particular), b);
+                    prop_assert_eq!(solve_kernel, kernel);
+                }
+                Solution::Inconsistent => {
+                    // b isn't in the column space, so appending it raises the rank
+                    let column = Matrix::new(3, 1, b);
+                    prop_assert_eq!(a.augment(&column).rank(), a.rank() + 1);
+                }
+            }
+        }
+    }
+}
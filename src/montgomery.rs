@@ -0,0 +1,225 @@
+//! The X25519 and X448 Diffie-Hellman functions of RFC 7748, which work with just the
+//! u-coordinate of points on the Montgomery curves Curve25519 and Curve448.
+//!
+//! The ladder does the same field operations in the same order whatever the scalar, and swaps
+//! with `PrimeField::conditional_swap` instead of branching. See `PrimeField` for how far that
+//! goes.
+use field::PrimeField;
+use fp_25519::Fp25519;
+use fp_448::Fp448;
+
+/// The u-coordinate of the Curve25519 base point, 9.
+pub const X25519_BASEPOINT: [u8; 32] = {
+    let mut u = [0u8; 32];
+    u[0] = 9;
+    u
+};
+
+/// The u-coordinate of the Curve448 base point, 5.
+pub const X448_BASEPOINT: [u8; 56] = {
+    let mut u = [0u8; 56];
+    u[0] = 5;
+    u
+};
+
+///Multiply the point with u-coordinate `u` by `scalar`, both little-endian. The scalar is
+///clamped to a multiple of the cofactor 8 with bit 254 set, and the top bit of `u` is ignored.
+///Public keys are `x25519(secret, &X25519_BASEPOINT)`. An all zero result means `u` was a point
+///of small order, which callers may want to reject.
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    let mut u = *u;
+    u[31] &= 127;
+    u.reverse();
+    let mut ret = ladder(&k, 255, Fp25519::from(u), Fp25519::from(121665u64)).to_bytes_array();
+    ret.reverse();
+    ret
+}
+
+///Multiply the point with u-coordinate `u` by `scalar`, both little-endian. The scalar is
+///clamped to a multiple of the cofactor 4 with bit 447 set. Public keys are
+///`x448(secret, &X448_BASEPOINT)`. An all zero result means `u` was a point of small order.
+pub fn x448(scalar: &[u8; 56], u: &[u8; 56]) -> [u8; 56] {
+    let mut k = *scalar;
+    k[0] &= 252;
+    k[55] |= 128;
+    let mut u = *u;
+    u.reverse();
+    let mut ret = ladder(&k, 448, Fp448::from(u), Fp448::from(39081u64)).to_bytes_array();
+    ret.reverse();
+    ret
+}
+
+///The Montgomery ladder of RFC 7748 section 5 on the low `bits` bits of the little-endian
+///`scalar`, where `a24 = (A - 2) / 4` for the curve `v^2 = u^3 + A u^2 + u`.
+fn ladder<F: PrimeField>(scalar: &[u8], bits: usize, u: F, a24: F) -> F {
+    let (mut x2, mut z2) = (F::one(), F::zero());
+    let (mut x3, mut z3) = (u, F::one());
+    let mut swap = false;
+    for t in (0..bits).rev() {
+        let bit = (scalar[t / 8] >> (t % 8)) & 1 == 1;
+        swap ^= bit;
+        F::conditional_swap(&mut x2, &mut x3, swap);
+        F::conditional_swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = x2 + z2;
+        let aa = a.square();
+        let b = x2 - z2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x3 + z3;
+        let d = x3 - z3;
+        let da = d * a;
+        let cb = c * b;
+        x3 = (da + cb).square();
+        z3 = u * (da - cb).square();
+        x2 = aa * bb;
+        z2 = e * (aa + a24 * e);
+    }
+    F::conditional_swap(&mut x2, &mut x3, swap);
+    F::conditional_swap(&mut z2, &mut z3, swap);
+    x2 * z2.inv_constant_time()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_util::from_hex;
+
+    fn x25519_hex(scalar: &str, u: &str) -> Vec<u8> {
+        let (mut k, mut v) = ([0u8; 32], [0u8; 32]);
+        k.copy_from_slice(&from_hex(scalar));
+        v.copy_from_slice(&from_hex(u));
+        x25519(&k, &v).to_vec()
+    }
+
+    fn x448_hex(scalar: &str, u: &str) -> Vec<u8> {
+        let (mut k, mut v) = ([0u8; 56], [0u8; 56]);
+        k.copy_from_slice(&from_hex(scalar));
+        v.copy_from_slice(&from_hex(u));
+        x448(&k, &v).to_vec()
+    }
+
+    #[test]
+    fn rfc7748_x25519_vectors() {
+        assert_eq!(
+            x25519_hex(
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c"
+            ),
+            from_hex("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+        // The top bit of this u is set and must be ignored.
+        assert_eq!(
+            x25519_hex(
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493"
+            ),
+            from_hex("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+    }
+
+    #[test]
+    fn rfc7748_x448_vectors() {
+        assert_eq!(
+            x448_hex(
+                "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+                "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086"
+            ),
+            from_hex("ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f")
+        );
+        assert_eq!(
+            x448_hex(
+                "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+                "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db"
+            ),
+            from_hex("884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d")
+        );
+    }
+
+    #[test]
+    fn rfc7748_iterated() {
+        // k = u = the base point, then repeatedly k, u = f(k, u), k.
+        let (mut k, mut u) = (X25519_BASEPOINT, X25519_BASEPOINT);
+        for i in 0..1000 {
+            let next = x25519(&k, &u);
+            u = k;
+            k = next;
+            if i == 0 {
+                assert_eq!(
+                    k.to_vec(),
+                    from_hex("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k.to_vec(),
+            from_hex("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+
+        let (mut k, mut u) = (X448_BASEPOINT, X448_BASEPOINT);
+        for i in 0..1000 {
+            let next = x448(&k, &u);
+            u = k;
+            k = next;
+            if i == 0 {
+                assert_eq!(
+                    k.to_vec(),
+                    from_hex("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113")
+                );
+            }
+        }
+        assert_eq!(
+            k.to_vec(),
+            from_hex("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38")
+        );
+    }
+
+    #[test]
+    fn rfc7748_diffie_hellman() {
+        let base = "0900000000000000000000000000000000000000000000000000000000000000";
+        let alice = "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+        let bob = "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb";
+        let alice_public = "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a";
+        let bob_public = "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f";
+        let shared = from_hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519_hex(alice, base), from_hex(alice_public));
+        assert_eq!(x25519_hex(bob, base), from_hex(bob_public));
+        assert_eq!(x25519_hex(alice, bob_public), shared);
+        assert_eq!(x25519_hex(bob, alice_public), shared);
+
+        let base = "0500000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+        let alice = "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b";
+        let bob = "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d";
+        let alice_public = "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0";
+        let bob_public = "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609";
+        let shared = from_hex("07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d");
+        assert_eq!(x448_hex(alice, base), from_hex(alice_public));
+        assert_eq!(x448_hex(bob, base), from_hex(bob_public));
+        assert_eq!(x448_hex(alice, bob_public), shared);
+        assert_eq!(x448_hex(bob, alice_public), shared);
+    }
+
+    #[test]
+    fn small_order_points_give_zero() {
+        let scalar = [0x42u8; 32];
+        assert_eq!(x25519(&scalar, &[0u8; 32]), [0u8; 32]);
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(x25519(&scalar, &one), [0u8; 32]);
+    }
+
+    proptest! {
+        #[test]
+        fn x25519_shared_secrets_agree(a in any::<[u8; 32]>(), b in any::<[u8; 32]>()) {
+            let alice_public = x25519(&a, &X25519_BASEPOINT);
+            let bob_public = x25519(&b, &X25519_BASEPOINT);
+            prop_assert_eq!(x25519(&a, &bob_public), x25519(&b, &alice_public));
+        }
+    }
+}
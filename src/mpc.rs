@@ -0,0 +1,269 @@
+//! Additive secret sharing among `n` parties, simulated in process for prototyping MPC protocols.
+//! A value is shared as `n` random field elements which sum to it. Parties can add shared values
+//! and multiply them by public constants locally, and multiply two shared values with a Beaver
+//! triple `(a, b, ab)` handed out by a trusted dealer, which costs one opening of two values.
+//!
+//! Every party's share lives in the same `Shared`, and all the randomness comes from the dealer's
+//! rng, so seeding it makes a whole protocol run deterministic.
+use field::Field;
+use rand::Rng;
+use std::ops::{Add, Neg, Sub};
+
+/// A value secret-shared among some number of parties. Share `i` belongs to party `i`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shared<F: Field> {
+    shares: Vec<F>,
+}
+
+/// Random shared `a` and `b` with `c = ab`, used up by one multiplication.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaverTriple<F: Field> {
+    pub a: Shared<F>,
+    pub b: Shared<F>,
+    pub c: Shared<F>,
+}
+
+impl<F: Field> Shared<F> {
+    ///Split `secret` into `parties` random shares. Panics if `parties` is zero.
+    pub fn share<R: Rng>(secret: F, parties: usize, rng: &mut R) -> Shared<F> {
+        assert!(parties > 0, "There must be at least one party.");
+        let mut shares: Vec<F> = (1..parties).map(|_| F::random(rng)).collect();
+        let rest = shares.iter().fold(secret, |acc, s| acc - *s);
+        shares.push(rest);
+        Shared { shares }
+    }
+
+    ///Collect shares the parties already hold. Panics if there are none.
+    pub fn from_shares(shares: Vec<F>) -> Shared<F> {
+        assert!(!shares.is_empty(), "There must be at least one party.");
+        Shared { shares }
+    }
+
+    pub fn shares(&self) -> &[F] {
+        &self.shares
+    }
+
+    pub fn parties(&self) -> usize {
+        self.shares.len()
+    }
+
+    ///Open the value: every party broadcasts their share and everyone sums them.
+    pub fn reveal(&self) -> F {
+        self.shares.iter().fold(F::zero(), |acc, s| acc + *s)
+    }
+
+    ///Add a public constant, which only party 0 has to do.
+    pub fn add_constant(&self, c: F) -> Shared<F> {
+        let mut shares = self.shares.clone();
+        shares[0] += c;
+        Shared { shares }
+    }
+
+    ///Multiply by a public constant, which every party does to their own share.
+    pub fn scale(&self, c: F) -> Shared<F> {
+        Shared {
+            shares: self.shares.iter().map(|s| *s * c).collect(),
+        }
+    }
+
+    ///Multiply two shared values using up `triple`. The parties open `d = x - a` and `e = y - b`,
+    ///which reveal nothing since `a` and `b` are uniform, and then
+    ///`xy = c + d b + e a + d e` is linear in the shares. Panics if the party counts differ.
+    pub fn beaver_mul(&self, other: &Shared<F>, triple: BeaverTriple<F>) -> Shared<F> {
+        let d = (self - &triple.a).reveal();
+        let e = (other - &triple.b).reveal();
+        (&(&triple.c + &triple.b.scale(d)) + &triple.a.scale(e)).add_constant(d * e)
+    }
+
+    fn check_parties(&self, other: &Shared<F>) {
+        assert_eq!(
+            self.parties(),
+            other.parties(),
+            "Shared values must have the same parties."
+        );
+    }
+}
+
+/// The trusted dealer, which shares the inputs and hands out Beaver triples for a fixed set of
+/// parties.
+#[derive(Debug)]
+pub struct Dealer<R: Rng> {
+    parties: usize,
+    rng: R,
+    triples: usize,
+}
+
+impl<R: Rng> Dealer<R> {
+    ///A dealer for `parties` parties. Panics if `parties` is zero.
+    pub fn new(parties: usize, rng: R) -> Dealer<R> {
+        assert!(parties > 0, "There must be at least one party.");
+        Dealer {
+            parties,
+            rng,
+            triples: 0,
+        }
+    }
+
+    pub fn parties(&self) -> usize {
+        self.parties
+    }
+
+    ///How many triples have been dealt, which is how many multiplications have been done.
+    pub fn triples_dealt(&self) -> usize {
+        self.triples
+    }
+
+    ///Share an input among the parties.
+    pub fn share<F: Field>(&mut self, secret: F) -> Shared<F> {
+        Shared::share(secret, self.parties, &mut self.rng)
+    }
+
+    ///A fresh Beaver triple.
+    pub fn triple<F: Field>(&mut self) -> BeaverTriple<F> {
+        let a = F::random(&mut self.rng);
+        let b = F::random(&mut self.rng);
+        self.triples += 1;
+        BeaverTriple {
+            a: self.share(a),
+            b: self.share(b),
+            c: self.share(a * b),
+        }
+    }
+
+    ///Multiply two shared values with a fresh triple.
+    pub fn mul<F: Field>(&mut self, x: &Shared<F>, y: &Shared<F>) -> Shared<F> {
+        let triple = self.triple();
+        x.beaver_mul(y, triple)
+    }
+}
+
+impl<F: Field> Add<&Shared<F>> for &Shared<F> {
+    type Output = Shared<F>;
+    fn add(self, other: &Shared<F>) -> Shared<F> {
+        self.check_parties(other);
+        Shared {
+            shares: self
+                .shares
+                .iter()
+                .zip(&other.shares)
+                .map(|(a, b)| *a + *b)
+                .collect(),
+        }
+    }
+}
+
+impl<F: Field> Sub<&Shared<F>> for &Shared<F> {
+    type Output = Shared<F>;
+    fn sub(self, other: &Shared<F>) -> Shared<F> {
+        self + &(-other)
+    }
+}
+
+impl<F: Field> Neg for &Shared<F> {
+    type Output = Shared<F>;
+    fn neg(self) -> Shared<F> {
+        Shared {
+            shares: self.shares.iter().map(|s| -*s).collect(),
+        }
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! shared_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<F: Field> $imp for Shared<F> {
+        type Output = Shared<F>;
+        #[inline]
+        fn $method(self, other: Shared<F>) -> Shared<F> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+shared_owned_ops! { Add, add; Sub, sub }
+
+impl<F: Field> Neg for Shared<F> {
+    type Output = Shared<F>;
+    #[inline]
+    fn neg(self) -> Shared<F> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn single_party_holds_the_secret() {
+        let mut rng = rng_from_seed(37);
+        let x = Shared::share(Fp256::from(5u64), 1, &mut rng);
+        assert_eq!(x.shares(), &[Fp256::from(5u64)]);
+    }
+
+    #[test]
+    fn runs_are_deterministic() {
+        let run = |seed| {
+            let mut dealer = Dealer::new(3, rng_from_seed(seed));
+            let x = dealer.share(Fp480::from(6u64));
+            let y = dealer.share(Fp480::from(7u64));
+            dealer.mul(&x, &y)
+        };
+        let z = run(1);
+        assert_eq!(z.reveal(), Fp480::from(42u64));
+        assert_eq!(z, run(1));
+        assert_ne!(z, run(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "same parties")]
+    fn mismatched_parties_panic() {
+        let mut rng = rng_from_seed(38);
+        let x = Shared::share(Fp256::from(1u64), 2, &mut rng);
+        let y = Shared::share(Fp256::from(1u64), 3, &mut rng);
+        let _ = x + y;
+    }
+
+    proptest! {
+        #[test]
+        fn linear_ops_are_local(seed in any::<u64>(), parties in 1usize..6, a in any::<i64>(), b in any::<i64>(), c in any::<i64>()) {
+            let (a, b, c) = (Fp256::from(a), Fp256::from(b), Fp256::from(c));
+            let mut dealer = Dealer::new(parties, rng_from_seed(seed));
+            let x = dealer.share(a);
+            let y = dealer.share(b);
+            prop_assert_eq!(x.parties(), parties);
+            prop_assert_eq!(x.reveal(), a);
+            prop_assert_eq!((&x + &y).reveal(), a + b);
+            prop_assert_eq!((&x - &y).reveal(), a - b);
+            prop_assert_eq!((-x.clone()).reveal(), -a);
+            prop_assert_eq!(x.scale(c).reveal(), a * c);
+            prop_assert_eq!(x.add_constant(c).reveal(), a + c);
+            prop_assert_eq!(dealer.triples_dealt(), 0);
+        }
+
+        #[test]
+        fn triples_multiply(seed in any::<u64>(), parties in 1usize..6) {
+            let mut dealer = Dealer::new(parties, rng_from_seed(seed));
+            let triple: BeaverTriple<Fp480> = dealer.triple();
+            prop_assert_eq!(triple.a.reveal() * triple.b.reveal(), triple.c.reveal());
+        }
+
+        #[test]
+        fn evaluates_circuit(seed in any::<u64>(), parties in 1usize..6, a in any::<i64>(), b in any::<i64>()) {
+            // x^3 + 2xy - 5
+            let (a, b) = (Fp256::from(a), Fp256::from(b));
+            let mut dealer = Dealer::new(parties, rng_from_seed(seed));
+            let x = dealer.share(a);
+            let y = dealer.share(b);
+            let x2 = dealer.mul(&x, &x);
+            let x3 = dealer.mul(&x2, &x);
+            let xy = dealer.mul(&x, &y);
+            let z = (x3 + xy.scale(Fp256::from(2u64))).add_constant(-Fp256::from(5u64));
+            prop_assert_eq!(z.reveal(), a * a * a + Fp256::from(2u64) * a * b - Fp256::from(5u64));
+            prop_assert_eq!(dealer.triples_dealt(), 3);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Multi-scalar multiplication `k_1 P_1 + ... + k_n P_n` on Weierstrass curves, the core of
+//! batch signature verification and of checking polynomial and vector commitments.
+//!
+//! Large inputs use Pippenger's bucket method: each `c` bit window of the scalars sorts the
+//! points into `2^c - 1` buckets by digit, and a running sum over the buckets weights each by its
+//! digit with two additions per bucket. That's about `b / c (n + 2^(c+1))` additions for b bit
+//! scalars, against `b n / 2` for one scalar multiplication at a time. Small inputs use Straus's
+//! method instead, which shares the doublings between the points and looks the window digits up
+//! in a small table of multiples per point.
+//!
+//! The points are affine so the many bucket additions can use mixed addition; convert Jacobian
+//! points with `Jacobian::batch_to_affine`, which costs one inversion for the lot. Neither method
+//! is constant-time, so the scalars shouldn't be secret.
+use digits::util::limb_window;
+use field::PrimeField;
+use weierstrass::{Affine, Jacobian, WeierstrassParams};
+
+/// Below this many points Straus's method does fewer additions than Pippenger's.
+const STRAUS_THRESHOLD: usize = 128;
+
+/// Window width for Straus's method, which keeps 15 multiples of each point.
+const STRAUS_WINDOW: usize = 4;
+
+///The sum of `scalars[i] * points[i]`. Panics if the slices have different lengths.
+pub fn msm<C: WeierstrassParams>(points: &[Affine<C>], scalars: &[C::Scalar]) -> Jacobian<C> {
+    assert_eq!(
+        points.len(),
+        scalars.len(),
+        "There must be one scalar per point."
+    );
+    let scalars: Vec<Vec<u64>> = scalars.iter().map(|k| k.to_limbs()).collect();
+    if points.len() < STRAUS_THRESHOLD {
+        straus(points, &scalars, C::Scalar::PRIMEBITS)
+    } else {
+        pippenger(points, &scalars, C::Scalar::PRIMEBITS)
+    }
+}
+
+///About `ln(n) + 2`, which balances the work per window against the number of windows.
+fn window_size(n: usize) -> usize {
+    let log2 = (0usize.leading_zeros() - 1 - n.max(1).leading_zeros()) as usize;
+    log2 * 69 / 100 + 2
+}
+
+fn pippenger<C: WeierstrassParams>(
+    points: &[Affine<C>],
+    scalars: &[Vec<u64>],
+    bits: usize,
+) -> Jacobian<C> {
+    let c = window_size(points.len());
+    let windows = bits.div_ceil(c);
+    let mut buckets = vec![Jacobian::identity(); (1 << c) - 1];
+    let mut ret = Jacobian::identity();
+    for w in (0..windows).rev() {
+        for _ in 0..c {
+            ret = ret.double();
+        }
+        for bucket in buckets.iter_mut() {
+            *bucket = Jacobian::identity();
+        }
+        for (p, k) in points.iter().zip(scalars) {
+            let digit = limb_window(k, w * c, c);
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1].add_affine(p);
+            }
+        }
+        // running is the sum of buckets d and up, so adding it in after each bucket counts
+        // bucket d exactly d times.
+        let mut running = Jacobian::identity();
+        let mut sum = Jacobian::identity();
+        for bucket in buckets.iter().rev() {
+            running = running + *bucket;
+            sum = sum + running;
+        }
+        ret = ret + sum;
+    }
+    ret
+}
+
+fn straus<C: WeierstrassParams>(
+    points: &[Affine<C>],
+    scalars: &[Vec<u64>],
+    bits: usize,
+) -> Jacobian<C> {
+    // tables[i][d - 1] = d * points[i]
+    let tables: Vec<Vec<Jacobian<C>>> = points
+        .iter()
+        .map(|p| {
+            let mut table = vec![p.to_jacobian()];
+            for d in 1..(1 << STRAUS_WINDOW) - 1 {
+                let next = table[d - 1].add_affine(p);
+                table.push(next);
+            }
+            table
+        })
+        .collect();
+    let windows = bits.div_ceil(STRAUS_WINDOW);
+    let mut ret = Jacobian::identity();
+    for w in (0..windows).rev() {
+        for _ in 0..STRAUS_WINDOW {
+            ret = ret.double();
+        }
+        for (table, k) in tables.iter().zip(scalars) {
+            let digit = limb_window(k, w * STRAUS_WINDOW, STRAUS_WINDOW);
+            if digit != 0 {
+                ret = ret + table[digit - 1];
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::Field;
+    use fr_bn254::FrBn254;
+    use num_traits::{One, Zero};
+    use pairing::Bls12_381G2;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+    use weierstrass::Bn254G1;
+
+    fn random_input<C: WeierstrassParams>(n: usize, seed: u64) -> (Vec<Affine<C>>, Vec<C::Scalar>) {
+        let mut rng = rng_from_seed(seed);
+        let g = Jacobian::<C>::generator();
+        let points: Vec<Jacobian<C>> = (0..n)
+            .map(|_| g.scalar_mul(&C::Scalar::random(&mut rng)))
+            .collect();
+        let scalars = (0..n).map(|_| C::Scalar::random(&mut rng)).collect();
+        (Jacobian::batch_to_affine(&points), scalars)
+    }
+
+    fn naive<C: WeierstrassParams>(points: &[Affine<C>], scalars: &[C::Scalar]) -> Jacobian<C> {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(Jacobian::identity(), |acc, (p, k)| {
+                acc + p.to_jacobian().scalar_mul(k)
+            })
+    }
+
+    #[test]
+    fn window_size_grows_with_n() {
+        assert_eq!(window_size(1), 2);
+        assert_eq!(window_size(128), 6);
+        assert_eq!(window_size(1 << 16), 13);
+    }
+
+    #[test]
+    fn identities_and_zero_scalars() {
+        let (mut points, mut scalars) = random_input::<Bn254G1>(200, 1);
+        points[3] = Affine::identity();
+        scalars[5] = FrBn254::zero();
+        scalars[7] = -FrBn254::one();
+        let expected = naive(&points, &scalars);
+        assert_eq!(msm(&points, &scalars), expected);
+        assert_eq!(
+            msm(&points[..10], &scalars[..10]),
+            naive(&points[..10], &scalars[..10])
+        );
+        assert!(msm::<Bn254G1>(&[], &[]).is_identity());
+    }
+
+    #[test]
+    fn works_over_extension_fields() {
+        let (points, scalars) = random_input::<Bls12_381G2>(5, 2);
+        assert_eq!(msm(&points, &scalars), naive(&points, &scalars));
+    }
+
+    #[test]
+    #[should_panic(expected = "one scalar per point")]
+    fn mismatched_lengths_panic() {
+        let (points, scalars) = random_input::<Bn254G1>(3, 3);
+        msm(&points, &scalars[..2]);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+        #[test]
+        fn pippenger_and_straus_agree(n in 0usize..40, seed in any::<u64>()) {
+            let (points, scalars) = random_input::<Bn254G1>(n, seed);
+            let limbs: Vec<Vec<u64>> = scalars.iter().map(|k| k.to_limbs()).collect();
+            let expected = naive(&points, &scalars);
+            prop_assert_eq!(pippenger(&points, &limbs, FrBn254::PRIMEBITS), expected);
+            prop_assert_eq!(straus(&points, &limbs, FrBn254::PRIMEBITS), expected);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Sparse multivariate polynomials over prime fields, plus conversion to and from the dense
+//! multilinear form (evaluations over the boolean hypercube) that sumcheck-style protocols use.
+use field::PrimeField;
+use std::collections::BTreeMap;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A polynomial in `num_vars` variables `x_0 ... x_{num_vars - 1}`, stored as a map from exponent
+/// vectors to non-zero coefficients. Terms with zero coefficients are never stored, so the zero
+/// polynomial has no terms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiPoly<F: PrimeField> {
+    num_vars: usize,
+    terms: BTreeMap<Vec<usize>, F>,
+}
+
+impl<F: PrimeField> MultiPoly<F> {
+    ///The zero polynomial in `num_vars` variables.
+    pub fn zero(num_vars: usize) -> MultiPoly<F> {
+        MultiPoly {
+            num_vars,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn constant(num_vars: usize, c: F) -> MultiPoly<F> {
+        MultiPoly::from_terms(num_vars, vec![(vec![0; num_vars], c)])
+    }
+
+    ///The polynomial `x_var`. Panics if `var >= num_vars`.
+    pub fn var(num_vars: usize, var: usize) -> MultiPoly<F> {
+        assert!(var < num_vars, "Variable index out of range.");
+        let mut exponents = vec![0; num_vars];
+        exponents[var] = 1;
+        MultiPoly::from_terms(num_vars, vec![(exponents, F::one())])
+    }
+
+    ///Build a polynomial from `(exponents, coefficient)` pairs. Repeated exponent vectors are
+    ///added together. Panics if an exponent vector isn't `num_vars` long.
+    pub fn from_terms(num_vars: usize, terms: Vec<(Vec<usize>, F)>) -> MultiPoly<F> {
+        let mut ret = MultiPoly::zero(num_vars);
+        for (exponents, c) in terms {
+            assert_eq!(
+                exponents.len(),
+                num_vars,
+                "Exponent vector has the wrong number of variables."
+            );
+            ret.add_term(exponents, c);
+        }
+        ret
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    ///The non-zero terms as `(exponents, coefficient)`, in lexicographic order of exponents.
+    pub fn terms(&self) -> impl Iterator<Item = (&[usize], F)> {
+        self.terms.iter().map(|(e, c)| (&e[..], *c))
+    }
+
+    ///The coefficient of the monomial with these exponents.
+    pub fn coeff(&self, exponents: &[usize]) -> F {
+        self.terms.get(exponents).cloned().unwrap_or_else(F::zero)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    ///The largest total degree of any term, or None for the zero polynomial.
+    pub fn total_degree(&self) -> Option<usize> {
+        self.terms.keys().map(|e| e.iter().sum()).max()
+    }
+
+    ///The largest power of `x_var` appearing, or None for the zero polynomial. Panics if
+    ///`var >= num_vars`.
+    pub fn degree_in(&self, var: usize) -> Option<usize> {
+        assert!(var < self.num_vars, "Variable index out of range.");
+        self.terms.keys().map(|e| e[var]).max()
+    }
+
+    ///True if no variable appears with an exponent above one.
+    pub fn is_multilinear(&self) -> bool {
+        self.terms.keys().all(|e| e.iter().all(|&d| d <= 1))
+    }
+
+    pub fn scale(&self, c: F) -> MultiPoly<F> {
+        MultiPoly::from_terms(
+            self.num_vars,
+            self.terms
+                .iter()
+                .map(|(e, x)| (e.clone(), *x * c))
+                .collect(),
+        )
+    }
+
+    ///Evaluate at a point. Panics if the point doesn't have `num_vars` coordinates.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars, "Point has the wrong dimension.");
+        self.terms.iter().fold(F::zero(), |acc, (exponents, c)| {
+            acc + exponents
+                .iter()
+                .zip(point)
+                .fold(*c, |term, (e, x)| term * x.pow_limbs(&[*e as u64]))
+        })
+    }
+
+    ///Substitute `value` for `x_var`. The result still has `num_vars` variables, but `x_var` no
+    ///longer appears in it.
+    pub fn partial_evaluate(&self, var: usize, value: F) -> MultiPoly<F> {
+        assert!(var < self.num_vars, "Variable index out of range.");
+        let mut ret = MultiPoly::zero(self.num_vars);
+        for (exponents, c) in self.terms.iter() {
+            let mut reduced = exponents.clone();
+            reduced[var] = 0;
+            ret.add_term(reduced, *c * value.pow_limbs(&[exponents[var] as u64]));
+        }
+        ret
+    }
+
+    ///The multilinear polynomial taking the given values on the boolean hypercube, where
+    ///`evals[i]` is the value at the point whose coordinate `x_j` is bit `j` of `i`.
+    ///Panics unless the number of evaluations is a power of two.
+    pub fn from_hypercube_evaluations(evals: &[F]) -> MultiPoly<F> {
+        assert!(
+            evals.len().is_power_of_two(),
+            "Need 2^n evaluations for n variables."
+        );
+        let num_vars = evals.len().trailing_zeros() as usize;
+        // Inverse of the zeta transform in to_hypercube_evaluations: afterwards coeffs[i] is the
+        // coefficient of the product of the variables whose bits are set in i.
+        let mut coeffs = evals.to_vec();
+        for j in 0..num_vars {
+            let bit = 1 << j;
+            for i in 0..coeffs.len() {
+                if i & bit != 0 {
+                    let low = coeffs[i ^ bit];
+                    coeffs[i] -= low;
+                }
+            }
+        }
+        MultiPoly::from_terms(
+            num_vars,
+            coeffs
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| ((0..num_vars).map(|j| (i >> j) & 1).collect(), c))
+                .collect(),
+        )
+    }
+
+    ///The values of the polynomial on the boolean hypercube, indexed as in
+    ///`from_hypercube_evaluations`. For a multilinear polynomial the two are inverses; for any
+    ///other polynomial this gives the evaluations of its multilinear reduction.
+    pub fn to_hypercube_evaluations(&self) -> Vec<F> {
+        // On {0, 1}, x^k = x for k >= 1, so collect each term into the dense multilinear
+        // coefficient for the set of variables it uses, then sum over subsets.
+        let mut evals = vec![F::zero(); 1 << self.num_vars];
+        for (exponents, c) in self.terms.iter() {
+            let index = exponents
+                .iter()
+                .enumerate()
+                .filter(|(_, &e)| e > 0)
+                .fold(0, |acc, (j, _)| acc | (1 << j));
+            evals[index] += *c;
+        }
+        for j in 0..self.num_vars {
+            let bit = 1 << j;
+            for i in 0..evals.len() {
+                if i & bit != 0 {
+                    let low = evals[i ^ bit];
+                    evals[i] += low;
+                }
+            }
+        }
+        evals
+    }
+
+    fn add_term(&mut self, exponents: Vec<usize>, c: F) {
+        if c.is_zero() {
+            return;
+        }
+        let sum = self.coeff(&exponents) + c;
+        if sum.is_zero() {
+            self.terms.remove(&exponents);
+        } else {
+            self.terms.insert(exponents, sum);
+        }
+    }
+
+    fn assert_same_vars(&self, other: &MultiPoly<F>) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "Polynomials must have the same number of variables."
+        );
+    }
+}
+
+///Multiplying monomials adds their exponents.
+fn monomial_product(e1: &[usize], e2: &[usize]) -> Vec<usize> {
+    e1.iter().zip(e2).map(|(a, b)| a + b).collect()
+}
+
+impl<F: PrimeField> Add<&MultiPoly<F>> for &MultiPoly<F> {
+    type Output = MultiPoly<F>;
+    fn add(self, other: &MultiPoly<F>) -> MultiPoly<F> {
+        self.assert_same_vars(other);
+        let mut ret = self.clone();
+        for (exponents, c) in other.terms.iter() {
+            ret.add_term(exponents.clone(), *c);
+        }
+        ret
+    }
+}
+
+impl<F: PrimeField> Sub<&MultiPoly<F>> for &MultiPoly<F> {
+    type Output = MultiPoly<F>;
+    fn sub(self, other: &MultiPoly<F>) -> MultiPoly<F> {
+        self.assert_same_vars(other);
+        let mut ret = self.clone();
+        for (exponents, c) in other.terms.iter() {
+            ret.add_term(exponents.clone(), -*c);
+        }
+        ret
+    }
+}
+
+impl<F: PrimeField> Mul<&MultiPoly<F>> for &MultiPoly<F> {
+    type Output = MultiPoly<F>;
+    fn mul(self, other: &MultiPoly<F>) -> MultiPoly<F> {
+        self.assert_same_vars(other);
+        let mut ret = MultiPoly::zero(self.num_vars);
+        for (e1, c1) in self.terms.iter() {
+            for (e2, c2) in other.terms.iter() {
+                ret.add_term(monomial_product(e1, e2), *c1 * *c2);
+            }
+        }
+        ret
+    }
+}
+
+impl<F: PrimeField> Neg for &MultiPoly<F> {
+    type Output = MultiPoly<F>;
+    fn neg(self) -> MultiPoly<F> {
+        MultiPoly {
+            num_vars: self.num_vars,
+            terms: self.terms.iter().map(|(e, c)| (e.clone(), -*c)).collect(),
+        }
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! multipoly_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<F: PrimeField> $imp for MultiPoly<F> {
+        type Output = MultiPoly<F>;
+        #[inline]
+        fn $method(self, other: MultiPoly<F>) -> MultiPoly<F> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+multipoly_owned_ops! { Add, add; Sub, sub; Mul, mul }
+
+impl<F: PrimeField> Neg for MultiPoly<F> {
+    type Output = MultiPoly<F>;
+    #[inline]
+    fn neg(self) -> MultiPoly<F> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn x(i: usize) -> MultiPoly<Fp256> {
+        MultiPoly::var(3, i)
+    }
+
+    fn c(v: i64) -> MultiPoly<Fp256> {
+        MultiPoly::constant(3, Fp256::from(v))
+    }
+
+    ///A random polynomial in three variables with small exponents.
+    fn arb_multipoly() -> impl Strategy<Value = MultiPoly<Fp256>> {
+        vec((vec(0usize..3, 3), any::<i64>()), 0..8).prop_map(|terms| {
+            MultiPoly::from_terms(
+                3,
+                terms
+                    .into_iter()
+                    .map(|(e, c)| (e, Fp256::from(c)))
+                    .collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn known_product() {
+        // (x0 + x1)(x0 - x1) = x0^2 - x1^2
+        let f = (x(0) + x(1)) * (x(0) - x(1));
+        assert_eq!(
+            f,
+            MultiPoly::from_terms(
+                3,
+                vec![
+                    (vec![2, 0, 0], Fp256::from(1u64)),
+                    (vec![0, 2, 0], Fp256::from(-1i64)),
+                ]
+            )
+        );
+        assert_eq!(f.total_degree(), Some(2));
+        assert_eq!(f.degree_in(2), Some(0));
+        assert_eq!((&f - &f).total_degree(), None);
+        assert!((&f - &f).is_zero());
+        assert!(!f.is_multilinear());
+    }
+
+    #[test]
+    fn partial_evaluation() {
+        // x0 x1 x2 + 3 x1 with x1 = 2 is 2 x0 x2 + 6
+        let f = x(0) * x(1) * x(2) + c(3) * x(1);
+        let g = f.partial_evaluate(1, Fp256::from(2u64));
+        assert_eq!(g, c(2) * x(0) * x(2) + c(6));
+        assert_eq!(g.degree_in(1), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn degree_in_missing_variable() {
+        MultiPoly::<Fp256>::zero(3).degree_in(3);
+    }
+
+    #[test]
+    fn hypercube_of_known_polynomial() {
+        // x0 + 2 x1 x2 over {0,1}^3, x0 the low bit of the index
+        let f = x(0) + c(2) * x(1) * x(2);
+        let expected: Vec<Fp256> = [0u64, 1, 0, 1, 0, 1, 2, 3]
+            .iter()
+            .map(|v| Fp256::from(*v))
+            .collect();
+        assert_eq!(f.to_hypercube_evaluations(), expected);
+        assert_eq!(MultiPoly::from_hypercube_evaluations(&expected), f);
+    }
+
+    proptest! {
+        #[test]
+        fn evaluation_is_a_ring_homomorphism(
+            a in arb_multipoly(),
+            b in arb_multipoly(),
+            ref point in vec(any::<u64>(), 3),
+        ) {
+            let point: Vec<Fp256> = point.iter().map(|v| Fp256::from(*v)).collect();
+            prop_assert_eq!((&a + &b).evaluate(&point), a.evaluate(&point) + b.evaluate(&point));
+            prop_assert_eq!((&a * &b).evaluate(&point), a.evaluate(&point) * b.evaluate(&point));
+            prop_assert_eq!(&(&a * &b) - &(&b * &a), MultiPoly::zero(3));
+        }
+
+        #[test]
+        fn partial_then_full_evaluation(a in arb_multipoly(), ref point in vec(any::<u64>(), 3)) {
+            let point: Vec<Fp256> = point.iter().map(|v| Fp256::from(*v)).collect();
+            let partial = a.partial_evaluate(0, point[0]);
+            prop_assert_eq!(partial.evaluate(&point), a.evaluate(&point));
+            prop_assert_eq!(partial.degree_in(0).unwrap_or(0), 0);
+        }
+
+        #[test]
+        fn hypercube_roundtrip(ref evals in vec(any::<i64>(), 8)) {
+            let evals: Vec<Fp256> = evals.iter().map(|v| Fp256::from(*v)).collect();
+            let f = MultiPoly::from_hypercube_evaluations(&evals);
+            prop_assert!(f.is_multilinear());
+            prop_assert_eq!(f.to_hypercube_evaluations(), evals);
+        }
+
+        #[test]
+        fn hypercube_matches_evaluate(a in arb_multipoly()) {
+            let evals = a.to_hypercube_evaluations();
+            for (i, v) in evals.iter().enumerate() {
+                let point: Vec<Fp256> = (0..3).map(|j| Fp256::from(((i >> j) & 1) as u64)).collect();
+                prop_assert_eq!(*v, a.evaluate(&point));
+            }
+        }
+    }
+}
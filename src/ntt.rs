@@ -0,0 +1,280 @@
+//! Number-theoretic transforms over prime fields whose multiplicative group has a large power of
+//! two in its order. The transforms work in place on slices whose length is a power of two, and
+//! are what `Poly` uses to multiply large polynomials in O(n log n).
+use field::PrimeField;
+
+///The largest `s` such that `2^s` divides `p - 1`. A primitive `2^k`-th root of unity exists
+///exactly when `k <= two_adicity`.
+pub fn two_adicity<F: PrimeField>() -> u32 {
+    let mut s = 0;
+    for (i, limb) in F::PRIME.iter().enumerate() {
+        // p is odd, so clearing the bottom bit gives p - 1.
+        let limb = if i == 0 { limb & !1 } else { *limb };
+        if limb != 0 {
+            return s + limb.trailing_zeros();
+        }
+        s += 64;
+    }
+    s
+}
+
+///True if `n` is a power of two for which the field has an `n`-th root of unity, in other words
+///if the transforms in this module can be run on a slice of length `n`.
+pub fn supports<F: PrimeField>(n: usize) -> bool {
+    n.is_power_of_two() && n.trailing_zeros() <= two_adicity::<F>()
+}
+
+///A primitive `2^log_n`-th root of unity, or `None` if the field doesn't have one.
+///
+///The root is `g^((p - 1) / 2^log_n)` where `g` is the smallest quadratic non-residue, so the
+///same field always hands back the same root.
+pub fn root_of_unity<F: PrimeField>(log_n: u32) -> Option<F> {
+    if log_n > two_adicity::<F>() {
+        return None;
+    }
+    let p_minus_one = prime_minus_one::<F>();
+    let minus_one = -F::one();
+    let half = shift_right(&p_minus_one, 1);
+    let non_residue = (2u64..)
+        .map(F::from)
+        .find(|g| g.pow_limbs(&half) == minus_one)
+        .expect("Every odd prime field has a quadratic non-residue.");
+    Some(non_residue.pow_limbs(&shift_right(&p_minus_one, log_n)))
+}
+
+///In-place forward transform: `values[i]` becomes the evaluation of the polynomial with
+///coefficients `values` at `w^i`, where `w = root_of_unity(log2(values.len()))`.
+///
+///Panics if `supports::<F>(values.len())` is false.
+pub fn ntt<F: PrimeField>(values: &mut [F]) {
+    let omega = root_for_len::<F>(values.len());
+    transform(values, omega);
+}
+
+///In-place inverse of `ntt`: takes evaluations at the powers of `w` back to coefficients.
+///
+///Panics if `supports::<F>(values.len())` is false.
+pub fn intt<F: PrimeField>(values: &mut [F]) {
+    let omega = root_for_len::<F>(values.len());
+    transform(values, omega.inv());
+    let n_inv = F::from(values.len() as u64).inv();
+    for v in values.iter_mut() {
+        *v *= n_inv;
+    }
+}
+
+///In-place transform over the coset `shift * <w>`: `values[i]` becomes the evaluation at
+///`shift * w^i`. Useful when the evaluations at the roots of unity themselves would be zero.
+///
+///Panics if `supports::<F>(values.len())` is false.
+pub fn coset_ntt<F: PrimeField>(values: &mut [F], shift: F) {
+    let mut power = F::one();
+    for v in values.iter_mut() {
+        *v *= power;
+        power *= shift;
+    }
+    ntt(values);
+}
+
+///In-place inverse of `coset_ntt` with the same `shift`.
+///
+///Panics if `supports::<F>(values.len())` is false or `shift` is zero.
+pub fn coset_intt<F: PrimeField>(values: &mut [F], shift: F) {
+    intt(values);
+    let shift_inv = shift.inv();
+    let mut power = F::one();
+    for v in values.iter_mut() {
+        *v *= power;
+        power *= shift_inv;
+    }
+}
+
+///Multiply two coefficient vectors by transforming both, multiplying pointwise and transforming
+///back. Returns `None` if the field can't do a transform long enough to hold the product.
+pub(crate) fn mul<F: PrimeField>(a: &[F], b: &[F]) -> Option<Vec<F>> {
+    if a.is_empty() || b.is_empty() {
+        return Some(vec![]);
+    }
+    let result_len = a.len() + b.len() - 1;
+    let n = result_len.next_power_of_two();
+    if !supports::<F>(n) {
+        return None;
+    }
+    let mut fa = a.to_vec();
+    fa.resize(n, F::zero());
+    let mut fb = b.to_vec();
+    fb.resize(n, F::zero());
+    ntt(&mut fa);
+    ntt(&mut fb);
+    for (x, y) in fa.iter_mut().zip(fb) {
+        *x *= y;
+    }
+    intt(&mut fa);
+    fa.truncate(result_len);
+    Some(fa)
+}
+
+fn root_for_len<F: PrimeField>(n: usize) -> F {
+    assert!(
+        supports::<F>(n),
+        "NTT length must be a power of two dividing p - 1."
+    );
+    root_of_unity(n.trailing_zeros()).unwrap()
+}
+
+///Iterative radix-2 Cooley-Tukey: bit-reverse the input, then combine butterflies of doubling
+///size. `omega` must be a primitive `values.len()`-th root of unity.
+fn transform<F: PrimeField>(values: &mut [F], omega: F) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    let mut j = 0;
+    for i in 1..n {
+        // Increment j as a bit-reversed counter.
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    // twiddles[i] = omega^i for the first half; each stage strides through them.
+    let mut twiddles = Vec::with_capacity(n / 2);
+    let mut w = F::one();
+    for _ in 0..n / 2 {
+        twiddles.push(w);
+        w *= omega;
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let stride = n / len;
+        for chunk in values.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(half);
+            for (k, (x, y)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                let t = *y * twiddles[k * stride];
+                *y = *x - t;
+                *x += t;
+            }
+        }
+        len *= 2;
+    }
+}
+
+fn prime_minus_one<F: PrimeField>() -> Vec<u64> {
+    let mut ret = F::PRIME.to_vec();
+    // p is odd, so this never borrows.
+    ret[0] -= 1;
+    ret
+}
+
+///Shift limbs (least significant first) right by `bits`.
+fn shift_right(limbs: &[u64], bits: u32) -> Vec<u64> {
+    let digits = (bits / 64) as usize;
+    let bits = bits % 64;
+    let mut ret: Vec<u64> = limbs.iter().skip(digits).cloned().collect();
+    if bits > 0 {
+        for i in 0..ret.len() {
+            let high = ret.get(i + 1).map_or(0, |next| next << (64 - bits));
+            ret[i] = (ret[i] >> bits) | high;
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_255::Fp255;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use num_traits::{One, Zero};
+    use poly::Poly;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn two_adicity_of_known_fields() {
+        assert_eq!(two_adicity::<Fp255>(), 32);
+        assert_eq!(two_adicity::<Fp256>(), 1);
+        assert_eq!(two_adicity::<Fp480>(), 1);
+        assert!(supports::<Fp255>(1 << 20));
+        assert!(!supports::<Fp255>(12));
+        assert!(!supports::<Fp256>(4));
+    }
+
+    #[test]
+    fn roots_are_primitive() {
+        for log_n in 1..33 {
+            let w = root_of_unity::<Fp255>(log_n).unwrap();
+            let mut x = w;
+            for _ in 1..log_n {
+                x = x.square();
+            }
+            assert_eq!(x, -Fp255::one(), "2^{} root", log_n);
+        }
+        assert_eq!(root_of_unity::<Fp255>(33), None);
+        assert_eq!(root_of_unity::<Fp256>(1), Some(-Fp256::one()));
+        assert_eq!(root_of_unity::<Fp256>(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn ntt_of_bad_length_panics() {
+        let mut values = vec![Fp255::one(); 6];
+        ntt(&mut values);
+    }
+
+    proptest! {
+        #[test]
+        fn ntt_roundtrip(ref coeffs in vec(any::<u64>(), 0..65), log_n in 0u32..7) {
+            let n = 1 << log_n;
+            let mut values: Vec<Fp255> = coeffs.iter().take(n).map(|c| Fp255::from(*c)).collect();
+            values.resize(n, Fp255::zero());
+            let original = values.clone();
+            ntt(&mut values);
+            intt(&mut values);
+            prop_assert_eq!(values, original);
+        }
+
+        #[test]
+        fn ntt_evaluates_at_roots(ref coeffs in vec(any::<u64>(), 1..33)) {
+            let n = coeffs.len().next_power_of_two();
+            let mut values: Vec<Fp255> = coeffs.iter().map(|c| Fp255::from(*c)).collect();
+            values.resize(n, Fp255::zero());
+            let poly = Poly::new(values.clone());
+            ntt(&mut values);
+            let omega = root_of_unity::<Fp255>(n.trailing_zeros()).unwrap();
+            let mut x = Fp255::one();
+            for v in values {
+                prop_assert_eq!(v, poly.evaluate(x));
+                x *= omega;
+            }
+        }
+
+        #[test]
+        fn coset_ntt_evaluates_on_coset(ref coeffs in vec(any::<u64>(), 1..17), shift in 1u64..) {
+            let n = coeffs.len().next_power_of_two();
+            let shift = Fp255::from(shift);
+            let mut values: Vec<Fp255> = coeffs.iter().map(|c| Fp255::from(*c)).collect();
+            values.resize(n, Fp255::zero());
+            let original = values.clone();
+            let poly = Poly::new(values.clone());
+            coset_ntt(&mut values, shift);
+            let omega = root_of_unity::<Fp255>(n.trailing_zeros()).unwrap();
+            let mut x = shift;
+            for v in values.iter() {
+                prop_assert_eq!(*v, poly.evaluate(x));
+                x *= omega;
+            }
+            coset_intt(&mut values, shift);
+            prop_assert_eq!(values, original);
+        }
+    }
+}
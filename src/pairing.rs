@@ -0,0 +1,467 @@
+//! The optimal ate pairing `e: G1 x G2 -> GT` on BN254 and BLS12-381.
+//!
+//! G1 is the prime order subgroup of the curve over the base field, G2 the one of its sextic
+//! twist over `Fp2`, and GT the r-th roots of unity in `Fp12`. The pairing is a Miller loop
+//! followed by the final exponentiation to the power `(p^12 - 1) / r`.
+//!
+//! The Miller loop keeps the G2 point in affine coordinates on the twist, where each step costs
+//! an `Fp2` inversion, and maps the points into `Fp12` only to evaluate the lines. Vertical lines
+//! are dropped, since their values are in `Fp6` and the final exponentiation sends those to one.
+//! The final exponentiation does the easy part `(p^6 - 1)(p^2 + 1)` with the Frobenius map, and
+//! the hard part `(p^4 - p^2 + 1) / r` with a plain `pow_limbs`.
+use field::{Field, PrimeField};
+use fp_255::Fp255;
+use fp_381::Fp381;
+use fp_bn254::FpBn254;
+use fr_bn254::FrBn254;
+use num_traits::{Inv, One, Zero};
+use tower::{frobenius_coefficients, Fp12, Fp2, TowerParams};
+use weierstrass::{Affine, Bls12_381G1, Bn254G1, WeierstrassParams};
+
+/// How G2 on the twist `y^2 = x^3 + b'` maps to the curve over `Fp12`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Twist {
+    ///`b' = b / ξ` and `(x, y) -> (x w^2, y w^3)`.
+    D,
+    ///`b' = b ξ` and `(x, y) -> (x / w^2, y / w^3)`.
+    M,
+}
+
+/// The family of the curve, which decides the shape of the Miller loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    ///Barreto-Naehrig, whose loop runs over `6x + 2` and ends with two Frobenius twisted lines.
+    Bn,
+    ///Barreto-Lynn-Scott with embedding degree 12, whose loop runs over `x`.
+    Bls12,
+}
+
+/// A pairing-friendly curve with embedding degree 12, along with the tower its pairing lands in.
+pub trait PairingParams: TowerParams {
+    ///Field of scalars, whose prime is the order r of G1, G2 and GT.
+    type Scalar: PrimeField;
+    type G1: WeierstrassParams<Base = Self::Base, Scalar = Self::Scalar>;
+    type G2: WeierstrassParams<Base = Fp2<Self>, Scalar = Self::Scalar>;
+    const TWIST: Twist;
+    const FAMILY: Family;
+    ///The absolute value of the Miller loop count, in limbs, least significant first.
+    const ATE_LOOP_COUNT: &'static [u64];
+    const ATE_LOOP_COUNT_IS_NEGATIVE: bool;
+    ///`(p^4 - p^2 + 1) / r`, the hard part of the final exponentiation, in limbs.
+    const FINAL_EXPONENT: &'static [u64];
+}
+
+/// Elements of GT, the order r subgroup of `Fp12*`, written multiplicatively.
+pub type Gt<P> = Fp12<P>;
+
+type G2Coordinates<P> = (Fp2<P>, Fp2<P>);
+
+///The optimal ate pairing of `p` and `q`. Both points must be in their prime order subgroups
+///for the result to mean anything; the identity on either side gives one.
+pub fn pairing<P: PairingParams>(p: &Affine<P::G1>, q: &Affine<P::G2>) -> Gt<P> {
+    final_exponentiation(&miller_loop(p, q))
+}
+
+///The Miller loop of the optimal ate pairing, whose value is only defined up to factors the
+///final exponentiation removes. Products of Miller loops only need one final exponentiation.
+pub fn miller_loop<P: PairingParams>(p: &Affine<P::G1>, q: &Affine<P::G2>) -> Fp12<P> {
+    let (p, q) = match (p.coordinates(), q.coordinates()) {
+        (Some((x, y)), Some(q)) => ((Fp12::from_base(x), Fp12::from_base(y)), q),
+        _ => return Fp12::one(),
+    };
+    let mut f = Fp12::one();
+    let mut t = Some(q);
+    let bits =
+        P::ATE_LOOP_COUNT.len() * 64 - P::ATE_LOOP_COUNT.last().unwrap().leading_zeros() as usize;
+    for i in (0..bits - 1).rev() {
+        f = f.square();
+        if let Some(double) = t {
+            add_step::<P>(&mut f, &mut t, double, &p);
+        }
+        if (P::ATE_LOOP_COUNT[i / 64] >> (i % 64)) & 1 == 1 {
+            add_step::<P>(&mut f, &mut t, q, &p);
+        }
+    }
+    if P::FAMILY == Family::Bn {
+        // The loop only covers 6x + 2 of the optimal ate pairing's 6x + 2 + p - p^2.
+        let q1 = twist_frobenius::<P>(q);
+        let (x2, y2) = twist_frobenius::<P>(q1);
+        add_step::<P>(&mut f, &mut t, q1, &p);
+        add_step::<P>(&mut f, &mut t, (x2, -y2), &p);
+    }
+    if P::ATE_LOOP_COUNT_IS_NEGATIVE {
+        // f_{-x} is 1 / f_x up to a vertical line, and conjugating inverts after the easy part.
+        f = f.conjugate();
+    }
+    f
+}
+
+///Raise the Miller loop value to `(p^12 - 1) / r`, which lands it in GT. Panics on zero, which
+///no Miller loop produces.
+pub fn final_exponentiation<P: PairingParams>(f: &Fp12<P>) -> Gt<P> {
+    let f = f.conjugate() * f.inv();
+    let f = f.frobenius_map(2) * f;
+    f.pow_limbs(P::FINAL_EXPONENT)
+}
+
+///Multiply `f` by the line through `t` and `q` (the tangent if they're equal) at `p`, and
+///move `t` to `t + q`.
+fn add_step<P: PairingParams>(
+    f: &mut Fp12<P>,
+    t: &mut Option<G2Coordinates<P>>,
+    q: G2Coordinates<P>,
+    p: &(Fp12<P>, Fp12<P>),
+) {
+    let (x1, y1) = match *t {
+        Some(t) => t,
+        None => {
+            // The line through the identity and q is vertical.
+            *t = Some(q);
+            return;
+        }
+    };
+    let (x2, y2) = q;
+    if x1 == x2 && (y1 != y2 || y1.is_zero()) {
+        // t + q is the identity and the line is vertical.
+        *t = None;
+        return;
+    }
+    let lambda = if x1 == x2 {
+        let three = Fp2::from_base(P::Base::from(3u64));
+        (three * x1.square() + P::G2::a()) / (y1 + y1)
+    } else {
+        (y2 - y1) / (x2 - x1)
+    };
+    let x3 = lambda.square() - x1 - x2;
+    let y3 = lambda * (x1 - x3) - y1;
+    let (ux1, uy1, slope) = untwist::<P>(x1, y1, lambda);
+    *f *= p.1 - uy1 - slope * (p.0 - ux1);
+    *t = Some((x3, y3));
+}
+
+///Map the point `(x, y)` on the twist and the slope `lambda` of a line through it onto the
+///curve over `Fp12`.
+fn untwist<P: PairingParams>(x: Fp2<P>, y: Fp2<P>, lambda: Fp2<P>) -> (Fp12<P>, Fp12<P>, Fp12<P>) {
+    match P::TWIST {
+        Twist::D => (monomial(x, 2), monomial(y, 3), monomial(lambda, 1)),
+        Twist::M => {
+            // 1 / w^k = w^(6 - k) / ξ
+            let xi_inv = P::xi().inv();
+            (
+                monomial(x * xi_inv, 4),
+                monomial(y * xi_inv, 3),
+                monomial(lambda * xi_inv, 5),
+            )
+        }
+    }
+}
+
+///`c w^k`
+fn monomial<P: TowerParams>(c: Fp2<P>, k: usize) -> Fp12<P> {
+    let mut coefficients = [Fp2::zero(); 6];
+    coefficients[k] = c;
+    Fp12::from_w_coefficients(coefficients)
+}
+
+///The p-power Frobenius endomorphism carried over to the twist.
+fn twist_frobenius<P: PairingParams>((x, y): G2Coordinates<P>) -> G2Coordinates<P> {
+    let gamma = frobenius_coefficients::<P>();
+    match P::TWIST {
+        Twist::D => (x.conjugate() * gamma[2], y.conjugate() * gamma[3]),
+        Twist::M => (x.conjugate() / gamma[2], y.conjugate() / gamma[3]),
+    }
+}
+
+/// BN254 with the tower `u^2 = -1`, `ξ = 9 + u`, as used by Ethereum's pairing precompiles.
+/// G1 is `Bn254G1` and G2 is `Bn254G2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bn254;
+
+impl TowerParams for Bn254 {
+    type Base = FpBn254;
+
+    fn beta() -> FpBn254 {
+        -FpBn254::one()
+    }
+
+    fn xi() -> Fp2<Bn254> {
+        Fp2::new(FpBn254::from(9u64), FpBn254::one())
+    }
+}
+
+impl PairingParams for Bn254 {
+    type Scalar = FrBn254;
+    type G1 = Bn254G1;
+    type G2 = Bn254G2;
+    const TWIST: Twist = Twist::D;
+    const FAMILY: Family = Family::Bn;
+    // 6x + 2 for x = 4965661367192848881
+    const ATE_LOOP_COUNT: &'static [u64] = &[0x9d797039be763ba8, 0x1];
+    const ATE_LOOP_COUNT_IS_NEGATIVE: bool = false;
+    const FINAL_EXPONENT: &'static [u64] = &[
+        0xe81bb482ccdf42b1,
+        0x5abf5cc4f49c36d4,
+        0xf1154e7e1da014fd,
+        0xdcc7b44c87cdbacf,
+        0xaaa441e3954bcf8a,
+        0x6b887d56d5095f23,
+        0x79581e16f3fd90c6,
+        0x3b1b1355d189227d,
+        0x4e529a5861876f6b,
+        0x6c0eb522d5b12278,
+        0x331ec15183177faf,
+        0x01baaa710b0759ad,
+    ];
+}
+
+/// The D-type twist of BN254, `y^2 = x^3 + 3 / (9 + u)` over `Fp2`, with cofactor `2p - r`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bn254G2;
+
+impl WeierstrassParams for Bn254G2 {
+    type Base = Fp2<Bn254>;
+    type Scalar = FrBn254;
+    const COFACTOR: &'static [u64] = &[
+        0x345f2299c0f9fa8d,
+        0x06ceecda572a2489,
+        0xb85045b68181585e,
+        0x30644e72e131a029,
+    ];
+
+    fn a() -> Fp2<Bn254> {
+        Fp2::zero()
+    }
+
+    fn b() -> Fp2<Bn254> {
+        Fp2::from_base(FpBn254::from(3u64)) / Bn254::xi()
+    }
+
+    fn generator() -> (Fp2<Bn254>, Fp2<Bn254>) {
+        (
+            Fp2::new(
+                FpBn254::new([
+                    0x46debd5cd992f6ed,
+                    0x674322d4f75edadd,
+                    0x426a00665e5c4479,
+                    0x1800deef121f1e76,
+                ]),
+                FpBn254::new([
+                    0x97e485b7aef312c2,
+                    0xf1aa493335a9e712,
+                    0x7260bfb731fb5d25,
+                    0x198e9393920d483a,
+                ]),
+            ),
+            Fp2::new(
+                FpBn254::new([
+                    0x4ce6cc0166fa7daa,
+                    0xe3d1e7690c43d37b,
+                    0x4aab71808dcb408f,
+                    0x12c85ea5db8c6deb,
+                ]),
+                FpBn254::new([
+                    0x55acdadcd122975b,
+                    0xbc4b313370b38ef3,
+                    0xec9e99ad690c3395,
+                    0x090689d0585ff075,
+                ]),
+            ),
+        )
+    }
+}
+
+/// BLS12-381 with the tower `u^2 = -1`, `ξ = 1 + u`. G1 is `Bls12_381G1` and G2 is
+/// `Bls12_381G2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bls12_381;
+
+impl TowerParams for Bls12_381 {
+    type Base = Fp381;
+
+    fn beta() -> Fp381 {
+        -Fp381::one()
+    }
+
+    fn xi() -> Fp2<Bls12_381> {
+        Fp2::new(Fp381::one(), Fp381::one())
+    }
+}
+
+impl PairingParams for Bls12_381 {
+    type Scalar = Fp255;
+    type G1 = Bls12_381G1;
+    type G2 = Bls12_381G2;
+    const TWIST: Twist = Twist::M;
+    const FAMILY: Family = Family::Bls12;
+    // |x| for x = -0xd201000000010000
+    const ATE_LOOP_COUNT: &'static [u64] = &[0xd201000000010000];
+    const ATE_LOOP_COUNT_IS_NEGATIVE: bool = true;
+    const FINAL_EXPONENT: &'static [u64] = &[
+        0xe516c3f438e3ba79,
+        0xfa9912aae208ccf1,
+        0x905ce937335d5b68,
+        0xc71a2629b0dea236,
+        0x83774940996754c8,
+        0x21d160aeb6a1e799,
+        0x2ed0b283ed237db4,
+        0x915c97f36c6f1821,
+        0x67f17fcbde783765,
+        0x2378b9039096d1b7,
+        0x7988f8761bdc51dc,
+        0x2076995003fc77a1,
+        0x827eca0ba621315b,
+        0xe5a72bce8d63cb9f,
+        0xf68f7764c28b6f8a,
+        0x2f230063cf081517,
+        0x94506632528d6a9a,
+        0xd3cde88eeb996ca3,
+        0xc0bd38c3195c899e,
+        0x000f686b3d807d01,
+    ];
+}
+
+/// The M-type twist of BLS12-381, `y^2 = x^3 + 4(1 + u)` over `Fp2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bls12_381G2;
+
+impl WeierstrassParams for Bls12_381G2 {
+    type Base = Fp2<Bls12_381>;
+    type Scalar = Fp255;
+    const COFACTOR: &'static [u64] = &[
+        0xcf1c38e31c7238e5,
+        0x1616ec6e786f0c70,
+        0x21537e293a6691ae,
+        0xa628f1cb4d9e82ef,
+        0xa68a205b2e5a7ddf,
+        0xcd91de4547085aba,
+        0x091d50792876a202,
+        0x05d543a95414e7f1,
+    ];
+
+    fn a() -> Fp2<Bls12_381> {
+        Fp2::zero()
+    }
+
+    fn b() -> Fp2<Bls12_381> {
+        Bls12_381::xi().scale(Fp381::from(4u64))
+    }
+
+    fn generator() -> (Fp2<Bls12_381>, Fp2<Bls12_381>) {
+        (
+            Fp2::new(
+                Fp381::new([
+                    0xd48056c8c121bdb8,
+                    0x0bac0326a805bbef,
+                    0xb4510b647ae3d177,
+                    0xc6e47ad4fa403b02,
+                    0x260805272dc51051,
+                    0x024aa2b2f08f0a91,
+                ]),
+                Fp381::new([
+                    0xe5ac7d055d042b7e,
+                    0x334cf11213945d57,
+                    0xb5da61bbdc7f5049,
+                    0x596bd0d09920b61a,
+                    0x7dacd3a088274f65,
+                    0x13e02b6052719f60,
+                ]),
+            ),
+            Fp2::new(
+                Fp381::new([
+                    0xe193548608b82801,
+                    0x923ac9cc3baca289,
+                    0x6d429a695160d12c,
+                    0xadfd9baa8cbdd3a7,
+                    0x8cc9cdc6da2e351a,
+                    0x0ce5d527727d6e11,
+                ]),
+                Fp381::new([
+                    0xaaa9075ff05f79be,
+                    0x3f370d275cec1da1,
+                    0x267492ab572e99ab,
+                    0xcb3e287e85a763af,
+                    0x32acd2b02bc28b99,
+                    0x0606c4a02ea734cc,
+                ]),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+    use weierstrass::Jacobian;
+
+    fn check_generators<P: PairingParams>() {
+        assert!(Affine::<P::G1>::generator().is_in_subgroup());
+        assert!(Affine::<P::G2>::generator().is_in_subgroup());
+        let g2 = Jacobian::<P::G2>::generator();
+        assert!(g2.is_on_curve());
+        assert!(!g2.mul_limbs(P::G2::COFACTOR).is_identity());
+    }
+
+    fn check_bilinear<P: PairingParams>(seed: u64) {
+        let mut rng = rng_from_seed(seed);
+        let a = P::Scalar::random(&mut rng);
+        let b = P::Scalar::random(&mut rng);
+        let g1 = Jacobian::<P::G1>::generator();
+        let g2 = Jacobian::<P::G2>::generator();
+        let e = pairing::<P>(&g1.to_affine(), &g2.to_affine());
+        let e_ab = pairing::<P>(
+            &g1.scalar_mul(&a).to_affine(),
+            &g2.scalar_mul(&b).to_affine(),
+        );
+        assert_eq!(e_ab, e.pow_limbs(&(a * b).to_limbs()));
+        let e_a = pairing::<P>(&g1.scalar_mul(&a).to_affine(), &g2.to_affine());
+        let e_b = pairing::<P>(&g1.to_affine(), &g2.scalar_mul(&b).to_affine());
+        assert_eq!(e_a, e.pow_limbs(&a.to_limbs()));
+        assert_eq!(e_b, e.pow_limbs(&b.to_limbs()));
+        // e(P, Q + R) = e(P, Q) e(P, R)
+        let e_sum = pairing::<P>(&g1.to_affine(), &(g2 + g2.scalar_mul(&b)).to_affine());
+        assert_eq!(e_sum, e * e_b);
+    }
+
+    fn check_non_degenerate<P: PairingParams>() {
+        let g1 = Affine::<P::G1>::generator();
+        let g2 = Affine::<P::G2>::generator();
+        let e = pairing::<P>(&g1, &g2);
+        assert_ne!(e, Gt::<P>::one());
+        assert_eq!(e.pow_limbs(P::Scalar::PRIME), Gt::<P>::one());
+        assert_eq!(pairing::<P>(&Affine::identity(), &g2), Gt::<P>::one());
+        assert_eq!(pairing::<P>(&g1, &Affine::identity()), Gt::<P>::one());
+        assert_eq!(pairing::<P>(&-g1, &g2), e.conjugate());
+        assert_eq!(pairing::<P>(&g1, &-g2), e.inv());
+    }
+
+    #[test]
+    fn generators_are_in_their_subgroups() {
+        check_generators::<Bn254>();
+        check_generators::<Bls12_381>();
+    }
+
+    #[test]
+    fn bn254_is_non_degenerate() {
+        check_non_degenerate::<Bn254>();
+    }
+
+    #[test]
+    fn bls12_381_is_non_degenerate() {
+        check_non_degenerate::<Bls12_381>();
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4))]
+        #[test]
+        fn bn254_is_bilinear(seed in any::<u64>()) {
+            check_bilinear::<Bn254>(seed);
+        }
+
+        #[test]
+        fn bls12_381_is_bilinear(seed in any::<u64>()) {
+            check_bilinear::<Bls12_381>(seed);
+        }
+    }
+}
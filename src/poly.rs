@@ -0,0 +1,469 @@
+use field::PrimeField;
+use ntt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Below this many points, multipoint evaluation and interpolation just use Horner's rule rather
+/// than splitting the points over a subproduct tree.
+const MULTIPOINT_CUTOFF: usize = 16;
+
+/// Products where both factors have at least this many coefficients go through the NTT, if the
+/// field has roots of unity of high enough order. Smaller ones are faster done by schoolbook.
+pub(crate) const NTT_CUTOFF: usize = 64;
+
+/// A univariate polynomial with coefficients in a prime field, least significant coefficient
+/// first. The coefficients are always trimmed so the leading coefficient is non-zero, which makes
+/// the zero polynomial the empty vector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Poly<F: PrimeField> {
+    coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> Poly<F> {
+    ///Create a polynomial from coefficients, least significant first.
+    pub fn new(coeffs: Vec<F>) -> Poly<F> {
+        let mut ret = Poly { coeffs };
+        ret.trim();
+        ret
+    }
+
+    pub fn zero() -> Poly<F> {
+        Poly { coeffs: vec![] }
+    }
+
+    pub fn one() -> Poly<F> {
+        Poly::constant(F::one())
+    }
+
+    pub fn constant(c: F) -> Poly<F> {
+        Poly::new(vec![c])
+    }
+
+    ///The polynomial `c * x^degree`.
+    pub fn monomial(c: F, degree: usize) -> Poly<F> {
+        let mut coeffs = vec![F::zero(); degree + 1];
+        coeffs[degree] = c;
+        Poly::new(coeffs)
+    }
+
+    ///The polynomial `x`.
+    pub fn x() -> Poly<F> {
+        Poly::monomial(F::one(), 1)
+    }
+
+    ///The monic polynomial which vanishes on exactly `points`, `(x - x_0)(x - x_1)...`.
+    pub fn vanishing(points: &[F]) -> Poly<F> {
+        match points.len() {
+            0 => Poly::one(),
+            1 => Poly::new(vec![-points[0], F::one()]),
+            n => {
+                let (left, right) = points.split_at(n / 2);
+                &Poly::vanishing(left) * &Poly::vanishing(right)
+            }
+        }
+    }
+
+    ///The coefficients, least significant first, with no trailing zeros.
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    ///Coefficient of `x^i`, which is zero past the degree.
+    pub fn coeff(&self, i: usize) -> F {
+        self.coeffs.get(i).cloned().unwrap_or_else(F::zero)
+    }
+
+    ///The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coeffs.len() == 1 && self.coeffs[0].is_one()
+    }
+
+    ///The leading coefficient, zero for the zero polynomial.
+    pub fn leading_coefficient(&self) -> F {
+        self.coeffs.last().cloned().unwrap_or_else(F::zero)
+    }
+
+    ///Scale so the leading coefficient is one. The zero polynomial stays zero.
+    pub fn monic(&self) -> Poly<F> {
+        if self.is_zero() {
+            self.clone()
+        } else {
+            self.scale(self.leading_coefficient().inv())
+        }
+    }
+
+    ///Multiply every coefficient by `c`.
+    pub fn scale(&self, c: F) -> Poly<F> {
+        Poly::new(self.coeffs.iter().map(|a| *a * c).collect())
+    }
+
+    ///Multiply by `x^n`.
+    pub fn shift(&self, n: usize) -> Poly<F> {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let mut coeffs = vec![F::zero(); n];
+        coeffs.extend_from_slice(&self.coeffs);
+        Poly { coeffs }
+    }
+
+    ///Evaluate at `x` with Horner's rule.
+    pub fn evaluate(&self, x: F) -> F {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, c| acc * x + *c)
+    }
+
+    ///Evaluate at every one of `points`. Large sets of points are split recursively so the
+    ///polynomial is only ever reduced modulo the vanishing polynomial of each half.
+    pub fn evaluate_many(&self, points: &[F]) -> Vec<F> {
+        let mut ret = Vec::with_capacity(points.len());
+        self.evaluate_many_into(points, &mut ret);
+        ret
+    }
+
+    fn evaluate_many_into(&self, points: &[F], out: &mut Vec<F>) {
+        if points.len() <= MULTIPOINT_CUTOFF {
+            out.extend(points.iter().map(|x| self.evaluate(*x)));
+        } else {
+            let (left, right) = points.split_at(points.len() / 2);
+            (self % &Poly::vanishing(left)).evaluate_many_into(left, out);
+            (self % &Poly::vanishing(right)).evaluate_many_into(right, out);
+        }
+    }
+
+    ///The unique polynomial of degree less than `points.len()` passing through every `(x, y)`,
+    ///found by Lagrange interpolation. Panics if two points share an `x`.
+    pub fn interpolate(points: &[(F, F)]) -> Poly<F> {
+        let xs: Vec<F> = points.iter().map(|&(x, _)| x).collect();
+        // The Lagrange basis polynomial for x_i is M(x) / ((x - x_i) * M'(x_i)) where M vanishes
+        // on every x, so we only need the weights y_i / M'(x_i).
+        let weights = Poly::vanishing(&xs).derivative().evaluate_many(&xs);
+        let scaled: Vec<(F, F)> = points
+            .iter()
+            .zip(weights)
+            .map(|(&(x, y), w)| (x, y / w))
+            .collect();
+        Poly::interpolate_weighted(&scaled).0
+    }
+
+    ///Returns `(sum_i w_i * M(x) / (x - x_i), M(x))` for `M` vanishing on every `x_i`.
+    fn interpolate_weighted(points: &[(F, F)]) -> (Poly<F>, Poly<F>) {
+        match points.len() {
+            0 => (Poly::zero(), Poly::one()),
+            1 => (
+                Poly::constant(points[0].1),
+                Poly::new(vec![-points[0].0, F::one()]),
+            ),
+            n => {
+                let (left, right) = points.split_at(n / 2);
+                let (left_sum, left_vanishing) = Poly::interpolate_weighted(left);
+                let (right_sum, right_vanishing) = Poly::interpolate_weighted(right);
+                (
+                    &(&left_sum * &right_vanishing) + &(&right_sum * &left_vanishing),
+                    &left_vanishing * &right_vanishing,
+                )
+            }
+        }
+    }
+
+    ///The formal derivative.
+    pub fn derivative(&self) -> Poly<F> {
+        Poly::new(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, c)| *c * F::from(i as u64))
+                .collect(),
+        )
+    }
+
+    ///The composition `self(other(x))`.
+    pub fn compose(&self, other: &Poly<F>) -> Poly<F> {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Poly::zero(), |acc, c| &(&acc * other) + &Poly::constant(*c))
+    }
+
+    ///Raise to the power `exp` (given in limbs, least significant first) modulo `modulus`.
+    pub fn pow_mod(&self, exp: &[u64], modulus: &Poly<F>) -> Poly<F> {
+        let base = self % modulus;
+        let mut ret = &Poly::one() % modulus;
+        for limb in exp.iter().rev() {
+            for i in (0..64).rev() {
+                ret = &(&ret * &ret) % modulus;
+                if (limb >> i) & 1 == 1 {
+                    ret = &(&ret * &base) % modulus;
+                }
+            }
+        }
+        ret
+    }
+
+    ///Long division, returning `(quotient, remainder)`. Large divisions in fields with an NTT
+    ///go through `div_rem_newton` instead. Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Poly<F>) -> (Poly<F>, Poly<F>) {
+        assert!(!divisor.is_zero(), "You cannot divide by zero.");
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return (Poly::zero(), self.clone());
+        }
+        let dlen = divisor.coeffs.len();
+        let quotient_len = self.coeffs.len() - dlen + 1;
+        if dlen.min(quotient_len) >= NTT_CUTOFF
+            && ntt::supports::<F>(self.coeffs.len().next_power_of_two())
+        {
+            return self.div_rem_newton(divisor);
+        }
+        let lead_inv = divisor.leading_coefficient().inv();
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![F::zero(); quotient_len];
+        for i in (0..quot.len()).rev() {
+            let c = rem[i + dlen - 1] * lead_inv;
+            quot[i] = c;
+            for (dst, d) in rem[i..].iter_mut().zip(divisor.coeffs.iter()) {
+                *dst -= c * *d;
+            }
+        }
+        rem.truncate(dlen - 1);
+        (Poly::new(quot), Poly::new(rem))
+    }
+
+    ///Monic greatest common divisor. The gcd of two zero polynomials is zero.
+    pub fn gcd(&self, other: &Poly<F>) -> Poly<F> {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    ///Extended Euclidean algorithm, returning `(g, s, t)` with `s*self + t*other = g` for the
+    ///monic `g = gcd(self, other)`.
+    pub fn ext_gcd(&self, other: &Poly<F>) -> (Poly<F>, Poly<F>, Poly<F>) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (Poly::one(), Poly::zero());
+        let (mut t0, mut t1) = (Poly::zero(), Poly::one());
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = &s0 - &(&q * &s1);
+            let t = &t0 - &(&q * &t1);
+            r0 = r1;
+            r1 = r;
+            s0 = s1;
+            s1 = s;
+            t0 = t1;
+            t1 = t;
+        }
+        if r0.is_zero() {
+            return (r0, s0, t0);
+        }
+        let scale = r0.leading_coefficient().inv();
+        (r0.scale(scale), s0.scale(scale), t0.scale(scale))
+    }
+
+    fn trim(&mut self) {
+        let len = self
+            .coeffs
+            .iter()
+            .rposition(|c| !c.is_zero())
+            .map_or(0, |i| i + 1);
+        self.coeffs.truncate(len);
+    }
+}
+
+///Schoolbook multiplication of coefficient slices.
+fn mul_schoolbook<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut ret = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            ret[i + j] += *x * *y;
+        }
+    }
+    ret
+}
+
+impl<F: PrimeField> Add<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+    fn add(self, other: &Poly<F>) -> Poly<F> {
+        let (long, short) = if self.coeffs.len() >= other.coeffs.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut coeffs = long.coeffs.clone();
+        for (dst, c) in coeffs.iter_mut().zip(short.coeffs.iter()) {
+            *dst += *c;
+        }
+        Poly::new(coeffs)
+    }
+}
+
+impl<F: PrimeField> Sub<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+    fn sub(self, other: &Poly<F>) -> Poly<F> {
+        let mut coeffs = self.coeffs.clone();
+        if coeffs.len() < other.coeffs.len() {
+            coeffs.resize(other.coeffs.len(), F::zero());
+        }
+        for (dst, c) in coeffs.iter_mut().zip(other.coeffs.iter()) {
+            *dst -= *c;
+        }
+        Poly::new(coeffs)
+    }
+}
+
+impl<F: PrimeField> Mul<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+    fn mul(self, other: &Poly<F>) -> Poly<F> {
+        if self.coeffs.len().min(other.coeffs.len()) >= NTT_CUTOFF {
+            if let Some(coeffs) = ntt::mul(&self.coeffs, &other.coeffs) {
+                return Poly::new(coeffs);
+            }
+        }
+        Poly::new(mul_schoolbook(&self.coeffs, &other.coeffs))
+    }
+}
+
+impl<F: PrimeField> Div<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+    fn div(self, other: &Poly<F>) -> Poly<F> {
+        self.div_rem(other).0
+    }
+}
+
+impl<F: PrimeField> Rem<&Poly<F>> for &Poly<F> {
+    type Output = Poly<F>;
+    fn rem(self, other: &Poly<F>) -> Poly<F> {
+        self.div_rem(other).1
+    }
+}
+
+impl<F: PrimeField> Neg for &Poly<F> {
+    type Output = Poly<F>;
+    fn neg(self) -> Poly<F> {
+        Poly {
+            coeffs: self.coeffs.iter().map(|c| -*c).collect(),
+        }
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! poly_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<F: PrimeField> $imp for Poly<F> {
+        type Output = Poly<F>;
+        #[inline]
+        fn $method(self, other: Poly<F>) -> Poly<F> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+poly_owned_ops! { Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem }
+
+impl<F: PrimeField> Neg for Poly<F> {
+    type Output = Poly<F>;
+    #[inline]
+    fn neg(self) -> Poly<F> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_255::Fp255;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+
+    fn p(coeffs: &[i64]) -> Poly<Fp256> {
+        Poly::new(coeffs.iter().map(|&c| Fp256::from(c)).collect())
+    }
+
+    #[test]
+    fn trims_leading_zeros() {
+        assert_eq!(p(&[1, 2, 0, 0]).coeffs().len(), 2);
+        assert_eq!(p(&[0, 0]), Poly::zero());
+        assert_eq!(Poly::<Fp256>::zero().degree(), None);
+        assert_eq!(p(&[5]).degree(), Some(0));
+        assert_eq!(p(&[1, 1]) - p(&[0, 1]), Poly::one());
+    }
+
+    #[test]
+    fn known_products_and_division() {
+        // (x + 1)(x - 1) = x^2 - 1
+        assert_eq!(p(&[1, 1]) * p(&[-1, 1]), p(&[-1, 0, 1]));
+        // x^3 + 2x + 5 = (x^2 + 1)(x) + (x + 5)
+        let (q, r) = p(&[5, 2, 0, 1]).div_rem(&p(&[1, 0, 1]));
+        assert_eq!(q, p(&[0, 1]));
+        assert_eq!(r, p(&[5, 1]));
+        assert_eq!(
+            p(&[1, 2]).div_rem(&p(&[1, 0, 1])),
+            (Poly::zero(), p(&[1, 2]))
+        );
+    }
+
+    #[test]
+    fn gcd_of_shared_factor() {
+        // (x - 1)(x - 2) and (x - 1)(x + 3) share x - 1
+        let a = p(&[-1, 1]) * p(&[-2, 1]);
+        let b = (p(&[-1, 1]) * p(&[3, 1])).scale(Fp256::from(7u64));
+        assert_eq!(a.gcd(&b), p(&[-1, 1]));
+        let (g, s, t) = a.ext_gcd(&b);
+        assert_eq!(g, p(&[-1, 1]));
+        assert_eq!(&(&s * &a) + &(&t * &b), g);
+        assert_eq!(Poly::<Fp256>::zero().gcd(&Poly::zero()), Poly::zero());
+    }
+
+    #[test]
+    fn derivative_and_compose() {
+        // d/dx (3x^3 + x + 4) = 9x^2 + 1
+        assert_eq!(p(&[4, 1, 0, 3]).derivative(), p(&[1, 0, 9]));
+        assert_eq!(p(&[4]).derivative(), Poly::zero());
+        // (x^2 + 1) o (x + 1) = x^2 + 2x + 2
+        assert_eq!(p(&[1, 0, 1]).compose(&p(&[1, 1])), p(&[2, 2, 1]));
+    }
+
+    #[test]
+    fn multipoint_matches_horner_past_cutoff() {
+        let f = Poly::new((0u64..40).map(|i| Fp480::from(i * i + 3)).collect());
+        let points: Vec<Fp480> = (0u64..50).map(|i| Fp480::from(1000 - i * 7)).collect();
+        let expected: Vec<Fp480> = points.iter().map(|x| f.evaluate(*x)).collect();
+        assert_eq!(f.evaluate_many(&points), expected);
+
+        let samples: Vec<(Fp480, Fp480)> = points.iter().cloned().zip(expected).collect();
+        assert_eq!(Poly::interpolate(&samples), f);
+    }
+
+    #[test]
+    fn ntt_product_matches_schoolbook() {
+        let a: Vec<Fp255> = (0u64..100).map(|i| Fp255::from(i * i + 1)).collect();
+        let b: Vec<Fp255> = (0u64..70).map(|i| -Fp255::from(3 * i + 2)).collect();
+        let expected = Poly::new(mul_schoolbook(&a, &b));
+        assert_eq!(Poly::new(a) * Poly::new(b), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interpolate_duplicate_x_panics() {
+        Poly::interpolate(&[
+            (Fp256::from(1u64), Fp256::from(2u64)),
+            (Fp256::from(1u64), Fp256::from(3u64)),
+        ]);
+    }
+}
@@ -0,0 +1,470 @@
+//! Truncated formal power series over prime fields. Inverse, square root, log and exp are all
+//! computed by Newton iteration, doubling the number of correct coefficients each step, so they
+//! cost a small multiple of a multiplication at the full precision.
+use field::PrimeField;
+use ntt;
+use poly::{Poly, NTT_CUTOFF};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A power series known modulo `x^precision`, least significant coefficient first. There are
+/// always exactly `precision` coefficients, so trailing zeros are significant here, unlike in
+/// `Poly`. Combining two series gives a result at the smaller of their precisions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerSeries<F: PrimeField> {
+    coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> PowerSeries<F> {
+    ///Create a series from its first coefficients. Coefficients past `precision` are dropped and
+    ///missing ones are zero.
+    pub fn new(mut coeffs: Vec<F>, precision: usize) -> PowerSeries<F> {
+        coeffs.resize(precision, F::zero());
+        PowerSeries { coeffs }
+    }
+
+    pub fn zero(precision: usize) -> PowerSeries<F> {
+        PowerSeries::new(vec![], precision)
+    }
+
+    pub fn one(precision: usize) -> PowerSeries<F> {
+        PowerSeries::new(vec![F::one()], precision)
+    }
+
+    ///The series `x`.
+    pub fn x(precision: usize) -> PowerSeries<F> {
+        PowerSeries::new(vec![F::zero(), F::one()], precision)
+    }
+
+    ///The polynomial as a series mod `x^precision`.
+    pub fn from_poly(poly: &Poly<F>, precision: usize) -> PowerSeries<F> {
+        PowerSeries::new(poly.coeffs().to_vec(), precision)
+    }
+
+    ///The known coefficients as a polynomial.
+    pub fn to_poly(&self) -> Poly<F> {
+        Poly::new(self.coeffs.clone())
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    ///The coefficient of `x^i`. Panics if `i` is not below the precision, since that
+    ///coefficient isn't known.
+    pub fn coeff(&self, i: usize) -> F {
+        assert!(i < self.precision(), "Coefficient is past the precision.");
+        self.coeffs[i]
+    }
+
+    pub fn precision(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    ///The same series known to a lower precision. Panics if `precision` is higher than the
+    ///current one, as the extra coefficients aren't known.
+    pub fn truncate(&self, precision: usize) -> PowerSeries<F> {
+        assert!(
+            precision <= self.precision(),
+            "Cannot raise the precision of a power series."
+        );
+        PowerSeries {
+            coeffs: self.coeffs[..precision].to_vec(),
+        }
+    }
+
+    pub fn scale(&self, c: F) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: self.coeffs.iter().map(|x| *x * c).collect(),
+        }
+    }
+
+    ///Formal derivative. The top coefficient is lost, so the precision drops by one.
+    pub fn derivative(&self) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: derivative(&self.coeffs),
+        }
+    }
+
+    ///Formal integral with constant term zero. The precision goes up by one.
+    pub fn integral(&self) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: integral(&self.coeffs),
+        }
+    }
+
+    ///The multiplicative inverse. Panics if the constant term is zero.
+    pub fn inverse(&self) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: inverse(&self.coeffs, self.precision()),
+        }
+    }
+
+    ///A square root, or None if the constant term isn't a non-zero square. The root chosen is
+    ///the one whose constant term is the field's `sqrt` of this series' constant term.
+    pub fn sqrt(&self) -> Option<PowerSeries<F>> {
+        let n = self.precision();
+        if n == 0 {
+            return Some(self.clone());
+        }
+        let c = self.coeffs[0];
+        if c.is_zero() {
+            return None;
+        }
+        let mut g = vec![c.sqrt()?];
+        let half = F::from(2u64).inv();
+        let mut k = 1;
+        // g <- (g + f / g) / 2
+        while k < n {
+            k = (2 * k).min(n);
+            let quotient = mul_truncated(&self.coeffs[..k], &inverse(&g, k), k);
+            g.resize(k, F::zero());
+            for (x, q) in g.iter_mut().zip(quotient) {
+                *x = (*x + q) * half;
+            }
+        }
+        Some(PowerSeries { coeffs: g })
+    }
+
+    ///The logarithm `integral(f' / f)`. Panics unless the constant term is one.
+    pub fn log(&self) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: log(&self.coeffs),
+        }
+    }
+
+    ///The exponential `sum f^k / k!`. Panics unless the constant term is zero.
+    pub fn exp(&self) -> PowerSeries<F> {
+        let n = self.precision();
+        if n == 0 {
+            return self.clone();
+        }
+        assert!(
+            self.coeffs[0].is_zero(),
+            "Exp needs a series with constant term zero."
+        );
+        // g <- g (1 - log g + f)
+        let mut g = vec![F::one()];
+        let mut k = 1;
+        while k < n {
+            k = (2 * k).min(n);
+            g.resize(k, F::zero());
+            let mut e: Vec<F> = self.coeffs[..k]
+                .iter()
+                .zip(log(&g))
+                .map(|(f, l)| *f - l)
+                .collect();
+            e[0] += F::one();
+            g = mul_truncated(&g, &e, k);
+        }
+        PowerSeries { coeffs: g }
+    }
+
+    ///Raise the series to a power. Panics if `exp` is negative and the constant term is zero.
+    pub fn pow(&self, exp: i64) -> PowerSeries<F> {
+        let base = if exp < 0 {
+            self.inverse()
+        } else {
+            self.clone()
+        };
+        let mut ret = PowerSeries::one(self.precision());
+        for i in (0..64).rev() {
+            ret = &ret * &ret;
+            if (exp.unsigned_abs() >> i) & 1 == 1 {
+                ret = &ret * &base;
+            }
+        }
+        ret
+    }
+}
+
+impl<F: PrimeField> Poly<F> {
+    ///Division with remainder by way of a power series inverse of the reversed divisor, which
+    ///is faster than long division when the field has fast multiplication. Panics if `divisor`
+    ///is zero.
+    pub fn div_rem_newton(&self, divisor: &Poly<F>) -> (Poly<F>, Poly<F>) {
+        assert!(!divisor.is_zero(), "You cannot divide by zero.");
+        let n = self.coeffs().len();
+        let m = divisor.coeffs().len();
+        if n < m {
+            return (Poly::zero(), self.clone());
+        }
+        // rev(a) = rev(q) rev(b) mod x^(n - m + 1), where rev reverses the coefficients.
+        let k = n - m + 1;
+        let rev_a: Vec<F> = self.coeffs().iter().rev().take(k).cloned().collect();
+        let rev_b: Vec<F> = divisor.coeffs().iter().rev().cloned().collect();
+        let mut rev_q = mul_truncated(&rev_a, &inverse(&rev_b, k), k);
+        rev_q.reverse();
+        let quotient = Poly::new(rev_q);
+        let remainder = self - &(&quotient * divisor);
+        (quotient, remainder)
+    }
+}
+
+///`a * b mod x^n`. Large products go through the NTT if the field supports it; anything else
+///is schoolbook, skipping the terms past `x^n`.
+fn mul_truncated<F: PrimeField>(a: &[F], b: &[F], n: usize) -> Vec<F> {
+    let a = &a[..a.len().min(n)];
+    let b = &b[..b.len().min(n)];
+    if a.len().min(b.len()) >= NTT_CUTOFF {
+        if let Some(mut ret) = ntt::mul(a, b) {
+            ret.resize(n, F::zero());
+            return ret;
+        }
+    }
+    let mut ret = vec![F::zero(); n];
+    for (i, x) in a.iter().enumerate() {
+        for (dst, y) in ret[i..].iter_mut().zip(b.iter()) {
+            *dst += *x * *y;
+        }
+    }
+    ret
+}
+
+///Inverse of `f` mod `x^n`: g <- g (2 - f g).
+fn inverse<F: PrimeField>(f: &[F], n: usize) -> Vec<F> {
+    if n == 0 {
+        return vec![];
+    }
+    assert!(
+        f.first().is_some_and(|c| !c.is_zero()),
+        "You cannot divide by zero."
+    );
+    let mut g = vec![f[0].inv()];
+    let mut k = 1;
+    while k < n {
+        k = (2 * k).min(n);
+        let mut e: Vec<F> = mul_truncated(f, &g, k).into_iter().map(|c| -c).collect();
+        e[0] += F::from(2u64);
+        g = mul_truncated(&g, &e, k);
+    }
+    g
+}
+
+fn derivative<F: PrimeField>(f: &[F]) -> Vec<F> {
+    f.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| *c * F::from(i as u64))
+        .collect()
+}
+
+fn integral<F: PrimeField>(f: &[F]) -> Vec<F> {
+    let mut ret = Vec::with_capacity(f.len() + 1);
+    ret.push(F::zero());
+    ret.extend(
+        f.iter()
+            .enumerate()
+            .map(|(i, c)| *c / F::from(i as u64 + 1)),
+    );
+    ret
+}
+
+fn log<F: PrimeField>(f: &[F]) -> Vec<F> {
+    let n = f.len();
+    if n == 0 {
+        return vec![];
+    }
+    assert!(f[0].is_one(), "Log needs a series with constant term one.");
+    let quotient = mul_truncated(&derivative(f), &inverse(f, n - 1), n - 1);
+    integral(&quotient)
+}
+
+impl<F: PrimeField> Add<&PowerSeries<F>> for &PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    fn add(self, other: &PowerSeries<F>) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: self
+                .coeffs
+                .iter()
+                .zip(other.coeffs.iter())
+                .map(|(a, b)| *a + *b)
+                .collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Sub<&PowerSeries<F>> for &PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    fn sub(self, other: &PowerSeries<F>) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: self
+                .coeffs
+                .iter()
+                .zip(other.coeffs.iter())
+                .map(|(a, b)| *a - *b)
+                .collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Mul<&PowerSeries<F>> for &PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    fn mul(self, other: &PowerSeries<F>) -> PowerSeries<F> {
+        let n = self.precision().min(other.precision());
+        PowerSeries {
+            coeffs: mul_truncated(&self.coeffs, &other.coeffs, n),
+        }
+    }
+}
+
+impl<F: PrimeField> Div<&PowerSeries<F>> for &PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    ///Panics if the constant term of `other` is zero.
+    fn div(self, other: &PowerSeries<F>) -> PowerSeries<F> {
+        let n = self.precision().min(other.precision());
+        PowerSeries {
+            coeffs: mul_truncated(&self.coeffs, &inverse(&other.coeffs, n), n),
+        }
+    }
+}
+
+impl<F: PrimeField> Neg for &PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    fn neg(self) -> PowerSeries<F> {
+        PowerSeries {
+            coeffs: self.coeffs.iter().map(|c| -*c).collect(),
+        }
+    }
+}
+
+/// Forward the by-value operators to the by-reference ones above.
+macro_rules! series_owned_ops { ($($imp:ident, $method:ident);*) => { $(
+    impl<F: PrimeField> $imp for PowerSeries<F> {
+        type Output = PowerSeries<F>;
+        #[inline]
+        fn $method(self, other: PowerSeries<F>) -> PowerSeries<F> {
+            (&self).$method(&other)
+        }
+    }
+)* }}
+
+series_owned_ops! { Add, add; Sub, sub; Mul, mul; Div, div }
+
+impl<F: PrimeField> Neg for PowerSeries<F> {
+    type Output = PowerSeries<F>;
+    #[inline]
+    fn neg(self) -> PowerSeries<F> {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_255::Fp255;
+    use fp_256::Fp256;
+    use num_traits::{Inv, One, Zero};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    fn s(coeffs: &[i64], precision: usize) -> PowerSeries<Fp256> {
+        PowerSeries::new(coeffs.iter().map(|&c| Fp256::from(c)).collect(), precision)
+    }
+
+    fn series_255(coeffs: &[u64], precision: usize) -> PowerSeries<Fp255> {
+        PowerSeries::new(coeffs.iter().map(|&c| Fp255::from(c)).collect(), precision)
+    }
+
+    #[test]
+    fn geometric_series_inverse() {
+        // 1 / (1 - x) = 1 + x + x^2 + ...
+        assert_eq!(s(&[1, -1], 6).inverse(), s(&[1, 1, 1, 1, 1, 1], 6));
+        assert_eq!(s(&[1], 6) / s(&[1, -1], 4), s(&[1, 1, 1, 1], 4));
+        assert_eq!(s(&[1, -1], 6).pow(-2), s(&[1, 2, 3, 4, 5, 6], 6));
+    }
+
+    #[test]
+    fn known_exp_and_log() {
+        // exp(x) = sum x^k / k!
+        let n = 10;
+        let mut factorial = Fp256::one();
+        let mut expected = vec![Fp256::one()];
+        for k in 1..n {
+            factorial *= Fp256::from(k as u64);
+            expected.push(factorial.inv());
+        }
+        assert_eq!(PowerSeries::x(n).exp(), PowerSeries::new(expected, n));
+        // log(1 / (1 - x)) = sum x^k / k
+        let log = s(&[1, -1], n).inverse().log();
+        for k in 1..n {
+            assert_eq!(log.coeff(k), Fp256::from(k as u64).inv());
+        }
+    }
+
+    #[test]
+    fn sqrt_of_known_series() {
+        // (1 + x)^2 = 1 + 2x + x^2
+        let root = s(&[1, 2, 1], 8).sqrt().unwrap();
+        assert!(root == s(&[1, 1], 8) || root == -s(&[1, 1], 8));
+        // p256 = 3 mod 4 so -1 has no root
+        assert_eq!(s(&[-1, 1], 8).sqrt(), None);
+        assert_eq!(s(&[0, 1], 8).sqrt(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "constant term one")]
+    fn log_needs_constant_one() {
+        s(&[2, 1], 4).log();
+    }
+
+    #[test]
+    fn ntt_path_matches_schoolbook() {
+        let a: Vec<u64> = (0..200).map(|i| i * i + 7).collect();
+        let f = series_255(&a, 200);
+        let inv = f.inverse();
+        assert_eq!(&f * &inv, PowerSeries::one(200));
+        // below the cutoff everything is schoolbook
+        assert_eq!(inv.truncate(50), f.truncate(50).inverse());
+        assert_eq!(&f.truncate(50) * &inv.truncate(50), PowerSeries::one(50));
+    }
+
+    #[test]
+    fn newton_division_past_ntt_cutoff() {
+        let a = Poly::new((0u64..300).map(|i| Fp255::from(i * 31 + 2)).collect());
+        let b = Poly::new((0u64..120).map(|i| Fp255::from(i * i + 5)).collect());
+        let (q, r) = a.div_rem_newton(&b);
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+        assert_eq!(a.div_rem(&b), (q, r));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn inverse_times_self_is_one(ref coeffs in vec(any::<i64>(), 1..20), precision in 1usize..40) {
+            prop_assume!(coeffs[0] != 0);
+            let f = s(coeffs, precision);
+            prop_assert_eq!(&f * &f.inverse(), PowerSeries::one(precision));
+        }
+
+        #[test]
+        fn exp_and_log_are_inverses(ref coeffs in vec(any::<i64>(), 0..20), precision in 1usize..40) {
+            let mut f = s(coeffs, precision);
+            f.coeffs[0] = Fp256::zero();
+            let g = f.exp();
+            prop_assert_eq!(g.log(), f.clone());
+            prop_assert_eq!((&f + &f).exp(), &g * &g);
+        }
+
+        #[test]
+        fn sqrt_squares_back(ref coeffs in vec(any::<i64>(), 1..20), precision in 1usize..40) {
+            prop_assume!(coeffs[0] != 0);
+            let f = s(coeffs, precision);
+            let square = &f * &f;
+            let root = square.sqrt().unwrap();
+            prop_assert_eq!(&root * &root, square);
+        }
+
+        #[test]
+        fn newton_division_matches_long_division(
+            ref a in vec(any::<i64>(), 0..30),
+            ref b in vec(any::<i64>(), 1..15),
+        ) {
+            let a = Poly::new(a.iter().map(|c| Fp256::from(*c)).collect());
+            let b = Poly::new(b.iter().map(|c| Fp256::from(*c)).collect());
+            prop_assume!(!b.is_zero());
+            prop_assert_eq!(a.div_rem_newton(&b), a.div_rem(&b));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Reed-Solomon codes over prime fields. A message of `k` field elements is the coefficient
+//! vector of a polynomial of degree below `k`, and its codeword is that polynomial evaluated at
+//! the `n` points `1, 2, ..., n`. Any `k` symbols of a codeword determine the message, and up to
+//! `(n - k) / 2` wrong symbols can be corrected with Gao's decoder.
+use field::PrimeField;
+use poly::Poly;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RsError {
+    ///Fewer symbols survived than the message length.
+    TooFewSymbols { needed: usize, got: usize },
+    ///The received word isn't close enough to any codeword to be corrected.
+    TooManyErrors,
+    ///The message must be non-empty and no longer than the codeword.
+    InvalidLength { message: usize, codeword: usize },
+}
+
+impl fmt::Display for RsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RsError::TooFewSymbols { needed, got } => write!(
+                f,
+                "Need at least {} symbols to decode, but only {} are present.",
+                needed, got
+            ),
+            RsError::TooManyErrors => write!(f, "Too many errors to decode."),
+            RsError::InvalidLength { message, codeword } => write!(
+                f,
+                "Cannot use a message of length {} with codewords of length {}.",
+                message, codeword
+            ),
+        }
+    }
+}
+
+impl Error for RsError {}
+
+///The points codewords of length `n` are evaluated at: `1, 2, ..., n`.
+pub fn evaluation_points<F: PrimeField>(n: usize) -> Vec<F> {
+    (1..=n as u64).map(F::from).collect()
+}
+
+///Encode `data` as a codeword of length `n`. Panics if `data` is empty or longer than `n`.
+pub fn encode<F: PrimeField>(data: &[F], n: usize) -> Vec<F> {
+    check_lengths(data.len(), n).unwrap();
+    Poly::new(data.to_vec()).evaluate_many(&evaluation_points(n))
+}
+
+///Recover a message of length `k` from a codeword where the missing symbols are None. Fails if
+///fewer than `k` symbols are present, or if the present ones don't all lie on one codeword.
+pub fn decode_erasures<F: PrimeField>(received: &[Option<F>], k: usize) -> Result<Vec<F>, RsError> {
+    check_lengths(k, received.len())?;
+    let points: Vec<(F, F)> = evaluation_points(received.len())
+        .into_iter()
+        .zip(received)
+        .filter_map(|(x, y)| y.map(|y| (x, y)))
+        .collect();
+    if points.len() < k {
+        return Err(RsError::TooFewSymbols {
+            needed: k,
+            got: points.len(),
+        });
+    }
+    let message = Poly::interpolate(&points);
+    if message.coeffs().len() > k {
+        return Err(RsError::TooManyErrors);
+    }
+    Ok(pad(&message, k))
+}
+
+///Recover a message of length `k` from a codeword with up to `(n - k) / 2` wrong symbols, using
+///Gao's algorithm. With more errors than that this either fails or decodes to a different
+///codeword, as no decoder can tell.
+pub fn decode_errors<F: PrimeField>(received: &[F], k: usize) -> Result<Vec<F>, RsError> {
+    let n = received.len();
+    check_lengths(k, n)?;
+    let xs = evaluation_points(n);
+    let points: Vec<(F, F)> = xs.iter().cloned().zip(received.iter().cloned()).collect();
+    // Run the extended Euclidean algorithm on g0 = prod(x - x_i) and the interpolant g1 of the
+    // received word, stopping at the first remainder g of degree below (n + k) / 2 with
+    // g = u g0 + v g1. If few enough symbols are wrong, v is the error locator and g / v is the
+    // message.
+    let stop = (n + k).div_ceil(2);
+    let (mut r0, mut r1) = (Poly::vanishing(&xs), Poly::interpolate(&points));
+    let (mut v0, mut v1) = (Poly::zero(), Poly::one());
+    while r1.coeffs().len() > stop {
+        let (q, r) = r0.div_rem(&r1);
+        let v = &v0 - &(&q * &v1);
+        r0 = r1;
+        r1 = r;
+        v0 = v1;
+        v1 = v;
+    }
+    let (message, remainder) = r1.div_rem(&v1);
+    if !remainder.is_zero() || message.coeffs().len() > k {
+        return Err(RsError::TooManyErrors);
+    }
+    Ok(pad(&message, k))
+}
+
+fn check_lengths(message: usize, codeword: usize) -> Result<(), RsError> {
+    if message == 0 || message > codeword {
+        Err(RsError::InvalidLength { message, codeword })
+    } else {
+        Ok(())
+    }
+}
+
+///The coefficients of `p` padded with zeros to length `k`.
+fn pad<F: PrimeField>(p: &Poly<F>, k: usize) -> Vec<F> {
+    let mut ret = p.coeffs().to_vec();
+    ret.resize(k, F::zero());
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+
+    fn f(x: i64) -> Fp256 {
+        Fp256::from(x)
+    }
+
+    #[test]
+    fn known_codeword() {
+        // 1 + 2x at 1, 2, 3, 4
+        let data = vec![f(1), f(2)];
+        let codeword = encode(&data, 4);
+        assert_eq!(codeword, vec![f(3), f(5), f(7), f(9)]);
+        assert_eq!(decode_errors(&codeword, 2), Ok(data.clone()));
+        // one error is correctable with n - k = 2
+        assert_eq!(
+            decode_errors(&[f(3), f(100), f(7), f(9)], 2),
+            Ok(data.clone())
+        );
+        assert_eq!(
+            decode_erasures(&[None, Some(f(5)), None, Some(f(9))], 2),
+            Ok(data)
+        );
+    }
+
+    #[test]
+    fn uncorrectable_words_are_errors() {
+        // Codewords with k = 1 are constant, and 1, 2, 3 is at distance 2 from every one of them.
+        assert_eq!(
+            decode_errors(&[f(1), f(2), f(3)], 1),
+            Err(RsError::TooManyErrors)
+        );
+        assert_eq!(
+            decode_erasures(&[Some(f(1)), Some(f(2)), Some(f(3))], 1),
+            Err(RsError::TooManyErrors)
+        );
+        assert_eq!(
+            decode_erasures(&[Some(f(1)), None, None], 2),
+            Err(RsError::TooFewSymbols { needed: 2, got: 1 })
+        );
+        assert_eq!(
+            decode_errors(&[f(1)], 2),
+            Err(RsError::InvalidLength {
+                message: 2,
+                codeword: 1
+            })
+        );
+    }
+
+    ///A message, a codeword length and positions to corrupt or erase.
+    fn arb_setup(
+        max_bad: fn(usize, usize) -> usize,
+    ) -> impl Strategy<Value = (Vec<i64>, usize, Vec<usize>)> {
+        (vec(any::<i64>(), 1..10), 0usize..10).prop_flat_map(move |(data, extra)| {
+            let n = data.len() + extra;
+            let bad = max_bad(n, data.len());
+            (
+                Just(data),
+                Just(n),
+                subsequence((0..n).collect::<Vec<_>>(), 0..=bad),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn corrects_errors_up_to_bound(
+            (data, n, positions) in arb_setup(|n, k| (n - k) / 2),
+            offset in 1i64..,
+        ) {
+            let data: Vec<Fp256> = data.iter().map(|x| f(*x)).collect();
+            let mut word = encode(&data, n);
+            for (i, p) in positions.iter().enumerate() {
+                word[*p] += f(offset) + f(i as i64);
+            }
+            prop_assert_eq!(decode_errors(&word, data.len()), Ok(data));
+        }
+
+        #[test]
+        fn recovers_erasures_up_to_bound((data, n, positions) in arb_setup(|n, k| n - k)) {
+            let data: Vec<Fp256> = data.iter().map(|x| f(*x)).collect();
+            let mut word: Vec<Option<Fp256>> = encode(&data, n).into_iter().map(Some).collect();
+            for p in positions {
+                word[p] = None;
+            }
+            prop_assert_eq!(decode_erasures(&word, data.len()), Ok(data));
+        }
+    }
+}
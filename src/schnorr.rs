@@ -0,0 +1,234 @@
+//! Schnorr signatures and non-interactive proofs of knowledge of a discrete log, in the prime
+//! order subgroups of `subgroup` such as the Schnorr group `Schnorr512`.
+//!
+//! To show it knows `x` with `y = b^x`, the prover commits to `t = b^k` for a fresh random `k`,
+//! gets a challenge `c` and answers with `s = k - cx`, and the verifier checks that `t = b^s y^c`.
+//! The Fiat-Shamir transform takes `c` to be a hash of `b`, `y`, `t` and a context instead, which
+//! makes the proof non-interactive: it's just `(c, s)`, and the verifier recomputes `t` and the
+//! hash. A signature is the same proof for the generator and the public key, with the message as
+//! the context. Proofs and signatures hash under different domains, so neither passes for the
+//! other.
+//!
+//! The challenge is the hash reduced mod q, so the hash should be longer than q to keep it close
+//! to uniform, for example SHA-512 for a 256 bit q.
+//!
+//! Anyone who learns a nonce `k` learns `x = (k - s) / c`, so the secret key and the nonces only
+//! go through `SubgroupParams::exp_secret`, whose operations don't depend on the exponent.
+use field::{Field, PrimeField};
+use hmac::digest::Digest;
+use rand::Rng;
+use subgroup::SubgroupParams;
+
+const PROOF_DOMAIN: &[u8] = b"gridiron schnorr proof";
+const SIGNATURE_DOMAIN: &[u8] = b"gridiron schnorr signature";
+
+/// A proof of knowledge of a discrete log, or a signature: the challenge and the response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Proof<S> {
+    pub challenge: S,
+    pub response: S,
+}
+
+/// A signature is a proof of knowledge of the secret key, bound to the message.
+pub type Signature<S> = Proof<S>;
+
+///Prove knowledge of `secret`, the discrete log of `base^secret` to the base `base`, bound to
+///`context`.
+pub fn prove<P: SubgroupParams, D: Digest, R: Rng>(
+    base: &P::Element,
+    secret: &P::Scalar,
+    context: &[u8],
+    rng: &mut R,
+) -> Proof<P::Scalar> {
+    let public = P::exp_secret(base, secret);
+    respond::<P, D, R>(PROOF_DOMAIN, base, &public, secret, context, rng)
+}
+
+///Check a proof from `prove` that its maker knows the discrete log of `public` to the base
+///`base`. False if either isn't a member of the subgroup.
+pub fn verify_proof<P: SubgroupParams, D: Digest>(
+    base: &P::Element,
+    public: &P::Element,
+    context: &[u8],
+    proof: &Proof<P::Scalar>,
+) -> bool {
+    P::is_member(base)
+        && P::is_member(public)
+        && check::<P, D>(PROOF_DOMAIN, base, public, context, proof)
+}
+
+///The public key `g^secret` of a secret key.
+pub fn public_key<P: SubgroupParams>(secret: &P::Scalar) -> P::Element {
+    P::exp_secret(&P::generator(), secret)
+}
+
+///Sign `message` with a secret key.
+pub fn sign<P: SubgroupParams, D: Digest, R: Rng>(
+    secret: &P::Scalar,
+    message: &[u8],
+    rng: &mut R,
+) -> Signature<P::Scalar> {
+    let public = public_key::<P>(secret);
+    respond::<P, D, R>(
+        SIGNATURE_DOMAIN,
+        &P::generator(),
+        &public,
+        secret,
+        message,
+        rng,
+    )
+}
+
+///True if `signature` is a signature of `message` under `public`, which has to be a member of
+///the subgroup.
+pub fn verify<P: SubgroupParams, D: Digest>(
+    public: &P::Element,
+    message: &[u8],
+    signature: &Signature<P::Scalar>,
+) -> bool {
+    P::is_member(public)
+        && check::<P, D>(
+            SIGNATURE_DOMAIN,
+            &P::generator(),
+            public,
+            message,
+            signature,
+        )
+}
+
+fn respond<P: SubgroupParams, D: Digest, R: Rng>(
+    domain: &[u8],
+    base: &P::Element,
+    public: &P::Element,
+    secret: &P::Scalar,
+    context: &[u8],
+    rng: &mut R,
+) -> Proof<P::Scalar> {
+    let k = P::Scalar::random(rng);
+    let commitment = P::exp_secret(base, &k);
+    let challenge = challenge::<P, D>(domain, base, public, &commitment, context);
+    Proof {
+        challenge,
+        response: k - challenge * *secret,
+    }
+}
+
+fn check<P: SubgroupParams, D: Digest>(
+    domain: &[u8],
+    base: &P::Element,
+    public: &P::Element,
+    context: &[u8],
+    proof: &Proof<P::Scalar>,
+) -> bool {
+    let commitment = P::multi_exp(&[(*base, proof.response), (*public, proof.challenge)]);
+    challenge::<P, D>(domain, base, public, &commitment, context) == proof.challenge
+}
+
+///The Fiat-Shamir challenge. The elements all encode to the same length, so the context can go
+///last without any ambiguity.
+fn challenge<P: SubgroupParams, D: Digest>(
+    domain: &[u8],
+    base: &P::Element,
+    public: &P::Element,
+    commitment: &P::Element,
+    context: &[u8],
+) -> P::Scalar {
+    let mut hash = D::new();
+    hash.input(domain);
+    for x in [base, public, commitment].iter() {
+        hash.input(x.to_bytes());
+    }
+    hash.input(context);
+    P::Scalar::from_bytes_reduced(&hash.result())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use fp_safe_256::FpSafe256;
+    use fp_safe_256_q::FpSafe256Q;
+    use fp_schnorr_512::FpSchnorr512;
+    use proptest::prelude::*;
+    use sha2::Sha512;
+    use subgroup::{SafePrime256, Schnorr512};
+    use test_util::rng_from_seed;
+    use vss::PedersenParams;
+
+    #[test]
+    fn rejects_non_members() {
+        let mut rng = rng_from_seed(47);
+        let secret = Fp256::from(12345u64);
+        let g = Schnorr512::generator();
+        let proof = prove::<Schnorr512, Sha512, _>(&g, &secret, b"", &mut rng);
+        let public = public_key::<Schnorr512>(&secret);
+        assert!(verify_proof::<Schnorr512, Sha512>(&g, &public, b"", &proof));
+        // 2 isn't in the subgroup, and neither is anything it multiplies a member by.
+        let two = FpSchnorr512::from(2u64);
+        assert!(!verify_proof::<Schnorr512, Sha512>(
+            &g,
+            &(public * two),
+            b"",
+            &proof
+        ));
+        assert!(!verify_proof::<Schnorr512, Sha512>(
+            &two, &public, b"", &proof
+        ));
+        let zero = FpSchnorr512::from(0u64);
+        let signature = sign::<Schnorr512, Sha512, _>(&secret, b"", &mut rng);
+        assert!(!verify::<Schnorr512, Sha512>(&zero, b"", &signature));
+    }
+
+    #[test]
+    fn signatures_and_proofs_are_separate() {
+        let mut rng = rng_from_seed(48);
+        let secret = FpSafe256Q::from(99u64);
+        let public = public_key::<SafePrime256>(&secret);
+        let g = SafePrime256::generator();
+        let signature = sign::<SafePrime256, Sha512, _>(&secret, b"message", &mut rng);
+        assert!(verify::<SafePrime256, Sha512>(
+            &public, b"message", &signature
+        ));
+        assert!(!verify_proof::<SafePrime256, Sha512>(
+            &g, &public, b"message", &signature
+        ));
+        let proof = prove::<SafePrime256, Sha512, _>(&g, &secret, b"message", &mut rng);
+        assert!(!verify::<SafePrime256, Sha512>(&public, b"message", &proof));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+        #[test]
+        fn sign_then_verify(seed in any::<u64>(), ref message in any::<Vec<u8>>()) {
+            let mut rng = rng_from_seed(seed);
+            let secret = Fp256::random(&mut rng);
+            let public = public_key::<Schnorr512>(&secret);
+            let signature = sign::<Schnorr512, Sha512, _>(&secret, message, &mut rng);
+            prop_assert!(verify::<Schnorr512, Sha512>(&public, message, &signature));
+            let mut other = message.clone();
+            other.push(0);
+            prop_assert!(!verify::<Schnorr512, Sha512>(&public, &other, &signature));
+            let forged = Signature { challenge: signature.challenge, response: signature.response + Fp256::from(1u64) };
+            prop_assert!(!verify::<Schnorr512, Sha512>(&public, message, &forged));
+            let wrong_key = public_key::<Schnorr512>(&(secret + Fp256::from(1u64)));
+            prop_assert!(!verify::<Schnorr512, Sha512>(&wrong_key, message, &signature));
+        }
+
+        #[test]
+        fn proofs_of_knowledge(seed in any::<u64>()) {
+            // Prove knowledge of a log to the base of the Pedersen generator h rather than g.
+            let mut rng = rng_from_seed(seed);
+            let h: FpSafe256 = SafePrime256::second_generator();
+            let secret = FpSafe256Q::random(&mut rng);
+            let public = SafePrime256::exp(&h, &secret);
+            let proof = prove::<SafePrime256, Sha512, _>(&h, &secret, b"context", &mut rng);
+            prop_assert!(verify_proof::<SafePrime256, Sha512>(&h, &public, b"context", &proof));
+            prop_assert!(!verify_proof::<SafePrime256, Sha512>(&h, &public, b"other", &proof));
+            let g = SafePrime256::generator();
+            prop_assert!(!verify_proof::<SafePrime256, Sha512>(&g, &public, b"context", &proof));
+            // A proof for one statement doesn't carry over to a related one.
+            let square = public * public;
+            prop_assert!(!verify_proof::<SafePrime256, Sha512>(&h, &square, b"context", &proof));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Shamir secret sharing over prime fields. A secret is the constant term of a random polynomial
+//! of degree `t - 1`, and share `i` is the point `(i, f(i))` for `i` in `1..=n`. Any `t` shares
+//! recover the secret by Lagrange interpolation at zero; fewer reveal nothing about it.
+use field::{batch_inverse, PrimeField};
+use poly::Poly;
+use rand::Rng;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShamirError {
+    ///The threshold must be at least one and at most the number of shares.
+    InvalidThreshold { threshold: usize, shares: usize },
+    ///Fewer shares were given than are needed.
+    TooFewShares { needed: usize, got: usize },
+    ///Two shares have the same x coordinate.
+    DuplicateX,
+    ///A share has x coordinate zero, where the secret itself lives.
+    ZeroX,
+    ///There are more shares than the threshold and they don't all lie on one polynomial of
+    ///degree below the threshold.
+    InconsistentShares,
+    ///Serialized share bytes have the wrong length or hold a value outside the field.
+    InvalidEncoding,
+}
+
+impl fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ShamirError::InvalidThreshold { threshold, shares } => write!(
+                f,
+                "Threshold {} is invalid for {} shares.",
+                threshold, shares
+            ),
+            ShamirError::TooFewShares { needed, got } => {
+                write!(f, "Need at least {} shares, but only got {}.", needed, got)
+            }
+            ShamirError::DuplicateX => write!(f, "Two shares have the same x coordinate."),
+            ShamirError::ZeroX => write!(f, "A share has x coordinate zero."),
+            ShamirError::InconsistentShares => {
+                write!(f, "The shares don't agree on a single secret.")
+            }
+            ShamirError::InvalidEncoding => write!(f, "Invalid share encoding."),
+        }
+    }
+}
+
+impl Error for ShamirError {}
+
+///Split `secret` into `n` shares, any `threshold` of which can reconstruct it.
+pub fn split<F: PrimeField, R: Rng>(
+    secret: F,
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<Vec<(F, F)>, ShamirError> {
+    check_threshold(threshold, n)?;
+    let f = random_polynomial(secret, threshold, rng);
+    Ok(shares_of(&f, n))
+}
+
+pub(crate) fn check_threshold(threshold: usize, n: usize) -> Result<(), ShamirError> {
+    if threshold == 0 || threshold > n {
+        Err(ShamirError::InvalidThreshold {
+            threshold,
+            shares: n,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+///A polynomial of degree below `threshold` with constant term `secret` and the rest random.
+pub(crate) fn random_polynomial<F: PrimeField, R: Rng>(
+    secret: F,
+    threshold: usize,
+    rng: &mut R,
+) -> Poly<F> {
+    let mut coeffs = Vec::with_capacity(threshold);
+    coeffs.push(secret);
+    coeffs.extend((1..threshold).map(|_| F::random(rng)));
+    Poly::new(coeffs)
+}
+
+///The shares `(i, f(i))` for `i` in `1..=n`.
+pub(crate) fn shares_of<F: PrimeField>(f: &Poly<F>, n: usize) -> Vec<(F, F)> {
+    (1..=n as u64)
+        .map(|i| {
+            let x = F::from(i);
+            (x, f.evaluate(x))
+        })
+        .collect()
+}
+
+///Recover the secret from shares by Lagrange interpolation at zero, using every share given.
+///Nothing here knows the threshold, so too few shares give a wrong secret rather than an error;
+///use `reconstruct_with_threshold` to check.
+pub fn reconstruct<F: PrimeField>(shares: &[(F, F)]) -> Result<F, ShamirError> {
+    if shares.is_empty() {
+        return Err(ShamirError::TooFewShares { needed: 1, got: 0 });
+    }
+    check_xs(shares)?;
+    // secret = sum y_i * prod_{j != i} x_j / (x_j - x_i)
+    //        = all_xs * sum y_i / (x_i * prod_{j != i} (x_j - x_i))
+    let mut denominators: Vec<F> = shares
+        .iter()
+        .map(|&(xi, _)| {
+            shares
+                .iter()
+                .filter(|&&(xj, _)| xj != xi)
+                .fold(xi, |acc, &(xj, _)| acc * (xj - xi))
+        })
+        .collect();
+    batch_inverse(&mut denominators);
+    let all_xs = shares.iter().fold(F::one(), |acc, &(x, _)| acc * x);
+    let sum = shares
+        .iter()
+        .zip(denominators)
+        .fold(F::zero(), |acc, (&(_, y), d)| acc + y * d);
+    Ok(all_xs * sum)
+}
+
+///Recover the secret, checking that at least `threshold` shares were given and, if there are
+///more than that, that they all agree.
+pub fn reconstruct_with_threshold<F: PrimeField>(
+    shares: &[(F, F)],
+    threshold: usize,
+) -> Result<F, ShamirError> {
+    if threshold == 0 {
+        return Err(ShamirError::InvalidThreshold {
+            threshold,
+            shares: shares.len(),
+        });
+    }
+    if shares.len() < threshold {
+        return Err(ShamirError::TooFewShares {
+            needed: threshold,
+            got: shares.len(),
+        });
+    }
+    check_xs(shares)?;
+    if shares.len() > threshold && Poly::interpolate(shares).coeffs().len() > threshold {
+        return Err(ShamirError::InconsistentShares);
+    }
+    reconstruct(&shares[..threshold])
+}
+
+///Serialize a share as the bytes of x followed by the bytes of y.
+pub fn share_to_bytes<F: PrimeField>(share: &(F, F)) -> Vec<u8> {
+    let mut ret = share.0.to_bytes();
+    ret.extend(share.1.to_bytes());
+    ret
+}
+
+///The inverse of `share_to_bytes`.
+pub fn share_from_bytes<F: PrimeField>(bytes: &[u8]) -> Result<(F, F), ShamirError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(ShamirError::InvalidEncoding);
+    }
+    let (x, y) = bytes.split_at(bytes.len() / 2);
+    match (F::from_bytes(x), F::from_bytes(y)) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        _ => Err(ShamirError::InvalidEncoding),
+    }
+}
+
+fn check_xs<F: PrimeField>(shares: &[(F, F)]) -> Result<(), ShamirError> {
+    let mut xs: Vec<F> = shares.iter().map(|&(x, _)| x).collect();
+    if xs.iter().any(|x| x.is_zero()) {
+        return Err(ShamirError::ZeroX);
+    }
+    xs.sort();
+    if xs.windows(2).any(|w| w[0] == w[1]) {
+        return Err(ShamirError::DuplicateX);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use field::Field;
+    use fp_256::Fp256;
+    use fp_480::Fp480;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn known_shares() {
+        // f(x) = 7 + 3x: shares (1, 10), (2, 13), (3, 16)
+        let shares: Vec<(Fp256, Fp256)> = vec![(1u64, 10u64), (2, 13), (3, 16)]
+            .into_iter()
+            .map(|(x, y)| (x.into(), y.into()))
+            .collect();
+        assert_eq!(reconstruct(&shares[1..]), Ok(Fp256::from(7u64)));
+        assert_eq!(
+            reconstruct_with_threshold(&shares, 2),
+            Ok(Fp256::from(7u64))
+        );
+        let mut bad = shares.clone();
+        bad[2].1 = Fp256::from(17u64);
+        assert_eq!(
+            reconstruct_with_threshold(&bad, 2),
+            Err(ShamirError::InconsistentShares)
+        );
+    }
+
+    #[test]
+    fn malformed_share_sets() {
+        let mut rng = rng_from_seed(35);
+        let secret = Fp480::from(99u64);
+        assert_eq!(
+            split(secret, 0, 3, &mut rng),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                shares: 3
+            })
+        );
+        assert_eq!(
+            split(secret, 4, 3, &mut rng),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 4,
+                shares: 3
+            })
+        );
+        let shares = split(secret, 3, 5, &mut rng).unwrap();
+        assert_eq!(
+            reconstruct_with_threshold(&shares[..2], 3),
+            Err(ShamirError::TooFewShares { needed: 3, got: 2 })
+        );
+        assert_eq!(
+            reconstruct::<Fp480>(&[]),
+            Err(ShamirError::TooFewShares { needed: 1, got: 0 })
+        );
+        let duplicated = vec![shares[0], shares[1], shares[0]];
+        assert_eq!(reconstruct(&duplicated), Err(ShamirError::DuplicateX));
+        let zero = vec![shares[0], shares[1], (Fp480::from(0u64), secret)];
+        assert_eq!(reconstruct(&zero), Err(ShamirError::ZeroX));
+    }
+
+    #[test]
+    fn share_encoding() {
+        let mut rng = rng_from_seed(36);
+        let shares = split(Fp256::from(5u64), 2, 3, &mut rng).unwrap();
+        let bytes = share_to_bytes(&shares[1]);
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(&bytes[..32], &Fp256::from(2u64).to_bytes_array()[..]);
+        assert_eq!(share_from_bytes(&bytes), Ok(shares[1]));
+        assert_eq!(
+            share_from_bytes::<Fp256>(&bytes[1..]),
+            Err(ShamirError::InvalidEncoding)
+        );
+        assert_eq!(
+            share_from_bytes::<Fp256>(&[0xff; 64]),
+            Err(ShamirError::InvalidEncoding)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn any_threshold_subset_reconstructs(
+            seed in any::<u64>(),
+            (t, subset) in (1usize..8).prop_flat_map(|t| (Just(t), subsequence((0..8).collect::<Vec<usize>>(), t..=8))),
+        ) {
+            let mut rng = rng_from_seed(seed);
+            let secret = Fp256::random(&mut rng);
+            let shares = split(secret, t, 8, &mut rng).unwrap();
+            let chosen: Vec<(Fp256, Fp256)> = subset.iter().map(|&i| shares[i]).collect();
+            prop_assert_eq!(reconstruct(&chosen), Ok(secret));
+            prop_assert_eq!(reconstruct_with_threshold(&chosen, t), Ok(secret));
+        }
+    }
+}
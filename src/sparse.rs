@@ -0,0 +1,412 @@
+//! Sparse matrices in compressed sparse row (CSR) form, and black-box linear algebra on them:
+//! Berlekamp-Massey, a Wiedemann solver for square non-singular systems and a Monte Carlo rank
+//! estimate. None of these ever form a dense matrix; they only need matrix-vector products.
+use field::{Field, PrimeField};
+use matrix::Matrix;
+use poly::Poly;
+use rand::Rng;
+
+/// How many random projections `solve` tries before giving up. Each one fails with probability
+/// around `n / p`, so more than one is only needed for tiny fields or singular matrices.
+const WIEDEMANN_ATTEMPTS: usize = 3;
+
+/// A `rows` x `cols` matrix in compressed sparse row form. The entries of row `i` are
+/// `values[row_ptr[i]..row_ptr[i + 1]]`, in the columns given by the same range of `col_idx`.
+/// Only non-zero entries are stored and within a row the columns are increasing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMatrix<F: Field> {
+    rows: usize,
+    cols: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    values: Vec<F>,
+}
+
+impl<F: Field> SparseMatrix<F> {
+    ///Build a matrix from `(row, col, value)` triplets in any order. Entries at the same position
+    ///are added together and zeros are dropped. Panics if a position is out of range.
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, F)]) -> Self {
+        let mut sorted = triplets.to_vec();
+        for &(i, j, _) in sorted.iter() {
+            assert!(i < rows && j < cols, "Matrix index out of range.");
+        }
+        sorted.sort_by_key(|&(i, j, _)| (i, j));
+        let mut row_ptr = vec![0; rows + 1];
+        let mut col_idx: Vec<usize> = Vec::with_capacity(sorted.len());
+        let mut values: Vec<F> = Vec::with_capacity(sorted.len());
+        let mut last = None;
+        for (i, j, v) in sorted {
+            if last == Some((i, j)) {
+                *values.last_mut().unwrap() += v;
+            } else {
+                // drop a zero left behind by the previous position before starting a new one
+                if values.last().is_some_and(|x| x.is_zero()) {
+                    values.pop();
+                    col_idx.pop();
+                    row_ptr[last.unwrap().0 + 1] -= 1;
+                }
+                col_idx.push(j);
+                values.push(v);
+                row_ptr[i + 1] += 1;
+                last = Some((i, j));
+            }
+        }
+        if values.last().is_some_and(|x| x.is_zero()) {
+            values.pop();
+            col_idx.pop();
+            row_ptr[last.unwrap().0 + 1] -= 1;
+        }
+        for i in 0..rows {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+        SparseMatrix {
+            rows,
+            cols,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    ///The sparse form of a dense matrix.
+    pub fn from_dense(m: &Matrix<F>) -> Self {
+        let mut triplets = Vec::new();
+        for i in 0..m.rows() {
+            for (j, v) in m.row(i).iter().enumerate() {
+                if !v.is_zero() {
+                    triplets.push((i, j, *v));
+                }
+            }
+        }
+        SparseMatrix::from_triplets(m.rows(), m.cols(), &triplets)
+    }
+
+    pub fn to_dense(&self) -> Matrix<F> {
+        let mut ret = Matrix::zero(self.rows, self.cols);
+        for i in 0..self.rows {
+            for (j, v) in self.row(i) {
+                ret[(i, j)] = v;
+            }
+        }
+        ret
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    ///Number of stored (non-zero) entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    ///The non-zero entries of row `i` as `(col, value)`.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, F)> + '_ {
+        let range = self.row_ptr[i]..self.row_ptr[i + 1];
+        self.col_idx[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.values[range].iter().cloned())
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut triplets = Vec::with_capacity(self.nnz());
+        for i in 0..self.rows {
+            for (j, v) in self.row(i) {
+                triplets.push((j, i, v));
+            }
+        }
+        SparseMatrix::from_triplets(self.cols, self.rows, &triplets)
+    }
+
+    ///The product `A v`. Panics if `v` doesn't have `cols` entries.
+    pub fn mul_vec(&self, v: &[F]) -> Vec<F> {
+        assert_eq!(v.len(), self.cols, "Vector has the wrong length.");
+        (0..self.rows)
+            .map(|i| self.row(i).fold(F::zero(), |acc, (j, a)| acc + a * v[j]))
+            .collect()
+    }
+
+    ///The product `A^T v`, without forming the transpose. Panics if `v` doesn't have `rows`
+    ///entries.
+    pub fn transpose_mul_vec(&self, v: &[F]) -> Vec<F> {
+        assert_eq!(v.len(), self.rows, "Vector has the wrong length.");
+        let mut ret = vec![F::zero(); self.cols];
+        for (i, x) in v.iter().enumerate() {
+            if x.is_zero() {
+                continue;
+            }
+            for (j, a) in self.row(i) {
+                ret[j] += a * *x;
+            }
+        }
+        ret
+    }
+}
+
+impl<F: PrimeField> SparseMatrix<F> {
+    ///Wiedemann's algorithm: solve `A x = b` for a square, non-singular `A` using only products
+    ///with `A`. Returns None if no solution was found, which happens when `A` is singular (unless
+    ///`b` happens to be reachable anyway) or, with negligible probability, when every random
+    ///projection was unlucky. A returned solution is always checked.
+    pub fn solve<R: Rng>(&self, b: &[F], rng: &mut R) -> Option<Vec<F>> {
+        assert_eq!(self.rows, self.cols, "Wiedemann needs a square matrix.");
+        assert_eq!(b.len(), self.rows, "Right hand side has the wrong length.");
+        let n = self.rows;
+        if b.iter().all(|x| x.is_zero()) {
+            return Some(vec![F::zero(); n]);
+        }
+        for _ in 0..WIEDEMANN_ATTEMPTS {
+            // The minimal polynomial of the sequence u^T A^i b divides that of A restricted to
+            // the Krylov space of b, and equals it for almost all u.
+            let minpoly = krylov_minimal_polynomial(n, |v| self.mul_vec(v), b, rng);
+            let m0 = minpoly.coeff(0);
+            if m0.is_zero() {
+                continue;
+            }
+            // m(A) b = 0, so b = -(1/m0) (m_1 b + m_2 A b + ...) = A x for
+            // x = -(1/m0) (m_1 b + m_2 A b + ... + m_d A^(d - 1) b), done by Horner.
+            let degree = minpoly.degree().unwrap_or(0);
+            let mut x = vec![F::zero(); n];
+            for i in (1..=degree).rev() {
+                x = self.mul_vec(&x);
+                let c = minpoly.coeff(i);
+                for (xi, bi) in x.iter_mut().zip(b) {
+                    *xi += c * *bi;
+                }
+            }
+            let scale = -m0.inv();
+            for xi in x.iter_mut() {
+                *xi *= scale;
+            }
+            if self.mul_vec(&x) == b {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    ///A Monte Carlo estimate of the rank. With random diagonal `D1`, `D2`, the minimal polynomial
+    ///of `B = D1 A^T D2 A D1` has degree `rank(A) + 1` (or `cols` if `A` has full column rank) with
+    ///high probability. The estimate can only be too low, and is wrong with probability around
+    ///`cols^2 / p`.
+    pub fn rank_estimate<R: Rng>(&self, rng: &mut R) -> usize {
+        let n = self.cols;
+        let random_diagonal = |rng: &mut R, len| -> Vec<F> {
+            (0..len)
+                .map(|_| loop {
+                    let x = F::random(rng);
+                    if !x.is_zero() {
+                        return x;
+                    }
+                })
+                .collect()
+        };
+        let d1 = random_diagonal(rng, n);
+        let d2 = random_diagonal(rng, self.rows);
+        let hadamard =
+            |a: &[F], b: &[F]| -> Vec<F> { a.iter().zip(b).map(|(x, y)| *x * *y).collect() };
+        let apply = |v: &[F]| {
+            let w = hadamard(&self.mul_vec(&hadamard(&d1, v)), &d2);
+            hadamard(&d1, &self.transpose_mul_vec(&w))
+        };
+        let start: Vec<F> = (0..n).map(|_| F::random(rng)).collect();
+        let minpoly = krylov_minimal_polynomial(n, apply, &start, rng);
+        let degree = minpoly.degree().unwrap_or(0);
+        if minpoly.coeff(0).is_zero() {
+            degree - 1
+        } else {
+            degree
+        }
+    }
+}
+
+///The minimal polynomial of the sequence `u^T A^i v` for a random `u`, which is (with high
+///probability) the minimal polynomial of `A` on the Krylov space of `v`.
+fn krylov_minimal_polynomial<F, M, R>(n: usize, apply: M, v: &[F], rng: &mut R) -> Poly<F>
+where
+    F: PrimeField,
+    M: Fn(&[F]) -> Vec<F>,
+    R: Rng,
+{
+    let u: Vec<F> = (0..n).map(|_| F::random(rng)).collect();
+    let mut sequence = Vec::with_capacity(2 * n);
+    let mut w = v.to_vec();
+    for i in 0..2 * n {
+        if i > 0 {
+            w = apply(&w);
+        }
+        sequence.push(
+            u.iter()
+                .zip(w.iter())
+                .fold(F::zero(), |acc, (a, b)| acc + *a * *b),
+        );
+    }
+    berlekamp_massey(&sequence)
+}
+
+///Berlekamp-Massey: the monic minimal polynomial `m(x) = x^L + c_{L-1} x^(L-1) + ... + c_0` of
+///the shortest linear recurrence `s[i + L] = -(c_{L-1} s[i + L - 1] + ... + c_0 s[i])` that
+///generates the sequence. A sequence of length `2L` determines a recurrence of order `L`.
+pub fn berlekamp_massey<F: PrimeField>(sequence: &[F]) -> Poly<F> {
+    // connection[i] is the coefficient of x^i in C(x) = 1 + c_1 x + ... + c_L x^L, where
+    // s[n] + c_1 s[n - 1] + ... + c_L s[n - L] = 0.
+    let mut connection = vec![F::one()];
+    let mut previous = vec![F::one()];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = F::one();
+    for n in 0..sequence.len() {
+        let discrepancy = (1..=length).fold(sequence[n], |acc, i| {
+            acc + connection.get(i).cloned().unwrap_or_else(F::zero) * sequence[n - i]
+        });
+        if discrepancy.is_zero() {
+            shift += 1;
+            continue;
+        }
+        let factor = discrepancy / previous_discrepancy;
+        let old = connection.clone();
+        if connection.len() < previous.len() + shift {
+            connection.resize(previous.len() + shift, F::zero());
+        }
+        for (i, b) in previous.iter().enumerate() {
+            connection[i + shift] -= factor * *b;
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = old;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    // The minimal polynomial is the reversal x^L C(1/x).
+    connection.resize(length + 1, F::zero());
+    connection.reverse();
+    Poly::new(connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use rand::StdRng;
+    use test_util::rng_from_seed;
+
+    fn f(x: i64) -> Fp256 {
+        Fp256::from(x)
+    }
+
+    ///A random n x n matrix with about `per_row` entries in each row, all small.
+    fn random_sparse(n: usize, per_row: usize, rng: &mut StdRng) -> SparseMatrix<Fp256> {
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            for _ in 0..per_row {
+                triplets.push((i, rng.gen_range(0, n), f(rng.gen_range(-5, 6))));
+            }
+        }
+        SparseMatrix::from_triplets(n, n, &triplets)
+    }
+
+    #[test]
+    fn triplets_are_combined() {
+        let m = SparseMatrix::from_triplets(
+            2,
+            3,
+            &[
+                (1, 2, f(4)),
+                (0, 1, f(2)),
+                (1, 2, f(1)),
+                (0, 0, f(3)),
+                (0, 0, f(-3)),
+            ],
+        );
+        assert_eq!(m.nnz(), 2);
+        assert_eq!(m.row(0).collect::<Vec<_>>(), vec![(1, f(2))]);
+        assert_eq!(m.row(1).collect::<Vec<_>>(), vec![(2, f(5))]);
+        assert_eq!(
+            m.to_dense(),
+            Matrix::new(2, 3, vec![f(0), f(2), f(0), f(0), f(0), f(5)])
+        );
+        assert_eq!(m.transpose().to_dense(), m.to_dense().transpose());
+        assert_eq!(SparseMatrix::from_dense(&m.to_dense()), m);
+    }
+
+    #[test]
+    fn berlekamp_massey_fibonacci() {
+        // s[i + 2] = s[i + 1] + s[i] has minimal polynomial x^2 - x - 1
+        let fib: Vec<Fp256> = [0, 1, 1, 2, 3, 5, 8, 13].iter().map(|x| f(*x)).collect();
+        assert_eq!(berlekamp_massey(&fib), Poly::new(vec![f(-1), f(-1), f(1)]));
+        // the all zero sequence satisfies the empty recurrence
+        assert_eq!(berlekamp_massey(&[f(0), f(0)]), Poly::one());
+        // 1, 0, 0, ... is generated by s[i + 1] = 0
+        assert_eq!(berlekamp_massey(&[f(1), f(0), f(0), f(0)]), Poly::x());
+    }
+
+    #[test]
+    fn wiedemann_solves_larger_system() {
+        let mut rng = rng_from_seed(33);
+        let n = 150;
+        let mut a = random_sparse(n, 4, &mut rng);
+        // make it diagonally heavy so it is non-singular
+        let mut triplets = Vec::new();
+        for i in 0..n {
+            for (j, v) in a.row(i) {
+                triplets.push((i, j, v));
+            }
+            triplets.push((i, i, f(1000)));
+        }
+        a = SparseMatrix::from_triplets(n, n, &triplets);
+        let b: Vec<Fp256> = (0..n as i64).map(f).collect();
+        let x = a.solve(&b, &mut rng).unwrap();
+        assert_eq!(a.mul_vec(&x), b);
+    }
+
+    #[test]
+    fn singular_system_has_no_wiedemann_solution() {
+        let mut rng = rng_from_seed(34);
+        let a = SparseMatrix::from_triplets(3, 3, &[(0, 0, f(1)), (1, 1, f(1)), (2, 0, f(1))]);
+        assert_eq!(a.solve(&[f(1), f(2), f(3)], &mut rng), None);
+        assert_eq!(a.rank_estimate(&mut rng), 2);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn products_match_dense(seed in any::<u64>(), ref v in vec(any::<i64>(), 12)) {
+            let mut rng = rng_from_seed(seed);
+            let a = random_sparse(12, 3, &mut rng);
+            let v: Vec<Fp256> = v.iter().map(|x| f(*x)).collect();
+            prop_assert_eq!(a.mul_vec(&v), a.to_dense().mul_vec(&v));
+            prop_assert_eq!(a.transpose_mul_vec(&v), a.transpose().mul_vec(&v));
+        }
+
+        #[test]
+        fn rank_estimate_matches_dense(seed in any::<u64>(), per_row in 0usize..3) {
+            let mut rng = rng_from_seed(seed);
+            let a = random_sparse(10, per_row, &mut rng);
+            prop_assert_eq!(a.rank_estimate(&mut rng), a.to_dense().rank());
+        }
+
+        #[test]
+        fn wiedemann_matches_dense(seed in any::<u64>(), ref b in vec(any::<i64>(), 8)) {
+            let mut rng = rng_from_seed(seed);
+            let a = random_sparse(8, 3, &mut rng);
+            let b: Vec<Fp256> = b.iter().map(|x| f(*x)).collect();
+            match a.to_dense().inverse() {
+                Some(inverse) => prop_assert_eq!(a.solve(&b, &mut rng), Some(inverse.mul_vec(&b))),
+                None => if let Some(x) = a.solve(&b, &mut rng) {
+                    prop_assert_eq!(a.mul_vec(&x), b);
+                },
+            }
+        }
+    }
+}
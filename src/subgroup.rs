@@ -0,0 +1,179 @@
+//! Prime order subgroups of the multiplicative group of a prime field. The parameters pair an
+//! `fp!` type for the group elements (mod p) with an `fp!` type for the exponents (mod q, where
+//! q | p - 1 is the order of the subgroup), plus a generator.
+use field::{Field, PrimeField};
+use fp_256::Fp256;
+use fp_safe_256::FpSafe256;
+use fp_safe_256_q::FpSafe256Q;
+use fp_schnorr_512::FpSchnorr512;
+use num_traits::{One, Zero};
+
+pub trait SubgroupParams {
+    ///Field whose multiplicative group contains the subgroup.
+    type Element: PrimeField;
+    ///Field of exponents, whose prime is the order q of the subgroup.
+    type Scalar: PrimeField;
+
+    ///A generator of the subgroup of order q.
+    fn generator() -> Self::Element;
+
+    ///`base^exponent`. This branches on the bits of the exponent, so use `exp_secret` when the
+    ///exponent is a key or a nonce.
+    fn exp(base: &Self::Element, exponent: &Self::Scalar) -> Self::Element {
+        base.pow_limbs(&exponent.to_limbs())
+    }
+
+    ///`base^exponent` with `PrimeField::pow_constant_time`, whose operations don't depend on the
+    ///exponent. Slower than `exp`.
+    fn exp_secret(base: &Self::Element, exponent: &Self::Scalar) -> Self::Element {
+        base.pow_constant_time(&exponent.to_limbs(), Self::Scalar::PRIMEBITS)
+    }
+
+    ///The product of `base^exponent` over `terms`, sharing the squarings with
+    ///`Field::multi_pow`.
+    fn multi_exp(terms: &[(Self::Element, Self::Scalar)]) -> Self::Element {
+        let limbs: Vec<Vec<u64>> = terms.iter().map(|t| t.1.to_limbs()).collect();
+        let terms: Vec<(Self::Element, &[u64])> = terms
+            .iter()
+            .zip(&limbs)
+            .map(|(t, e)| (t.0, &e[..]))
+            .collect();
+        Self::Element::multi_pow(&terms)
+    }
+
+    ///`g^exponent` for the generator g.
+    fn exp_generator(exponent: &Self::Scalar) -> Self::Element {
+        Self::exp(&Self::generator(), exponent)
+    }
+
+    ///True if `x` is in the subgroup, which is when `x^q = 1`.
+    fn is_member(x: &Self::Element) -> bool {
+        !x.is_zero() && x.pow_limbs(Self::Scalar::PRIME).is_one()
+    }
+
+    ///Check the generator is a member of the subgroup other than 1. Since q is prime that makes
+    ///its order exactly q, which also shows that q divides p - 1.
+    fn check_params() -> bool {
+        let g = Self::generator();
+        !g.is_one() && Self::is_member(&g)
+    }
+}
+
+/// The subgroup of squares mod the safe prime `p = 2^255 + 196479`, which has prime order
+/// `q = (p - 1) / 2`, generated by 4. It's sized for tests and isn't secure: discrete logs mod a
+/// 256 bit prime are practical to compute. Use the `ffdhe` groups for real keys.
+#[derive(Clone, Copy, Debug)]
+pub struct SafePrime256;
+
+impl SubgroupParams for SafePrime256 {
+    type Element = FpSafe256;
+    type Scalar = FpSafe256Q;
+
+    fn generator() -> FpSafe256 {
+        FpSafe256::from(4u64)
+    }
+}
+
+/// A Schnorr group: the subgroup of order q, the 256 bit prime of `Fp256`, mod the 512 bit prime
+/// `p = q (2^256 + 546) + 1`. The generator is `2^((p - 1) / q)`.
+#[derive(Clone, Copy, Debug)]
+pub struct Schnorr512;
+
+impl SubgroupParams for Schnorr512 {
+    type Element = FpSchnorr512;
+    type Scalar = Fp256;
+
+    fn generator() -> FpSchnorr512 {
+        FpSchnorr512::new([
+            0xc6249c63a00197d6,
+            0x61a9aef10c501f87,
+            0x9fb41a9de80ae0d6,
+            0x6944544cce48e81a,
+            0x4a5d3a613f2dbe0a,
+            0x670c70fb4ec49682,
+            0x2056727fb9692a05,
+            0x3f6ed444c6e7f4dd,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use test_util::rng_from_seed;
+
+    /// Deliberately broken parameters: 5 is not a square mod the safe prime.
+    struct NotASubgroup;
+
+    impl SubgroupParams for NotASubgroup {
+        type Element = FpSafe256;
+        type Scalar = FpSafe256Q;
+
+        fn generator() -> FpSafe256 {
+            FpSafe256::from(5u64)
+        }
+    }
+
+    /// The exponent field doesn't match the group at all.
+    struct Mismatched;
+
+    impl SubgroupParams for Mismatched {
+        type Element = Fp256;
+        type Scalar = FpSafe256Q;
+
+        fn generator() -> Fp256 {
+            Fp256::from(4u64)
+        }
+    }
+
+    #[test]
+    fn params_are_checked() {
+        assert!(SafePrime256::check_params());
+        assert!(!NotASubgroup::check_params());
+        assert!(!Mismatched::check_params());
+        assert!(!SafePrime256::is_member(&FpSafe256::from(0u64)));
+        assert!(!SafePrime256::is_member(&-FpSafe256::from(1u64)));
+    }
+
+    #[test]
+    fn schnorr_512_generator() {
+        let cofactor = [546, 0, 0, 0, 1];
+        let two = FpSchnorr512::from(2u64);
+        assert_eq!(Schnorr512::generator(), two.pow_limbs(&cofactor));
+        assert!(Schnorr512::check_params());
+        assert!(!Schnorr512::is_member(&two));
+        assert!(Schnorr512::is_member(
+            &FpSchnorr512::from(3u64).pow_limbs(&cofactor)
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn exponents_add(seed in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            let a = FpSafe256Q::random(&mut rng);
+            let b = FpSafe256Q::random(&mut rng);
+            let ga = SafePrime256::exp_generator(&a);
+            prop_assert!(SafePrime256::is_member(&ga));
+            prop_assert_eq!(ga * SafePrime256::exp_generator(&b), SafePrime256::exp_generator(&(a + b)));
+            prop_assert_eq!(SafePrime256::exp(&ga, &b), SafePrime256::exp_generator(&(a * b)));
+            let h = SafePrime256::exp(&ga, &a);
+            prop_assert_eq!(
+                SafePrime256::multi_exp(&[(ga, b), (h, a)]),
+                SafePrime256::exp_generator(&(a * b + a * a * a))
+            );
+        }
+
+        #[test]
+        fn exp_secret_matches_exp(seed in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            let base = SafePrime256::exp_generator(&FpSafe256Q::random(&mut rng));
+            let e = FpSafe256Q::random(&mut rng);
+            prop_assert_eq!(SafePrime256::exp_secret(&base, &e), SafePrime256::exp(&base, &e));
+            let e = Fp256::random(&mut rng);
+            let g = Schnorr512::generator();
+            prop_assert_eq!(Schnorr512::exp_secret(&g, &e), Schnorr512::exp(&g, &e));
+        }
+    }
+}
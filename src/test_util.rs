@@ -0,0 +1,29 @@
+//! Fixtures shared by the tests of several modules.
+use field::PrimeField;
+use rand::{SeedableRng, StdRng};
+
+///A generator seeded from `seed`, so proptests can draw random field elements from a `u64` and
+///shrink and replay them like any other input.
+pub fn rng_from_seed(seed: u64) -> StdRng {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    StdRng::from_seed(bytes)
+}
+
+///The bytes of a hex string, two digits each.
+pub fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+///Lowercase hex of `bytes`, the inverse of `from_hex`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+///A field element from big-endian hex. Panics unless it's less than the prime.
+pub fn field_from_hex<F: PrimeField>(hex: &str) -> F {
+    F::from_bytes(&from_hex(hex)).unwrap()
+}
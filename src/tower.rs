@@ -0,0 +1,394 @@
+//! The degree 12 extension tower used by pairings, built over one of the `fp!` fields:
+//!
+//! * `Fp2 = Fp[u] / (u^2 - β)`
+//! * `Fp6 = Fp2[v] / (v^3 - ξ)`
+//! * `Fp12 = Fp6[w] / (w^2 - v)`
+//!
+//! Unlike `ExtField`, whose modulus is chosen at runtime, the non-residues come from a
+//! `TowerParams` type, so every level is a `Field` and the generic code in the crate (curves,
+//! polynomials, ...) works over it.
+use field::{Field, PrimeField};
+use num_traits::{Inv, One, Zero};
+use rand::Rng;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The non-residues which define the tower.
+pub trait TowerParams: Copy + Debug + Eq {
+    type Base: PrimeField;
+    ///β, a non-square in the base field, with `u^2 = β`.
+    fn beta() -> Self::Base;
+    ///ξ, neither a square nor a cube in `Fp2`, with `v^3 = ξ` and so `w^6 = ξ`. The prime must
+    ///be 1 mod 6.
+    fn xi() -> Fp2<Self>;
+}
+
+/// `c0 + c1 u`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp2<P: TowerParams> {
+    pub c0: P::Base,
+    pub c1: P::Base,
+}
+
+/// `c0 + c1 v + c2 v^2`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp6<P: TowerParams> {
+    pub c0: Fp2<P>,
+    pub c1: Fp2<P>,
+    pub c2: Fp2<P>,
+}
+
+/// `c0 + c1 w`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fp12<P: TowerParams> {
+    pub c0: Fp6<P>,
+    pub c1: Fp6<P>,
+}
+
+impl<P: TowerParams> Fp2<P> {
+    pub fn new(c0: P::Base, c1: P::Base) -> Fp2<P> {
+        Fp2 { c0, c1 }
+    }
+
+    ///The base field element `c` as `c + 0u`.
+    pub fn from_base(c: P::Base) -> Fp2<P> {
+        Fp2::new(c, P::Base::zero())
+    }
+
+    ///`c0 - c1 u`, which is also the Frobenius map `x -> x^p`.
+    pub fn conjugate(&self) -> Fp2<P> {
+        Fp2::new(self.c0, -self.c1)
+    }
+
+    ///Multiply by an element of the base field.
+    pub fn scale(&self, c: P::Base) -> Fp2<P> {
+        Fp2::new(self.c0 * c, self.c1 * c)
+    }
+
+    ///Multiply by ξ.
+    pub fn mul_by_xi(&self) -> Fp2<P> {
+        *self * P::xi()
+    }
+}
+
+impl<P: TowerParams> Fp6<P> {
+    pub fn new(c0: Fp2<P>, c1: Fp2<P>, c2: Fp2<P>) -> Fp6<P> {
+        Fp6 { c0, c1, c2 }
+    }
+
+    pub fn from_fp2(c: Fp2<P>) -> Fp6<P> {
+        Fp6::new(c, Fp2::zero(), Fp2::zero())
+    }
+
+    ///Multiply by v, which shifts the coefficients up and wraps `v^3` around to ξ.
+    pub fn mul_by_v(&self) -> Fp6<P> {
+        Fp6::new(self.c2.mul_by_xi(), self.c0, self.c1)
+    }
+}
+
+impl<P: TowerParams> Fp12<P> {
+    pub fn new(c0: Fp6<P>, c1: Fp6<P>) -> Fp12<P> {
+        Fp12 { c0, c1 }
+    }
+
+    pub fn from_base(c: P::Base) -> Fp12<P> {
+        Fp12::from_fp2(Fp2::from_base(c))
+    }
+
+    pub fn from_fp2(c: Fp2<P>) -> Fp12<P> {
+        Fp12::new(Fp6::from_fp2(c), Fp6::zero())
+    }
+
+    ///The element `sum(c[k] w^k)`. Since `w^2 = v`, this is the same tower viewed as
+    ///`Fp2[w] / (w^6 - ξ)`, which is the easier form for Frobenius maps and twists.
+    pub fn from_w_coefficients(c: [Fp2<P>; 6]) -> Fp12<P> {
+        Fp12::new(Fp6::new(c[0], c[2], c[4]), Fp6::new(c[1], c[3], c[5]))
+    }
+
+    ///The inverse of `from_w_coefficients`.
+    pub fn w_coefficients(&self) -> [Fp2<P>; 6] {
+        [
+            self.c0.c0, self.c1.c0, self.c0.c1, self.c1.c1, self.c0.c2, self.c1.c2,
+        ]
+    }
+
+    ///`c0 - c1 w`, which is `x -> x^(p^6)`. For elements of norm one, such as pairing values
+    ///after the easy part of the final exponentiation, it's also the inverse.
+    pub fn conjugate(&self) -> Fp12<P> {
+        Fp12::new(self.c0, -self.c1)
+    }
+
+    ///`x -> x^(p^power)`.
+    pub fn frobenius_map(&self, power: usize) -> Fp12<P> {
+        let gamma = frobenius_coefficients::<P>();
+        let mut c = self.w_coefficients();
+        for _ in 0..power {
+            for (ck, gk) in c.iter_mut().zip(gamma.iter()) {
+                *ck = ck.conjugate() * *gk;
+            }
+        }
+        Fp12::from_w_coefficients(c)
+    }
+}
+
+///`ξ^(k (p - 1) / 6)` for k in 0..6. Since `w^6 = ξ`, `(a w^k)^p = a^p w^k ξ^(k (p - 1) / 6)`.
+pub fn frobenius_coefficients<P: TowerParams>() -> [Fp2<P>; 6] {
+    let gamma = P::xi().pow_limbs(&div_small(P::Base::PRIME, 6));
+    let mut ret = [Fp2::one(); 6];
+    for k in 1..6 {
+        ret[k] = ret[k - 1] * gamma;
+    }
+    ret
+}
+
+///`floor(n / d)` for `n` in limbs, least significant first.
+fn div_small(n: &[u64], d: u64) -> Vec<u64> {
+    let mut ret = vec![0u64; n.len()];
+    let mut rem = 0u128;
+    for i in (0..n.len()).rev() {
+        let cur = (rem << 64) | u128::from(n[i]);
+        ret[i] = (cur / u128::from(d)) as u64;
+        rem = cur % u128::from(d);
+    }
+    ret
+}
+
+impl<P: TowerParams> Mul for Fp2<P> {
+    type Output = Fp2<P>;
+    fn mul(self, rhs: Fp2<P>) -> Fp2<P> {
+        // Karatsuba: three base multiplications instead of four.
+        let aa = self.c0 * rhs.c0;
+        let bb = self.c1 * rhs.c1;
+        let cross = (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - aa - bb;
+        Fp2::new(aa + P::beta() * bb, cross)
+    }
+}
+
+impl<P: TowerParams> Inv for Fp2<P> {
+    type Output = Fp2<P>;
+    fn inv(self) -> Fp2<P> {
+        // 1 / (a + bu) = (a - bu) / (a^2 - β b^2), and the norm is in the base field.
+        let norm = self.c0.square() - P::beta() * self.c1.square();
+        self.conjugate().scale(norm.inv())
+    }
+}
+
+impl<P: TowerParams> Field for Fp2<P> {
+    fn square(&self) -> Fp2<P> {
+        *self * *self
+    }
+
+    fn random<R: Rng>(rng: &mut R) -> Fp2<P> {
+        Fp2::new(P::Base::random(rng), P::Base::random(rng))
+    }
+}
+
+impl<P: TowerParams> Mul for Fp6<P> {
+    type Output = Fp6<P>;
+    fn mul(self, rhs: Fp6<P>) -> Fp6<P> {
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let (b0, b1, b2) = (rhs.c0, rhs.c1, rhs.c2);
+        Fp6::new(
+            a0 * b0 + (a1 * b2 + a2 * b1).mul_by_xi(),
+            a0 * b1 + a1 * b0 + (a2 * b2).mul_by_xi(),
+            a0 * b2 + a1 * b1 + a2 * b0,
+        )
+    }
+}
+
+impl<P: TowerParams> Inv for Fp6<P> {
+    type Output = Fp6<P>;
+    fn inv(self) -> Fp6<P> {
+        // The adjugate of multiplication by self, divided by its determinant.
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let t0 = a0.square() - (a1 * a2).mul_by_xi();
+        let t1 = a2.square().mul_by_xi() - a0 * a1;
+        let t2 = a1.square() - a0 * a2;
+        let det = a0 * t0 + (a2 * t1 + a1 * t2).mul_by_xi();
+        let inv = det.inv();
+        Fp6::new(t0 * inv, t1 * inv, t2 * inv)
+    }
+}
+
+impl<P: TowerParams> Field for Fp6<P> {
+    fn square(&self) -> Fp6<P> {
+        *self * *self
+    }
+
+    fn random<R: Rng>(rng: &mut R) -> Fp6<P> {
+        Fp6::new(Fp2::random(rng), Fp2::random(rng), Fp2::random(rng))
+    }
+}
+
+impl<P: TowerParams> Mul for Fp12<P> {
+    type Output = Fp12<P>;
+    fn mul(self, rhs: Fp12<P>) -> Fp12<P> {
+        let aa = self.c0 * rhs.c0;
+        let bb = self.c1 * rhs.c1;
+        let cross = (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - aa - bb;
+        Fp12::new(aa + bb.mul_by_v(), cross)
+    }
+}
+
+impl<P: TowerParams> Inv for Fp12<P> {
+    type Output = Fp12<P>;
+    fn inv(self) -> Fp12<P> {
+        // 1 / (a + bw) = (a - bw) / (a^2 - v b^2)
+        let norm = self.c0.square() - self.c1.square().mul_by_v();
+        let inv = norm.inv();
+        Fp12::new(self.c0 * inv, -self.c1 * inv)
+    }
+}
+
+impl<P: TowerParams> Field for Fp12<P> {
+    fn square(&self) -> Fp12<P> {
+        *self * *self
+    }
+
+    fn random<R: Rng>(rng: &mut R) -> Fp12<P> {
+        Fp12::new(Fp6::random(rng), Fp6::random(rng))
+    }
+}
+
+/// The operators which work coefficient by coefficient, or follow from `Mul` and `Inv`.
+macro_rules! tower_ops { ($($t:ident { $first:ident $(, $rest:ident)* });*) => { $(
+    impl<P: TowerParams> Zero for $t<P> {
+        fn zero() -> $t<P> {
+            $t { $first: Zero::zero() $(, $rest: Zero::zero())* }
+        }
+
+        fn is_zero(&self) -> bool {
+            self.$first.is_zero() $(&& self.$rest.is_zero())*
+        }
+    }
+
+    impl<P: TowerParams> One for $t<P> {
+        fn one() -> $t<P> {
+            $t { $first: One::one() $(, $rest: Zero::zero())* }
+        }
+    }
+
+    impl<P: TowerParams> Add for $t<P> {
+        type Output = $t<P>;
+        fn add(self, rhs: $t<P>) -> $t<P> {
+            $t { $first: self.$first + rhs.$first $(, $rest: self.$rest + rhs.$rest)* }
+        }
+    }
+
+    impl<P: TowerParams> Sub for $t<P> {
+        type Output = $t<P>;
+        fn sub(self, rhs: $t<P>) -> $t<P> {
+            $t { $first: self.$first - rhs.$first $(, $rest: self.$rest - rhs.$rest)* }
+        }
+    }
+
+    impl<P: TowerParams> Neg for $t<P> {
+        type Output = $t<P>;
+        fn neg(self) -> $t<P> {
+            $t { $first: -self.$first $(, $rest: -self.$rest)* }
+        }
+    }
+
+    impl<P: TowerParams> Div for $t<P> {
+        type Output = $t<P>;
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        fn div(self, rhs: $t<P>) -> $t<P> {
+            self * rhs.inv()
+        }
+    }
+
+    impl<P: TowerParams> AddAssign for $t<P> {
+        fn add_assign(&mut self, rhs: $t<P>) {
+            *self = *self + rhs;
+        }
+    }
+
+    impl<P: TowerParams> SubAssign for $t<P> {
+        fn sub_assign(&mut self, rhs: $t<P>) {
+            *self = *self - rhs;
+        }
+    }
+
+    impl<P: TowerParams> MulAssign for $t<P> {
+        fn mul_assign(&mut self, rhs: $t<P>) {
+            *self = *self * rhs;
+        }
+    }
+)* }}
+
+tower_ops! { Fp2 { c0, c1 }; Fp6 { c0, c1, c2 }; Fp12 { c0, c1 } }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pairing::{Bls12_381, Bn254};
+    use proptest::prelude::*;
+    use rand::StdRng;
+    use test_util::rng_from_seed;
+
+    fn check_field<F: Field>(rng: &mut StdRng) {
+        let (a, b, c) = (F::random(rng), F::random(rng), F::random(rng));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * b, b * a);
+        assert_eq!(a.square(), a * a);
+        assert_eq!(a + -a, F::zero());
+        assert_eq!(a - b, -(b - a));
+        assert_eq!(a * F::one(), a);
+        assert_eq!(a * a.inv(), F::one());
+        assert_eq!((a / b) * b, a);
+    }
+
+    fn check_frobenius<P: TowerParams>(rng: &mut StdRng) {
+        let a = Fp12::<P>::random(rng);
+        let b = Fp12::<P>::random(rng);
+        assert_eq!(a.frobenius_map(1), a.pow_limbs(P::Base::PRIME));
+        assert_eq!(
+            (a * b).frobenius_map(2),
+            a.frobenius_map(2) * b.frobenius_map(2)
+        );
+        assert_eq!(a.frobenius_map(6), a.conjugate());
+        assert_eq!(a.frobenius_map(12), a);
+        assert_eq!(Fp12::<P>::from_w_coefficients(a.w_coefficients()), a);
+    }
+
+    #[test]
+    fn nonresidues_are_nonresidues() {
+        // s = ξ^((p^2 - 1) / 6) = γ^(p + 1), and ξ is a square exactly when s^3 = 1 and a
+        // cube exactly when s^2 = 1.
+        fn check<P: TowerParams>() {
+            assert!(P::beta().sqrt().is_none());
+            let gamma = frobenius_coefficients::<P>()[1];
+            let s = gamma.conjugate() * gamma;
+            assert_ne!(s.square(), Fp2::one());
+            assert_ne!(s.square() * s, Fp2::one());
+        }
+        check::<Bn254>();
+        check::<Bls12_381>();
+    }
+
+    proptest! {
+        #[test]
+        fn bn254_tower_is_a_field(seed in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            check_field::<Fp2<Bn254>>(&mut rng);
+            check_field::<Fp6<Bn254>>(&mut rng);
+            check_field::<Fp12<Bn254>>(&mut rng);
+        }
+
+        #[test]
+        fn bls12_381_tower_is_a_field(seed in any::<u64>()) {
+            let mut rng = rng_from_seed(seed);
+            check_field::<Fp2<Bls12_381>>(&mut rng);
+            check_field::<Fp6<Bls12_381>>(&mut rng);
+            check_field::<Fp12<Bls12_381>>(&mut rng);
+        }
+    }
+
+    #[test]
+    fn frobenius_is_the_pth_power() {
+        let mut rng = rng_from_seed(41);
+        check_frobenius::<Bn254>(&mut rng);
+        check_frobenius::<Bls12_381>(&mut rng);
+    }
+}
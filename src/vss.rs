@@ -0,0 +1,271 @@
+//! Verifiable secret sharing on top of `shamir`. The dealer publishes commitments to the
+//! coefficients of the sharing polynomial in a prime order subgroup, and each participant checks
+//! their share against them without learning anything else.
+//!
+//! Feldman commitments `g^(a_i)` are simple but reveal `g^secret`. Pedersen commitments
+//! `g^(a_i) h^(b_i)` add a second, random polynomial and hide the secret completely, as long as
+//! nobody knows the discrete log of `h` to the base `g`.
+use field::Field;
+use num_traits::One;
+use rand::Rng;
+use shamir::{check_threshold, random_polynomial, shares_of, ShamirError};
+use subgroup::{SafePrime256, Schnorr512, SubgroupParams};
+
+/// A subgroup with a second generator `h` for Pedersen commitments. The commitments only bind
+/// while nobody knows the discrete log of `h` to the base of the first generator, so `h` comes
+/// from hashing into the group rather than from anyone's choice: it's `hash_to_field` with SHA-256
+/// of the message `h` under the tag `SECOND_GENERATOR_DST`, raised to the power `(p - 1) / q` to
+/// land in the subgroup.
+pub trait PedersenParams: SubgroupParams {
+    /// The domain separation tag `second_generator` was hashed with.
+    const SECOND_GENERATOR_DST: &'static [u8];
+
+    fn second_generator() -> Self::Element;
+}
+
+impl PedersenParams for SafePrime256 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-SafePrime256";
+
+    ///The hashed value squared, since p is a safe prime. Like `SafePrime256` itself this is only
+    ///for tests: discrete logs mod a 256 bit prime are within reach, and then so is this one.
+    fn second_generator() -> Self::Element {
+        Self::Element::new([
+            0x9e719aace23ba333,
+            0xa1b1ed2d79251562,
+            0xfc0b8d5a1a00a25b,
+            0x0c95d6a52a3a83ac,
+        ])
+    }
+}
+
+impl PedersenParams for Schnorr512 {
+    const SECOND_GENERATOR_DST: &'static [u8] = b"GRIDIRON-V01-PEDERSEN-Schnorr512";
+
+    ///The hashed value to the power `2^256 + 546`.
+    fn second_generator() -> Self::Element {
+        Self::Element::new([
+            0x9498bee2fc7d37c1,
+            0xc4022c31a7083e5a,
+            0x17c7988561eac492,
+            0xdbaf088b073cb241,
+            0x34aa1ad179e91d7b,
+            0x39c19ffc80a91a92,
+            0x3680ac387dc6c6c2,
+            0x35b4bef65c3d9803,
+        ])
+    }
+}
+
+///The shares from `feldman_split` and the dealer's published commitments, one per coefficient of
+///the sharing polynomial.
+pub type FeldmanSharing<P> = (
+    Vec<(<P as SubgroupParams>::Scalar, <P as SubgroupParams>::Scalar)>,
+    Vec<<P as SubgroupParams>::Element>,
+);
+
+///The shares from `pedersen_split` and the dealer's published commitments.
+pub type PedersenSharing<P> = (
+    Vec<PedersenShare<<P as SubgroupParams>::Scalar>>,
+    Vec<<P as SubgroupParams>::Element>,
+);
+
+/// A Pedersen share: the point `(x, y)` on the secret polynomial and the value of the blinding
+/// polynomial at the same `x`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenShare<S> {
+    pub x: S,
+    pub y: S,
+    pub blinding: S,
+}
+
+///Split `secret` into `n` Shamir shares with threshold `threshold`, returning the shares and the
+///Feldman commitments `g^(a_0), ..., g^(a_(threshold - 1))` to the polynomial's coefficients.
+pub fn feldman_split<P: SubgroupParams, R: Rng>(
+    secret: P::Scalar,
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<FeldmanSharing<P>, ShamirError> {
+    check_threshold(threshold, n)?;
+    let f = random_polynomial(secret, threshold, rng);
+    let commitments = (0..threshold)
+        .map(|i| P::exp_secret(&P::generator(), &f.coeff(i)))
+        .collect();
+    Ok((shares_of(&f, n), commitments))
+}
+
+///Check a share against Feldman commitments: `g^y = prod C_i^(x^i)`.
+pub fn feldman_verify<P: SubgroupParams>(
+    share: &(P::Scalar, P::Scalar),
+    commitments: &[P::Element],
+) -> bool {
+    let (x, y) = *share;
+    commitments.iter().all(P::is_member)
+        && evaluate_commitments::<P>(commitments, &x) == P::exp_secret(&P::generator(), &y)
+}
+
+///Split `secret` like `feldman_split`, but with Pedersen commitments `g^(a_i) h^(b_i)` where the
+///`b_i` are the coefficients of a random blinding polynomial. Both exponents are secret, so each
+///commitment takes two `exp_secret`s rather than one `multi_exp`.
+pub fn pedersen_split<P: PedersenParams, R: Rng>(
+    secret: P::Scalar,
+    threshold: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<PedersenSharing<P>, ShamirError> {
+    check_threshold(threshold, n)?;
+    let f = random_polynomial(secret, threshold, rng);
+    let blinding_secret = P::Scalar::random(rng);
+    let blinding = random_polynomial(blinding_secret, threshold, rng);
+    let (g, h) = (P::generator(), P::second_generator());
+    let commitments = (0..threshold)
+        .map(|i| P::exp_secret(&g, &f.coeff(i)) * P::exp_secret(&h, &blinding.coeff(i)))
+        .collect();
+    let shares = shares_of(&f, n)
+        .into_iter()
+        .map(|(x, y)| PedersenShare {
+            x,
+            y,
+            blinding: blinding.evaluate(x),
+        })
+        .collect();
+    Ok((shares, commitments))
+}
+
+///Check a share against Pedersen commitments: `g^y h^blinding = prod C_i^(x^i)`.
+pub fn pedersen_verify<P: PedersenParams>(
+    share: &PedersenShare<P::Scalar>,
+    commitments: &[P::Element],
+) -> bool {
+    let expected = P::multi_exp(&[
+        (P::generator(), share.y),
+        (P::second_generator(), share.blinding),
+    ]);
+    commitments.iter().all(P::is_member)
+        && evaluate_commitments::<P>(commitments, &share.x) == expected
+}
+
+///`prod C_i^(x^i)`, the commitment to the polynomial's value at `x`, by Horner's rule in the
+///exponent.
+fn evaluate_commitments<P: SubgroupParams>(
+    commitments: &[P::Element],
+    x: &P::Scalar,
+) -> P::Element {
+    commitments
+        .iter()
+        .rev()
+        .fold(P::Element::one(), |acc, c| P::exp(&acc, x) * *c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use fp_safe_256::FpSafe256;
+    use fp_safe_256_q::FpSafe256Q;
+    use fp_schnorr_512::FpSchnorr512;
+    use hash_to_field::hash_to_field;
+    use proptest::prelude::*;
+    use sha2::Sha256;
+    use shamir::reconstruct;
+    use test_util::rng_from_seed;
+
+    #[test]
+    fn second_generator_is_a_member() {
+        let h = SafePrime256::second_generator();
+        assert!(SafePrime256::is_member(&h));
+        assert_ne!(h, SafePrime256::generator());
+    }
+
+    #[test]
+    fn second_generator_is_hashed() {
+        let dst = SafePrime256::SECOND_GENERATOR_DST;
+        let u: FpSafe256 = hash_to_field::<_, Sha256>(b"h", dst, 1)[0];
+        assert_eq!(SafePrime256::second_generator(), u.square());
+    }
+
+    #[test]
+    fn schnorr_512_second_generator_is_hashed() {
+        let dst = Schnorr512::SECOND_GENERATOR_DST;
+        let u: FpSchnorr512 = hash_to_field::<_, Sha256>(b"h", dst, 1)[0];
+        let h = Schnorr512::second_generator();
+        assert_eq!(h, u.pow_limbs(&[546, 0, 0, 0, 1]));
+        assert!(Schnorr512::is_member(&h));
+        assert_ne!(h, Schnorr512::generator());
+    }
+
+    #[test]
+    fn invalid_threshold_is_an_error() {
+        let mut rng = rng_from_seed(36);
+        let secret = FpSafe256Q::from(1u64);
+        assert_eq!(
+            feldman_split::<SafePrime256, _>(secret, 3, 2, &mut rng).map(|_| ()),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 3,
+                shares: 2
+            })
+        );
+        assert_eq!(
+            pedersen_split::<SafePrime256, _>(secret, 0, 2, &mut rng).map(|_| ()),
+            Err(ShamirError::InvalidThreshold {
+                threshold: 0,
+                shares: 2
+            })
+        );
+    }
+
+    #[test]
+    fn non_member_commitments_are_rejected() {
+        let mut rng = rng_from_seed(37);
+        let (shares, mut commitments) =
+            feldman_split::<SafePrime256, _>(FpSafe256Q::from(5u64), 1, 2, &mut rng).unwrap();
+        assert!(feldman_verify::<SafePrime256>(&shares[0], &commitments));
+        // -g^5 has the same square but isn't in the subgroup.
+        commitments[0] = -commitments[0];
+        assert!(!feldman_verify::<SafePrime256>(&shares[0], &commitments));
+    }
+
+    #[test]
+    fn pedersen_shares_verify_in_schnorr_512() {
+        let mut rng = rng_from_seed(38);
+        let secret = Fp256::random(&mut rng);
+        let (shares, commitments) =
+            pedersen_split::<Schnorr512, _>(secret, 2, 3, &mut rng).unwrap();
+        for share in &shares {
+            assert!(pedersen_verify::<Schnorr512>(share, &commitments));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn feldman_shares_verify(seed in any::<u64>(), t in 1usize..5, extra in 0usize..3) {
+            let mut rng = rng_from_seed(seed);
+            let secret = FpSafe256Q::random(&mut rng);
+            let (shares, commitments) =
+                feldman_split::<SafePrime256, _>(secret, t, t + extra, &mut rng).unwrap();
+            prop_assert_eq!(commitments[0], SafePrime256::exp_generator(&secret));
+            for share in &shares {
+                prop_assert!(feldman_verify::<SafePrime256>(share, &commitments));
+                let tampered = (share.0, share.1 + FpSafe256Q::from(1u64));
+                prop_assert!(!feldman_verify::<SafePrime256>(&tampered, &commitments));
+            }
+            prop_assert_eq!(reconstruct(&shares[..t]), Ok(secret));
+        }
+
+        #[test]
+        fn pedersen_shares_verify(seed in any::<u64>(), t in 1usize..5, extra in 0usize..3) {
+            let mut rng = rng_from_seed(seed);
+            let secret = FpSafe256Q::random(&mut rng);
+            let (shares, commitments) =
+                pedersen_split::<SafePrime256, _>(secret, t, t + extra, &mut rng).unwrap();
+            for share in &shares {
+                prop_assert!(pedersen_verify::<SafePrime256>(share, &commitments));
+                let mut tampered = *share;
+                tampered.blinding += FpSafe256Q::from(1u64);
+                prop_assert!(!pedersen_verify::<SafePrime256>(&tampered, &commitments));
+            }
+            let points: Vec<(FpSafe256Q, FpSafe256Q)> = shares.iter().map(|s| (s.x, s.y)).collect();
+            prop_assert_eq!(reconstruct(&points[..t]), Ok(secret));
+        }
+    }
+}
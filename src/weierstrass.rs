@@ -0,0 +1,939 @@
+//! Elliptic curves in short Weierstrass form `y^2 = x^3 + ax + b` over any `Field`. Points come
+//! in three coordinate systems:
+//!
+//! * `Affine` is `(x, y)` plus a flag for the point at infinity. It's the canonical form, but
+//!   adding in it needs an inversion.
+//! * `Jacobian` is `(X, Y, Z)` for `(X / Z^2, Y / Z^3)`, with the fastest doubling.
+//! * `Projective` is `(X, Y, Z)` for `(X / Z, Y / Z)`, using the complete formulas of
+//!   Renes, Costello and Batina (https://eprint.iacr.org/2015/1060), which have no special cases
+//!   on curves of odd order.
+//!
+//! Scalar multiplication uses the non-adjacent form of the scalar, so its time depends on the
+//! scalar. For secret scalars over prime fields, `Projective::scalar_mul_constant_time` does the
+//! same operations whatever the scalar.
+use digits::util::limb_window;
+use field::{batch_inverse, Field, PrimeField};
+use fp_255::Fp255;
+use fp_381::Fp381;
+use fp_bn254::FpBn254;
+use fp_p256::FpP256;
+use fp_secp256k1::FpSecp256k1;
+use fr_bn254::FrBn254;
+use fr_p256::FrP256;
+use fr_secp256k1::FrSecp256k1;
+use num_traits::{Inv, One, Zero};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Neg, Sub};
+
+/// The parameters of a curve, and of the prime order subgroup used as the group.
+pub trait WeierstrassParams: Copy + Debug + Eq {
+    ///Field the coordinates live in.
+    type Base: Field;
+    ///Field of scalars, whose prime is the order r of the subgroup.
+    type Scalar: PrimeField;
+    ///The cofactor h, where the curve has `h * r` points. In limbs, least significant first.
+    const COFACTOR: &'static [u64];
+
+    fn a() -> Self::Base;
+    fn b() -> Self::Base;
+    ///Coordinates of a generator of the subgroup of order r.
+    fn generator() -> (Self::Base, Self::Base);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Affine<C: WeierstrassParams> {
+    x: C::Base,
+    y: C::Base,
+    infinity: bool,
+    curve: PhantomData<C>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Jacobian<C: WeierstrassParams> {
+    x: C::Base,
+    y: C::Base,
+    z: C::Base,
+    curve: PhantomData<C>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Projective<C: WeierstrassParams> {
+    x: C::Base,
+    y: C::Base,
+    z: C::Base,
+    curve: PhantomData<C>,
+}
+
+impl<C: WeierstrassParams> Affine<C> {
+    ///The point `(x, y)`, or None if it isn't on the curve.
+    pub fn new(x: C::Base, y: C::Base) -> Option<Affine<C>> {
+        let p = Affine::new_unchecked(x, y);
+        if p.is_on_curve() {
+            Some(p)
+        } else {
+            None
+        }
+    }
+
+    ///The point `(x, y)` without checking it's on the curve.
+    pub fn new_unchecked(x: C::Base, y: C::Base) -> Affine<C> {
+        Affine {
+            x,
+            y,
+            infinity: false,
+            curve: PhantomData,
+        }
+    }
+
+    ///The point at infinity.
+    pub fn identity() -> Affine<C> {
+        Affine {
+            x: C::Base::zero(),
+            y: C::Base::zero(),
+            infinity: true,
+            curve: PhantomData,
+        }
+    }
+
+    pub fn generator() -> Affine<C> {
+        let (x, y) = C::generator();
+        Affine::new_unchecked(x, y)
+    }
+
+    ///The coordinates, or None for the point at infinity.
+    pub fn coordinates(&self) -> Option<(C::Base, C::Base)> {
+        if self.infinity {
+            None
+        } else {
+            Some((self.x, self.y))
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.infinity
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        self.infinity || self.y.square() == (self.x.square() + C::a()) * self.x + C::b()
+    }
+
+    ///True if the point is on the curve and in the subgroup of order r.
+    pub fn is_in_subgroup(&self) -> bool {
+        self.to_jacobian().is_in_subgroup()
+    }
+
+    pub fn to_jacobian(&self) -> Jacobian<C> {
+        if self.infinity {
+            Jacobian::identity()
+        } else {
+            Jacobian::new(self.x, self.y, C::Base::one())
+        }
+    }
+
+    pub fn to_projective(&self) -> Projective<C> {
+        if self.infinity {
+            Projective::identity()
+        } else {
+            Projective::new(self.x, self.y, C::Base::one())
+        }
+    }
+}
+
+impl<C: WeierstrassParams> Neg for Affine<C> {
+    type Output = Affine<C>;
+    fn neg(self) -> Affine<C> {
+        if self.infinity {
+            self
+        } else {
+            Affine::new_unchecked(self.x, -self.y)
+        }
+    }
+}
+
+impl<C: WeierstrassParams> Jacobian<C> {
+    ///The point `(x / z^2, y / z^3)`, without checking it's on the curve.
+    pub fn new(x: C::Base, y: C::Base, z: C::Base) -> Jacobian<C> {
+        Jacobian {
+            x,
+            y,
+            z,
+            curve: PhantomData,
+        }
+    }
+
+    ///The point at infinity, which is any point with `z = 0`.
+    pub fn identity() -> Jacobian<C> {
+        Jacobian::new(C::Base::one(), C::Base::one(), C::Base::zero())
+    }
+
+    pub fn generator() -> Jacobian<C> {
+        Affine::generator().to_jacobian()
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    ///True if `Y^2 = X^3 + a X Z^4 + b Z^6`.
+    pub fn is_on_curve(&self) -> bool {
+        let z2 = self.z.square();
+        let z4 = z2.square();
+        self.is_identity()
+            || self.y.square() == (self.x.square() + C::a() * z4) * self.x + C::b() * z4 * z2
+    }
+
+    pub fn to_affine(&self) -> Affine<C> {
+        if self.is_identity() {
+            return Affine::identity();
+        }
+        let zinv = self.z.inv();
+        let zinv2 = zinv.square();
+        Affine::new_unchecked(self.x * zinv2, self.y * zinv2 * zinv)
+    }
+
+    ///Double the point with dbl-2007-bl from the Explicit-Formulas Database.
+    pub fn double(&self) -> Jacobian<C> {
+        if self.is_identity() || self.y.is_zero() {
+            return Jacobian::identity();
+        }
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let yyyy = yy.square();
+        let zz = self.z.square();
+        let s = double((self.x + yy).square() - xx - yyyy);
+        let m = triple(xx) + C::a() * zz.square();
+        let x3 = m.square() - double(s);
+        let y3 = m * (s - x3) - double(double(double(yyyy)));
+        let z3 = (self.y + self.z).square() - yy - zz;
+        Jacobian::new(x3, y3, z3)
+    }
+
+    ///Add an affine point with madd-2007-bl from the Explicit-Formulas Database, which saves
+    ///about a third of a full addition since its z is one.
+    pub fn add_affine(&self, other: &Affine<C>) -> Jacobian<C> {
+        let (x2, y2) = match other.coordinates() {
+            Some(c) => c,
+            None => return *self,
+        };
+        if self.is_identity() {
+            return other.to_jacobian();
+        }
+        let z1z1 = self.z.square();
+        let u2 = x2 * z1z1;
+        let s2 = y2 * self.z * z1z1;
+        let h = u2 - self.x;
+        let r = double(s2 - self.y);
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Jacobian::identity()
+            };
+        }
+        let hh = h.square();
+        let i = double(double(hh));
+        let j = h * i;
+        let v = self.x * i;
+        let x3 = r.square() - j - double(v);
+        let y3 = r * (v - x3) - double(self.y * j);
+        let z3 = (self.z + h).square() - z1z1 - hh;
+        Jacobian::new(x3, y3, z3)
+    }
+
+    ///Convert many points to affine with a single field inversion, using `batch_inverse`.
+    pub fn batch_to_affine(points: &[Jacobian<C>]) -> Vec<Affine<C>> {
+        let mut zs: Vec<C::Base> = points
+            .iter()
+            .filter(|p| !p.is_identity())
+            .map(|p| p.z)
+            .collect();
+        batch_inverse(&mut zs);
+        let mut zinvs = zs.into_iter();
+        points
+            .iter()
+            .map(|p| {
+                if p.is_identity() {
+                    return Affine::identity();
+                }
+                let zinv = zinvs.next().unwrap();
+                let zinv2 = zinv.square();
+                Affine::new_unchecked(p.x * zinv2, p.y * zinv2 * zinv)
+            })
+            .collect()
+    }
+}
+
+impl<C: WeierstrassParams> PartialEq for Jacobian<C> {
+    fn eq(&self, other: &Jacobian<C>) -> bool {
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() && other.is_identity();
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        self.x * z2z2 == other.x * z1z1 && self.y * z2z2 * other.z == other.y * z1z1 * self.z
+    }
+}
+
+impl<C: WeierstrassParams> Eq for Jacobian<C> {}
+
+impl<C: WeierstrassParams> Add<&Jacobian<C>> for &Jacobian<C> {
+    type Output = Jacobian<C>;
+    ///add-2007-bl from the Explicit-Formulas Database, falling back to doubling when the points
+    ///are equal.
+    fn add(self, other: &Jacobian<C>) -> Jacobian<C> {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+        let h = u2 - u1;
+        let r = double(s2 - s1);
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Jacobian::identity()
+            };
+        }
+        let i = double(h).square();
+        let j = h * i;
+        let v = u1 * i;
+        let x3 = r.square() - j - double(v);
+        let y3 = r * (v - x3) - double(s1 * j);
+        let z3 = ((self.z + other.z).square() - z1z1 - z2z2) * h;
+        Jacobian::new(x3, y3, z3)
+    }
+}
+
+impl<C: WeierstrassParams> Neg for &Jacobian<C> {
+    type Output = Jacobian<C>;
+    fn neg(self) -> Jacobian<C> {
+        Jacobian::new(self.x, -self.y, self.z)
+    }
+}
+
+impl<C: WeierstrassParams> Projective<C> {
+    ///The point `(x / z, y / z)`, without checking it's on the curve.
+    pub fn new(x: C::Base, y: C::Base, z: C::Base) -> Projective<C> {
+        Projective {
+            x,
+            y,
+            z,
+            curve: PhantomData,
+        }
+    }
+
+    ///The point at infinity, `(0, 1, 0)`.
+    pub fn identity() -> Projective<C> {
+        Projective::new(C::Base::zero(), C::Base::one(), C::Base::zero())
+    }
+
+    pub fn generator() -> Projective<C> {
+        Affine::generator().to_projective()
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    ///True if `Y^2 Z = X^3 + a X Z^2 + b Z^3`. The only point with `Z = 0` on the curve is the
+    ///point at infinity.
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_identity() {
+            return self.x.is_zero() && !self.y.is_zero();
+        }
+        let z2 = self.z.square();
+        self.y.square() * self.z == (self.x.square() + C::a() * z2) * self.x + C::b() * z2 * self.z
+    }
+
+    pub fn to_affine(&self) -> Affine<C> {
+        if self.is_identity() {
+            return Affine::identity();
+        }
+        let zinv = self.z.inv();
+        Affine::new_unchecked(self.x * zinv, self.y * zinv)
+    }
+
+    ///Double the point with algorithm 3 of Renes-Costello-Batina.
+    pub fn double(&self) -> Projective<C> {
+        let (a, b3) = (C::a(), triple(C::b()));
+        let (x, y, z) = (self.x, self.y, self.z);
+        let t0 = x.square();
+        let t1 = y.square();
+        let t2 = z.square();
+        let t3 = double(x * y);
+        let z3 = double(x * z);
+        let x3 = a * z3;
+        let y3 = b3 * t2 + x3;
+        let x3 = t1 - y3;
+        let y3 = x3 * (t1 + y3);
+        let x3 = t3 * x3;
+        let z3 = b3 * z3;
+        let t2 = a * t2;
+        let t3 = a * (t0 - t2) + z3;
+        let t0 = triple(t0) + t2;
+        let y3 = y3 + t0 * t3;
+        let t2 = double(y * z);
+        let x3 = x3 - t2 * t3;
+        let z3 = double(double(t2 * t1));
+        Projective::new(x3, y3, z3)
+    }
+}
+
+impl<C: WeierstrassParams> PartialEq for Projective<C> {
+    fn eq(&self, other: &Projective<C>) -> bool {
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() && other.is_identity();
+        }
+        self.x * other.z == other.x * self.z && self.y * other.z == other.y * self.z
+    }
+}
+
+impl<C: WeierstrassParams> Eq for Projective<C> {}
+
+impl<C: WeierstrassParams> Add<&Projective<C>> for &Projective<C> {
+    type Output = Projective<C>;
+    ///Algorithm 1 of Renes-Costello-Batina, complete for curves of odd order.
+    fn add(self, other: &Projective<C>) -> Projective<C> {
+        let (a, b3) = (C::a(), triple(C::b()));
+        let (x1, y1, z1) = (self.x, self.y, self.z);
+        let (x2, y2, z2) = (other.x, other.y, other.z);
+        let t0 = x1 * x2;
+        let t1 = y1 * y2;
+        let t2 = z1 * z2;
+        let t3 = (x1 + y1) * (x2 + y2) - (t0 + t1);
+        let t4 = (x1 + z1) * (x2 + z2) - (t0 + t2);
+        let t5 = (y1 + z1) * (y2 + z2) - (t1 + t2);
+        let z3 = a * t4 + b3 * t2;
+        let x3 = t1 - z3;
+        let z3 = t1 + z3;
+        let y3 = x3 * z3;
+        let t1 = triple(t0);
+        let t2 = a * t2;
+        let t4 = b3 * t4 + a * (t0 - t2);
+        let t1 = t1 + t2;
+        let y3 = y3 + t1 * t4;
+        let x3 = t3 * x3 - t5 * t4;
+        let z3 = t5 * z3 + t3 * t1;
+        Projective::new(x3, y3, z3)
+    }
+}
+
+impl<C: WeierstrassParams> Neg for &Projective<C> {
+    type Output = Projective<C>;
+    fn neg(self) -> Projective<C> {
+        Projective::new(self.x, -self.y, self.z)
+    }
+}
+
+/// Width of the windows of `Projective::scalar_mul_constant_time`, in bits.
+const CONSTANT_TIME_WINDOW: usize = 4;
+
+impl<C: WeierstrassParams> Projective<C>
+where
+    C::Base: PrimeField,
+{
+    ///`b` if `choice` is true and `a` otherwise, without branching on `choice`.
+    pub fn conditional_select(a: &Projective<C>, b: &Projective<C>, choice: bool) -> Projective<C> {
+        Projective::new(
+            C::Base::conditional_select(&a.x, &b.x, choice),
+            C::Base::conditional_select(&a.y, &b.y, choice),
+            C::Base::conditional_select(&a.z, &b.z, choice),
+        )
+    }
+
+    ///Multiply by a secret scalar, such as a key or a nonce. The scalar is taken 4 bits at a time
+    ///over every bit of the scalar field, and each window costs the same doublings and one
+    ///complete addition of a multiple of the point, read from a table by going through every
+    ///entry and keeping the right one with `conditional_select`. Which operations happen doesn't
+    ///depend on the scalar, with the caveat on `PrimeField`. Like the other projective formulas,
+    ///it needs a curve of odd order.
+    pub fn scalar_mul_constant_time(&self, k: &C::Scalar) -> Projective<C> {
+        // table[d] = d P
+        let mut table = vec![Projective::identity(), *self];
+        for d in 2..1 << CONSTANT_TIME_WINDOW {
+            let next = &table[d - 1] + self;
+            table.push(next);
+        }
+        let limbs = k.to_limbs();
+        let windows = C::Scalar::PRIMEBITS.div_ceil(CONSTANT_TIME_WINDOW);
+        let mut ret = Projective::identity();
+        for i in (0..windows).rev() {
+            for _ in 0..CONSTANT_TIME_WINDOW {
+                ret = ret.double();
+            }
+            let digit = limb_window(&limbs, i * CONSTANT_TIME_WINDOW, CONSTANT_TIME_WINDOW);
+            let mut entry = table[0];
+            for (d, p) in table.iter().enumerate().skip(1) {
+                entry = Projective::conditional_select(&entry, p, d == digit);
+            }
+            ret = ret + entry;
+        }
+        ret
+    }
+
+    ///`to_affine`, but inverting z with `PrimeField::inv_constant_time`, for points that are
+    ///secret such as the output of `scalar_mul_constant_time`.
+    pub fn to_affine_constant_time(&self) -> Affine<C> {
+        let zinv = self.z.inv_constant_time();
+        Affine {
+            x: self.x * zinv,
+            y: self.y * zinv,
+            infinity: self.z.is_zero(),
+            curve: PhantomData,
+        }
+    }
+}
+
+/// The operations Jacobian and projective points share, written in terms of `add` and `double`.
+macro_rules! point_ops { ($($point:ident),*) => { $(
+    impl<C: WeierstrassParams> $point<C> {
+        ///Multiply by a scalar, adding or subtracting the point for each nonzero digit of the
+        ///scalar's non-adjacent form.
+        pub fn scalar_mul(&self, k: &C::Scalar) -> $point<C> {
+            let neg = -self;
+            k.create_naf().iter().rev().fold($point::identity(), |acc, digit| {
+                let acc = acc.double();
+                match *digit {
+                    1 => &acc + self,
+                    -1 => &acc + &neg,
+                    _ => acc,
+                }
+            })
+        }
+
+        ///Multiply by an integer given in limbs, least significant first, which can be larger
+        ///than r. This is double and add, so it's meant for public values like the order and
+        ///the cofactor.
+        pub fn mul_limbs(&self, k: &[u64]) -> $point<C> {
+            let mut ret = $point::identity();
+            for limb in k.iter().rev() {
+                for i in (0..64).rev() {
+                    ret = ret.double();
+                    if (limb >> i) & 1 == 1 {
+                        ret = &ret + self;
+                    }
+                }
+            }
+            ret
+        }
+
+        ///True if the point is on the curve and `[r] P` is the identity.
+        pub fn is_in_subgroup(&self) -> bool {
+            self.is_on_curve() && self.mul_limbs(C::Scalar::PRIME).is_identity()
+        }
+
+        ///Multiply by the cofactor, which maps any point on the curve into the subgroup.
+        pub fn clear_cofactor(&self) -> $point<C> {
+            self.mul_limbs(C::COFACTOR)
+        }
+    }
+
+    impl<C: WeierstrassParams> Sub<&$point<C>> for &$point<C> {
+        type Output = $point<C>;
+        fn sub(self, other: &$point<C>) -> $point<C> {
+            self + &(-other)
+        }
+    }
+
+    impl<C: WeierstrassParams> Add for $point<C> {
+        type Output = $point<C>;
+        #[inline]
+        fn add(self, other: $point<C>) -> $point<C> {
+            &self + &other
+        }
+    }
+
+    impl<C: WeierstrassParams> Sub for $point<C> {
+        type Output = $point<C>;
+        #[inline]
+        fn sub(self, other: $point<C>) -> $point<C> {
+            &self - &other
+        }
+    }
+
+    impl<C: WeierstrassParams> Neg for $point<C> {
+        type Output = $point<C>;
+        #[inline]
+        fn neg(self) -> $point<C> {
+            -&self
+        }
+    }
+)* }}
+
+point_ops! { Jacobian, Projective }
+
+fn double<F: Field>(x: F) -> F {
+    x + x
+}
+
+fn triple<F: Field>(x: F) -> F {
+    x + x + x
+}
+
+/// BN254 (also called alt_bn128), `y^2 = x^3 + 3`, the curve of Ethereum's pairing precompiles.
+/// Its order is prime, so every point is in the group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bn254G1;
+
+impl WeierstrassParams for Bn254G1 {
+    type Base = FpBn254;
+    type Scalar = FrBn254;
+    const COFACTOR: &'static [u64] = &[1];
+
+    fn a() -> FpBn254 {
+        FpBn254::zero()
+    }
+
+    fn b() -> FpBn254 {
+        FpBn254::from(3u64)
+    }
+
+    fn generator() -> (FpBn254, FpBn254) {
+        (FpBn254::from(1u64), FpBn254::from(2u64))
+    }
+}
+
+/// BLS12-381, `y^2 = x^3 + 4`, the G1 group of the pairing-friendly curve used by Zcash and
+/// Ethereum 2.0 BLS signatures. The group has prime order r, the modulus of `Fp255`, and
+/// cofactor `(x - 1)^2 / 3` for the curve parameter `x = -0xd201000000010000`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bls12_381G1;
+
+impl WeierstrassParams for Bls12_381G1 {
+    type Base = Fp381;
+    type Scalar = Fp255;
+    const COFACTOR: &'static [u64] = &[0x8c00aaab0000aaab, 0x396c8c005555e156];
+
+    fn a() -> Fp381 {
+        Fp381::zero()
+    }
+
+    fn b() -> Fp381 {
+        Fp381::from(4u64)
+    }
+
+    fn generator() -> (Fp381, Fp381) {
+        (
+            Fp381::new([
+                0xfb3af00adb22c6bb,
+                0x6c55e83ff97a1aef,
+                0xa14e3a3f171bac58,
+                0xc3688c4f9774b905,
+                0x2695638c4fa9ac0f,
+                0x17f1d3a73197d794,
+            ]),
+            Fp381::new([
+                0x0caa232946c5e7e1,
+                0xd03cc744a2888ae4,
+                0x00db18cb2c04b3ed,
+                0xfcf5e095d5d00af6,
+                0xa09e30ed741d8ae4,
+                0x08b3f481e3aaa0f1,
+            ]),
+        )
+    }
+}
+
+/// secp256k1, `y^2 = x^3 + 7` from SEC 2, the curve of Bitcoin and Ethereum ECDSA signatures.
+/// Its order is prime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Secp256k1;
+
+impl WeierstrassParams for Secp256k1 {
+    type Base = FpSecp256k1;
+    type Scalar = FrSecp256k1;
+    const COFACTOR: &'static [u64] = &[1];
+
+    fn a() -> FpSecp256k1 {
+        FpSecp256k1::zero()
+    }
+
+    fn b() -> FpSecp256k1 {
+        FpSecp256k1::from(7u64)
+    }
+
+    fn generator() -> (FpSecp256k1, FpSecp256k1) {
+        (
+            FpSecp256k1::new([
+                0x59f2815b16f81798,
+                0x029bfcdb2dce28d9,
+                0x55a06295ce870b07,
+                0x79be667ef9dcbbac,
+            ]),
+            FpSecp256k1::new([
+                0x9c47d08ffb10d4b8,
+                0xfd17b448a6855419,
+                0x5da4fbfc0e1108a8,
+                0x483ada7726a3c465,
+            ]),
+        )
+    }
+}
+
+/// NIST P-256 (secp256r1), `y^2 = x^3 - 3x + b` from FIPS 186-4. Its order is prime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct P256;
+
+impl WeierstrassParams for P256 {
+    type Base = FpP256;
+    type Scalar = FrP256;
+    const COFACTOR: &'static [u64] = &[1];
+
+    fn a() -> FpP256 {
+        -FpP256::from(3u64)
+    }
+
+    fn b() -> FpP256 {
+        FpP256::new([
+            0x3bce3c3e27d2604b,
+            0x651d06b0cc53b0f6,
+            0xb3ebbd55769886bc,
+            0x5ac635d8aa3a93e7,
+        ])
+    }
+
+    fn generator() -> (FpP256, FpP256) {
+        (
+            FpP256::new([
+                0xf4a13945d898c296,
+                0x77037d812deb33a0,
+                0xf8bce6e563a440f2,
+                0x6b17d1f2e12c4247,
+            ]),
+            FpP256::new([
+                0xcbb6406837bf51f5,
+                0x2bce33576b315ece,
+                0x8ee7eb4a7c0f9e16,
+                0x4fe342e2fe1a7f9b,
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fp_256::Fp256;
+    use poly::Poly;
+    use proptest::prelude::*;
+    use test_util::{field_from_hex, rng_from_seed};
+
+    /// `y^2 = x^3 - 3x + 8` over `Fp256`. The cubic has no roots, so there are no points of
+    /// order 2 and the complete formulas have no exceptions. The group order isn't known, so
+    /// this only tests the group law with `a != 0`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct MinusThree;
+
+    impl WeierstrassParams for MinusThree {
+        type Base = Fp256;
+        type Scalar = FrBn254;
+        const COFACTOR: &'static [u64] = &[1];
+
+        fn a() -> Fp256 {
+            -Fp256::from(3u64)
+        }
+
+        fn b() -> Fp256 {
+            Fp256::from(8u64)
+        }
+
+        fn generator() -> (Fp256, Fp256) {
+            point_with_x(Fp256::from(0u64))
+        }
+    }
+
+    ///The first point on `MinusThree` with x at least `x`.
+    fn point_with_x(mut x: Fp256) -> (Fp256, Fp256) {
+        loop {
+            let rhs = (x.square() + MinusThree::a()) * x + MinusThree::b();
+            if let Some(y) = rhs.sqrt() {
+                return (x, y);
+            }
+            x += Fp256::one();
+        }
+    }
+
+    fn fr(x: u64) -> FrBn254 {
+        FrBn254::from(x)
+    }
+
+    prop_compose! {
+        fn arb_fr()(seed in any::<u64>()) -> FrBn254 {
+            FrBn254::random(&mut rng_from_seed(seed))
+        }
+    }
+
+    prop_compose! {
+        fn arb_minus_three()(x in any::<u64>(), k in any::<u64>()) -> Jacobian<MinusThree> {
+            let (x, y) = point_with_x(Fp256::from(x));
+            Affine::<MinusThree>::new(x, y).unwrap().to_jacobian().mul_limbs(&[k])
+        }
+    }
+
+    #[test]
+    fn minus_three_has_no_two_torsion() {
+        let cubic = Poly::new(vec![
+            MinusThree::b(),
+            MinusThree::a(),
+            Fp256::zero(),
+            Fp256::one(),
+        ]);
+        assert!(cubic.roots(&mut rng_from_seed(38)).is_empty());
+    }
+
+    #[test]
+    fn bn254_known_points() {
+        let g = Jacobian::<Bn254G1>::generator();
+        let two_g = Affine::<Bn254G1>::new(
+            field_from_hex("030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"),
+            field_from_hex("15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"),
+        )
+        .unwrap();
+        assert_eq!(g.double().to_affine(), two_g);
+        assert_eq!(g.scalar_mul(&fr(2)).to_affine(), two_g);
+        assert_eq!(
+            Projective::<Bn254G1>::generator().double().to_affine(),
+            two_g
+        );
+        assert!(g.is_in_subgroup());
+        assert!(g.mul_limbs(FrBn254::PRIME).is_identity());
+        assert_eq!(g.scalar_mul(&-fr(1)), -g);
+        assert!(g.scalar_mul(&fr(0)).is_identity());
+        assert!(Affine::<Bn254G1>::new(FpBn254::from(1u64), FpBn254::from(3u64)).is_none());
+        assert!(Affine::<Bn254G1>::identity().is_in_subgroup());
+    }
+
+    #[test]
+    fn bls12_381_cofactor_clearing() {
+        let g = Jacobian::<Bls12_381G1>::generator();
+        assert!(g.is_on_curve());
+        assert!(g.is_in_subgroup());
+        // (0, 2) has order 3, and x = 4 gives the first point of order divisible by r.
+        let three_torsion = Affine::<Bls12_381G1>::new(Fp381::zero(), Fp381::from(2u64)).unwrap();
+        assert!(three_torsion.to_jacobian().clear_cofactor().is_identity());
+        let x = Fp381::from(4u64);
+        let y = (x.square() * x + Bls12_381G1::b()).sqrt().unwrap();
+        let p = Affine::<Bls12_381G1>::new(x, y).unwrap();
+        assert!(!p.is_in_subgroup());
+        let cleared = p.to_jacobian().clear_cofactor();
+        assert!(cleared.is_in_subgroup());
+        assert!(!cleared.is_identity());
+    }
+
+    #[test]
+    fn ecdsa_curves_have_prime_order() {
+        let g = Jacobian::<Secp256k1>::generator();
+        let two_g = Affine::<Secp256k1>::new(
+            field_from_hex("c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"),
+            field_from_hex("1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"),
+        )
+        .unwrap();
+        assert_eq!(g.double().to_affine(), two_g);
+        assert!(g.mul_limbs(FrSecp256k1::PRIME).is_identity());
+        let g = Projective::<P256>::generator();
+        assert!(g.is_on_curve());
+        assert!(g.mul_limbs(FrP256::PRIME).is_identity());
+        assert!(!g.mul_limbs(&[2]).is_identity());
+    }
+
+    #[test]
+    fn constant_time_scalar_mul_edge_cases() {
+        let g = Projective::<Secp256k1>::generator();
+        let one = FrSecp256k1::one();
+        for k in [FrSecp256k1::zero(), one, -one, FrSecp256k1::from(16u64)].iter() {
+            assert_eq!(g.scalar_mul_constant_time(k), g.scalar_mul(k));
+            assert_eq!(
+                g.scalar_mul_constant_time(k).to_affine_constant_time(),
+                g.scalar_mul(k).to_affine()
+            );
+        }
+        assert!(g
+            .scalar_mul_constant_time(&FrSecp256k1::zero())
+            .to_affine_constant_time()
+            .is_identity());
+    }
+
+    #[test]
+    fn mixed_addition_and_batch_normalization() {
+        let g = Jacobian::<Bn254G1>::generator();
+        let points: Vec<Jacobian<Bn254G1>> = (0..6u64)
+            .map(|k| g.scalar_mul(&fr(k)))
+            .chain(vec![-g, Jacobian::identity()])
+            .collect();
+        let affine = Jacobian::batch_to_affine(&points);
+        for (p, a) in points.iter().zip(&affine) {
+            assert_eq!(p.to_affine(), *a);
+            for q in &points {
+                assert_eq!(q.add_affine(a), q + p);
+            }
+        }
+    }
+
+    #[test]
+    fn identity_is_neutral() {
+        let g = Projective::<Bn254G1>::generator();
+        let o = Projective::<Bn254G1>::identity();
+        assert!(o.is_on_curve());
+        assert_eq!(g + o, g);
+        assert_eq!(o + g, g);
+        assert!((g - g).is_identity());
+        assert!(o.double().is_identity());
+        assert!(Jacobian::<Bn254G1>::identity().double().is_identity());
+        assert!(Jacobian::<Bn254G1>::identity().to_affine().is_identity());
+    }
+
+    proptest! {
+        #[test]
+        fn coordinates_agree(a in arb_fr(), b in arb_fr()) {
+            let (ja, jb) = (Jacobian::<Bn254G1>::generator().scalar_mul(&a), Jacobian::<Bn254G1>::generator().scalar_mul(&b));
+            let (pa, pb) = (Projective::<Bn254G1>::generator().scalar_mul(&a), Projective::<Bn254G1>::generator().scalar_mul(&b));
+            prop_assert_eq!(ja.to_affine(), pa.to_affine());
+            prop_assert_eq!((ja + jb).to_affine(), (pa + pb).to_affine());
+            prop_assert_eq!(ja.to_affine().to_projective(), pa);
+            prop_assert!(ja.is_on_curve() && pa.is_on_curve());
+        }
+
+        #[test]
+        fn scalar_mul_is_linear(a in arb_fr(), b in arb_fr()) {
+            let g = Jacobian::<Bn254G1>::generator();
+            prop_assert_eq!(g.scalar_mul(&a) + g.scalar_mul(&b), g.scalar_mul(&(a + b)));
+            prop_assert_eq!(g.scalar_mul(&a).scalar_mul(&b), g.scalar_mul(&(a * b)));
+            prop_assert_eq!(g.scalar_mul(&a), g.mul_limbs(&a.to_limbs()));
+            prop_assert!(g.scalar_mul(&a).is_in_subgroup());
+        }
+
+        #[test]
+        fn scalar_mul_constant_time_matches_scalar_mul(a in arb_fr(), seed in any::<u64>()) {
+            let g = Projective::<Bn254G1>::generator();
+            prop_assert_eq!(g.scalar_mul_constant_time(&a), g.scalar_mul(&a));
+            // Bn254 has a = 0, so try P-256 too for the a terms of the complete formulas.
+            let k = FrP256::random(&mut rng_from_seed(seed));
+            let g = Projective::<P256>::generator();
+            let expected = g.scalar_mul(&k).to_affine();
+            prop_assert_eq!(g.scalar_mul_constant_time(&k).to_affine_constant_time(), expected);
+        }
+
+        #[test]
+        fn group_law_with_nonzero_a(p in arb_minus_three(), q in arb_minus_three(), r in arb_minus_three()) {
+            prop_assert!(p.is_on_curve() && (p + q).is_on_curve());
+            prop_assert_eq!(p + q, q + p);
+            prop_assert_eq!((p + q) + r, p + (q + r));
+            prop_assert_eq!(p + p, p.double());
+            prop_assert!((p - p).is_identity());
+            let (pp, qp) = (p.to_affine().to_projective(), q.to_affine().to_projective());
+            prop_assert!((pp + qp).is_on_curve());
+            prop_assert_eq!((pp + qp).to_affine(), (p + q).to_affine());
+            prop_assert_eq!((pp + pp).to_affine(), p.double().to_affine());
+            prop_assert_eq!(pp.double(), pp + pp);
+        }
+    }
+}